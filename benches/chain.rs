@@ -0,0 +1,135 @@
+//! Per-step overhead of the actors loop:
+//! a chain of a source, a sampler and a sink, with the outputs sending one or several samples at a time
+//! and with the source writing either new or recycled buffers
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gmt_dos_actors::{
+    clients::Sampler,
+    io::{Data, Recycler, Write},
+    prelude::*,
+    Update,
+};
+use std::sync::Arc;
+
+#[derive(UID)]
+enum In {}
+#[derive(UID)]
+enum Out {}
+
+/// Source writing `n_data` samples per step, in new or recycled buffers
+struct Source {
+    n_step: usize,
+    n_data: usize,
+    buffers: Option<Recycler<In>>,
+}
+impl Source {
+    fn new(n_step: usize, n_data: usize, recycle: bool) -> Self {
+        Self {
+            n_step,
+            n_data,
+            buffers: recycle.then(Recycler::default),
+        }
+    }
+}
+impl Update for Source {}
+impl Write<In> for Source {
+    fn write(&mut self) -> Option<Arc<Data<In>>> {
+        if self.n_step == 0 {
+            return None;
+        }
+        self.n_step -= 1;
+        let (n_data, value) = (self.n_data, self.n_step as f64);
+        match self.buffers.as_mut() {
+            Some(buffers) => Some(buffers.recycle(|data| {
+                data.clear();
+                data.resize(n_data, value);
+            })),
+            None => Some(Arc::new(Data::new(vec![value; n_data]))),
+        }
+    }
+}
+
+async fn chain(source: Source) {
+    let n_data = source.n_data;
+    let mut source: Initiator<_> = source.into();
+    let mut sampler: Actor<_> = Sampler::<Vec<f64>, In, Out>::default().into();
+    let mut sink: Terminator<_> = Logging::<f64>::default().capacity(n_data).into();
+    source.add_output().build::<In>().into_input(&mut sampler);
+    sampler.add_output().build::<Out>().into_input(&mut sink);
+    Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+        .check()
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+}
+
+async fn signals(n_step: usize, k: usize) {
+    let mut source: Initiator<_> = Signals::new(1, n_step).into();
+    let mut sampler: Actor<_> = Sampler::<Vec<f64>, In, Out>::default().into();
+    let mut sink: Terminator<_> = Logging::<f64>::default().capacity(n_step).into();
+    source
+        .add_output()
+        .batch(k)
+        .build::<In>()
+        .into_input(&mut sampler);
+    sampler
+        .add_output()
+        .batch(k)
+        .build::<Out>()
+        .into_input(&mut sink);
+    Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+        .check()
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+}
+
+fn steps(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("signals_sampler_logging");
+    for n_step in [100, 1000] {
+        group.throughput(Throughput::Elements(n_step as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_step),
+            &n_step,
+            |b, &n_step| b.to_async(&runtime).iter(|| signals(n_step, 1)),
+        );
+    }
+    group.finish();
+}
+
+fn batching(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let n_step = 10000;
+    let mut group = c.benchmark_group("batching");
+    group.throughput(Throughput::Elements(n_step as u64));
+    for k in [1, 8, 64] {
+        group.bench_with_input(BenchmarkId::from_parameter(k), &k, |b, &k| {
+            b.to_async(&runtime).iter(|| signals(n_step, k))
+        });
+    }
+    group.finish();
+}
+
+fn recycling(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let n_step = 1000;
+    let mut group = c.benchmark_group("recycling");
+    group.throughput(Throughput::Elements(n_step as u64));
+    for recycle in [false, true] {
+        group.bench_with_input(
+            BenchmarkId::new(if recycle { "recycled" } else { "new" }, n_step),
+            &recycle,
+            |b, &recycle| {
+                b.to_async(&runtime)
+                    .iter(|| chain(Source::new(n_step, 1024, recycle)))
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, steps, batching, recycling);
+criterion_main!(benches);
@@ -0,0 +1,62 @@
+//! Throughput of a model shaped like the M1 segment loops:
+//! a source feeding the 7 segment actors that all feed the same sink actor with 7 inputs
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use gmt_dos_actors::{clients::Sampler, prelude::*};
+
+enum Command<const ID: u8> {}
+impl<const ID: u8> UniqueIdentifier for Command<ID> {
+    type Data = Vec<f64>;
+}
+enum Rbm<const ID: u8> {}
+impl<const ID: u8> UniqueIdentifier for Rbm<ID> {
+    type Data = Vec<f64>;
+}
+
+/// Adds the segment #`ID` actor between the source and the sink
+fn segment<const ID: u8>(
+    source: &mut Initiator<Signals>,
+    sink: &mut Terminator<Logging<f64>>,
+) -> Box<dyn Task> {
+    let mut segment: Actor<_> = Sampler::<Vec<f64>, Command<ID>, Rbm<ID>>::default().into();
+    source
+        .add_output()
+        .build::<Command<ID>>()
+        .into_input(&mut segment);
+    segment.add_output().build::<Rbm<ID>>().into_input(sink);
+    Box::new(segment)
+}
+
+async fn m1_loops(n_step: usize) {
+    let mut source: Initiator<_> = Signals::new(6, n_step).into();
+    let mut sink: Terminator<_> = Logging::<f64>::default().capacity(42 * n_step).into();
+    let mut actors = vec![
+        segment::<1>(&mut source, &mut sink),
+        segment::<2>(&mut source, &mut sink),
+        segment::<3>(&mut source, &mut sink),
+        segment::<4>(&mut source, &mut sink),
+        segment::<5>(&mut source, &mut sink),
+        segment::<6>(&mut source, &mut sink),
+        segment::<7>(&mut source, &mut sink),
+    ];
+    actors.push(Box::new(source));
+    actors.push(Box::new(sink));
+    Model::new(actors).check().unwrap().run().await.unwrap();
+}
+
+fn many_inputs(c: &mut Criterion) {
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let mut group = c.benchmark_group("m1_loops");
+    for n_step in [100, 1000] {
+        group.throughput(Throughput::Elements(n_step as u64));
+        group.bench_with_input(
+            BenchmarkId::from_parameter(n_step),
+            &n_step,
+            |b, &n_step| b.to_async(&runtime).iter(|| m1_loops(n_step)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, many_inputs);
+criterion_main!(benches);
@@ -3,6 +3,7 @@
 
 A simulation data logger that records the data in the [Apache Arrow] format and
 automatically saves the data into a [Parquet] file (`data.parquet`) at the end of a simulation.
+The data is not saved if the logging actor ends abnormally.
 
 [Apache Arrow]: https://docs.rs/arrow
 [Parquet]: https://docs.rs/parquet
@@ -55,8 +56,9 @@ use arrow::{
     record_batch::{RecordBatch, RecordBatchReader},
 };
 use dos_actors::{
+    actor::{Context, Exit},
     io::{Data, Read, UniqueIdentifier},
-    print_error, Entry, Update, Who,
+    ClientResult, Entry, TryUpdate, Who,
 };
 use parquet::{
     arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, arrow_writer::ArrowWriter},
@@ -408,32 +410,25 @@ impl Display for Arrow {
     }
 }
 
-impl Drop for Arrow {
-    fn drop(&mut self) {
-        log::info!("{self}");
-        match self.drop_option {
-            DropOption::Save(ref filename) => {
-                let file_name = filename
-                    .as_ref()
-                    .cloned()
-                    .unwrap_or_else(|| "data".to_string());
-                match self.file_format {
-                    FileFormat::Parquet => {
-                        if let Err(e) = self.to_parquet(file_name) {
-                            print_error("Arrow error", &e);
-                        }
-                    }
-                    #[cfg(feature = "matio-rs")]
-                    FileFormat::Matlab(_) => {
-                        if let Err(e) = self.to_mat(file_name) {
-                            print_error("Arrow error", &e);
-                        }
-                    }
-                }
-            }
+impl Arrow {
+    /// Saves the data to the file set with [filename](ArrowBuilder::filename)
+    ///
+    /// Nothing is saved if the logger was built with [no_save](ArrowBuilder::no_save)
+    pub fn save(&mut self) -> Result<()> {
+        let file_name = match self.drop_option {
+            DropOption::Save(ref filename) => filename
+                .as_ref()
+                .cloned()
+                .unwrap_or_else(|| "data".to_string()),
             DropOption::NoSave => {
-                log::info!("Dropping Arrow logger without saving.");
+                log::info!("Arrow logger finished without saving.");
+                return Ok(());
             }
+        };
+        match self.file_format {
+            FileFormat::Parquet => self.to_parquet(file_name),
+            #[cfg(feature = "matio-rs")]
+            FileFormat::Matlab(_) => self.to_mat(file_name),
         }
     }
 }
@@ -674,7 +669,21 @@ where
     }
 }
 
-impl Update for Arrow {}
+impl TryUpdate for Arrow {
+    fn try_update(&mut self) -> ClientResult<()> {
+        Ok(())
+    }
+    /// Saves the data if the model ended normally
+    fn on_finish(&mut self, _context: &Context, exit: &Exit) -> ClientResult<()> {
+        log::info!("{self}");
+        if exit.is_normal() {
+            self.save()?;
+        } else {
+            log::warn!("Arrow logger not saved, the actor ended with: {exit}");
+        }
+        Ok(())
+    }
+}
 impl<T, U> Read<U> for Arrow
 where
     T: ArrowNativeType,
@@ -1,6 +1,7 @@
 use dos_actors::{
+    actor::Context,
     io::{Data, UniqueIdentifier, Write},
-    Size, Update, UID,
+    ClientResult, Size, TryUpdate, UID,
 };
 use glob::{glob, GlobError, PatternError};
 use serde::{Deserialize, Serialize};
@@ -43,6 +44,7 @@ pub struct DomeSeeingData {
 type Counter = Box<dyn Iterator<Item = usize> + Send>;
 pub struct DomeSeeing {
     upsampling: usize,
+    take: Option<usize>,
     data: Vec<DomeSeeingData>,
     counter: Counter,
     i: usize,
@@ -79,31 +81,34 @@ impl DomeSeeing {
             });
         }
         data.sort_by(|a, b| a.time_stamp.partial_cmp(&b.time_stamp).unwrap());
-        let mut counter = if let Some(take) = take {
-            Box::new(
-                (0..data.len())
-                    .chain((0..data.len()).skip(1).rev().skip(1))
-                    .cycle()
-                    .take(take),
-            ) as Counter
-        } else {
-            Box::new(
-                (0..data.len())
-                    .chain((0..data.len()).skip(1).rev().skip(1))
-                    .cycle(),
-            ) as Counter
-        };
-        let y2 = bincode::deserialize_from(&File::open(&data[counter.next().unwrap()].file)?)?;
         Ok(Self {
             upsampling,
+            take,
             data,
-            counter,
+            counter: Box::new(std::iter::empty()),
             i: 0,
             y1: Default::default(),
-            y2,
+            y2: Default::default(),
             mapping: OpdMapping::Whole,
         })
     }
+    /// Resets the data sequence and loads the first data file
+    ///
+    /// Invoked by the actor when the simulation starts
+    pub fn start(&mut self) -> Result<()> {
+        let n = self.data.len();
+        let sequence = (0..n).chain((0..n).skip(1).rev().skip(1)).cycle();
+        self.counter = if let Some(take) = self.take {
+            Box::new(sequence.take(take)) as Counter
+        } else {
+            Box::new(sequence) as Counter
+        };
+        self.i = 0;
+        self.y1 = Default::default();
+        // out-of-bounds error if there is no data file
+        self.y2 = self.get(self.counter.next().unwrap_or_default())?;
+        Ok(())
+    }
     pub fn masked(mut self) -> Self {
         self.mapping = OpdMapping::Masked;
         self
@@ -159,13 +164,25 @@ impl Iterator for DomeSeeing {
     }
 }
 
-impl Update for DomeSeeing {}
+impl TryUpdate for DomeSeeing {
+    fn try_update(&mut self) -> ClientResult<()> {
+        Ok(())
+    }
+    fn on_start(&mut self, _context: &Context) -> ClientResult<()> {
+        Ok(self.start()?)
+    }
+}
 
 #[derive(UID)]
 pub enum DomeSeeingOpd {}
 impl Size<DomeSeeingOpd> for DomeSeeing {
     fn len(&self) -> usize {
-        self.y2.mask.len()
+        // the first data file is loaded on start, it is read here if the model is being wired
+        if self.y2.mask.is_empty() {
+            self.get(0).map_or(0, |opd| opd.mask.len())
+        } else {
+            self.y2.mask.len()
+        }
     }
 }
 impl Write<DomeSeeingOpd> for DomeSeeing {
@@ -190,6 +207,7 @@ fn next() {
     const N: usize = 5;
     let mut dome_seeing: DomeSeeing =
         DomeSeeing::new("/fsx/CASES/zen30az000_OS7/", N, Some(n)).unwrap();
+    dome_seeing.start().unwrap();
     let mut i = 0;
     while let Some(opd) = dome_seeing.next() {
         let val = 1e9 * opd[123456];
@@ -0,0 +1,109 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering::Relaxed},
+    Arc,
+};
+
+/**
+Simulation clock of an actor
+
+The clock is given to the client with the [Context](super::Context) of the [on_start](super::Update::on_start) hook
+and the client keeps it to read the simulation time and step while the model runs.
+The actor sets the clock to the current sample of the simulation before reading the inputs into the client,
+the samples are counted at the [Model](crate::model::Model) [sampling frequency](crate::model::Model::sampling_frequency)
+whatever the actor inputs and outputs rates `NI` and `NO`.
+
+```
+use gmt_dos_actors::{actor::{Clock, Context}, io::{Data, Write}, prelude::*, Update};
+use std::{f64::consts::PI, sync::Arc};
+# tokio_test::block_on(async {
+#[derive(UID)]
+enum Wave {};
+#[derive(Default)]
+struct Oscillator {
+    clock: Clock,
+    y: f64,
+}
+impl Update for Oscillator {
+    fn on_start(&mut self, context: &Context) {
+        self.clock = context.clock.clone();
+    }
+    fn update(&mut self) {
+        self.y = (2. * PI * 10. * self.clock.time()).sin();
+    }
+}
+impl Write<Wave> for Oscillator {
+    fn write(&mut self) -> Option<Arc<Data<Wave>>> {
+        (self.clock.time() < 0.1).then(|| Arc::new(Data::new(vec![self.y])))
+    }
+}
+let mut oscillator: Initiator<_, 5> = Oscillator::default().into();
+let logging = Logging::<f64>::default().stamped().into_arcx();
+let mut sink = Terminator::<_, 5>::new(logging.clone());
+oscillator.add_output().build::<Wave>().into_input(&mut sink);
+Model::new(vec![Box::new(oscillator), Box::new(sink)])
+    .sampling_frequency(1e3)
+    .check()?
+    .run()
+    .await?;
+let logging = logging.lock().await;
+assert_eq!(logging.len(), 20);
+assert_eq!(logging.time().unwrap()[..3], [0., 5e-3, 10e-3]);
+assert!(logging
+    .iter()
+    .zip(logging.time().unwrap())
+    .all(|(y, t)| (y - (2. * PI * 10. * t).sin()).abs() < 1e-12));
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+#[derive(Debug, Clone)]
+pub struct Clock {
+    tick: Arc<AtomicUsize>,
+    rate: usize,
+    sampling_frequency: Option<f64>,
+}
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(None, 1)
+    }
+}
+impl Clock {
+    /// Creates the clock of an actor updating its client every `rate` samples
+    pub(crate) fn new(sampling_frequency: Option<f64>, rate: usize) -> Self {
+        Self {
+            tick: Arc::new(AtomicUsize::new(0)),
+            rate: rate.max(1),
+            sampling_frequency,
+        }
+    }
+    /// Sets the current sample of the simulation
+    pub(crate) fn set(&self, tick: usize) {
+        self.tick.store(tick, Relaxed);
+    }
+    /// Returns the current sample of the simulation
+    pub fn tick(&self) -> usize {
+        self.tick.load(Relaxed)
+    }
+    /// Returns the current step of the client, i.e. the number of client updates before the current sample
+    pub fn step(&self) -> usize {
+        self.tick().div_ceil(self.rate)
+    }
+    /// Returns the number of samples between two client updates
+    pub fn rate(&self) -> usize {
+        self.rate
+    }
+    /// Returns the simulation sampling frequency in Hz, if known
+    pub fn sampling_frequency(&self) -> Option<f64> {
+        self.sampling_frequency
+    }
+    /// Returns the client updates frequency in Hz, if known
+    pub fn update_frequency(&self) -> Option<f64> {
+        self.sampling_frequency.map(|fs| fs / self.rate as f64)
+    }
+    /// Returns the current simulation time in seconds
+    ///
+    /// The time is given in samples if the simulation sampling frequency is not known
+    pub fn time(&self) -> f64 {
+        self.tick() as f64 / self.sampling_frequency.unwrap_or(1.)
+    }
+}
@@ -0,0 +1,73 @@
+use super::Clock;
+
+/**
+Simulation context passed to the client [on_start](super::Update::on_start) and [on_finish](super::Update::on_finish) hooks
+
+The context holds the [Clock] of the actor, for the client to read the simulation time while the model runs.
+
+```
+use gmt_dos_actors::{
+    actor::{Context, Exit},
+    io::{Data, Read},
+    prelude::*,
+    Update,
+};
+use std::sync::Arc;
+# tokio_test::block_on(async {
+#[derive(UID)]
+enum Source {};
+#[derive(Default)]
+struct Hooked {
+    started: Option<String>,
+    finished: Option<(usize, bool)>,
+}
+impl Update for Hooked {
+    fn on_start(&mut self, context: &Context) {
+        self.started = context.model.clone();
+    }
+    fn on_finish(&mut self, context: &Context, exit: &Exit) {
+        self.finished = Some((context.step, exit.is_normal()));
+    }
+}
+impl Read<Source> for Hooked {
+    fn read(&mut self, _: Arc<Data<Source>>) {}
+}
+let mut source: Initiator<_> = Signals::new(1, 10).into();
+let hooked = Hooked::default().into_arcx();
+let mut sink = Terminator::<_>::new(hooked.clone());
+source.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sink)])
+    .name("hooks")
+    .sampling_frequency(1e3)
+    .check()?
+    .run()
+    .await?;
+let hooked = hooked.lock().await;
+assert_eq!(hooked.started.as_deref(), Some("hooks"));
+assert_eq!(hooked.finished, Some((10, true)));
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    /// Model name
+    pub model: Option<String>,
+    /// Simulation sampling frequency in Hz, if known
+    pub sampling_frequency: Option<f64>,
+    /// # of client updates
+    pub step: usize,
+    /// Simulation clock of the actor
+    pub clock: Clock,
+}
+impl Context {
+    /// Creates a new context for the given model name and sampling frequency
+    pub fn new(model: Option<String>, sampling_frequency: Option<f64>) -> Self {
+        Self {
+            model,
+            sampling_frequency,
+            step: 0,
+            clock: Clock::default(),
+        }
+    }
+}
@@ -1,60 +1,95 @@
 use super::{
-    plain::{PlainActor, PlainIO, PlainOutput},
-    Task, Update,
+    plain::{self, PlainActor, PlainIO, PlainOutput},
+    ActorProfile, ActorReport, AsyncUpdate, Clock, Context, Exit, Halt, Pacer, Task,
 };
+#[cfg(feature = "checkpoint")]
+use crate::checkpoint::ActorState;
 use crate::{io::*, ActorError, ActorOutputBuilder, Result, Who};
 use async_trait::async_trait;
-use futures::future::join_all;
-use std::{fmt, sync::Arc};
+use futures::FutureExt;
+use std::{
+    any::{type_name, Any},
+    fmt,
+    future::poll_fn,
+    panic::AssertUnwindSafe,
+    sync::Arc,
+    task::Poll,
+    time::{Duration, Instant},
+};
 use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
 
 /// Actor model implementation
 pub struct Actor<C, const NI: usize = 1, const NO: usize = 1>
 where
-    C: Update + Send,
+    C: AsyncUpdate,
 {
-    inputs: Option<Vec<Box<dyn InputObject>>>,
-    pub(crate) outputs: Option<Vec<Box<dyn OutputObject>>>,
+    pub(crate) inputs: Option<Vec<Box<dyn InputObject<C>>>>,
+    pub(crate) outputs: Option<Vec<Box<dyn OutputObject<C>>>>,
     pub(crate) client: Arc<Mutex<C>>,
     name: Option<String>,
+    cancellation: CancellationToken,
+    step: usize,
+    tick: usize,
+    bootstrap_round: usize,
+    profile: Option<ActorProfile>,
+    pacer: Option<Pacer>,
+    context: Context,
+    clusters: Vec<String>,
+    halt: Option<Halt>,
+    halted: Option<Instant>,
+    launched: Option<Instant>,
+    started: bool,
+    retired_client: Option<Arc<Mutex<C>>>,
+    #[cfg(feature = "checkpoint")]
+    codec: Option<crate::checkpoint::ClientCodec<C>>,
+    pub(crate) transitions: Vec<Box<dyn Task>>,
+    pub(crate) invalid_transition: Option<String>,
+    pub(crate) inputs_rate: usize,
+    pub(crate) outputs_rate: usize,
 }
 
 impl<C, const NI: usize, const NO: usize> From<&Actor<C, NI, NO>> for PlainActor
 where
-    C: Update + Send,
+    C: AsyncUpdate,
 {
     fn from(actor: &Actor<C, NI, NO>) -> Self {
         use PlainOutput::*;
         Self {
             client: actor.name.as_ref().unwrap_or(&actor.who()).to_owned(),
-            inputs_rate: NI,
-            outputs_rate: NO,
+            inputs_rate: actor.inputs_rate,
+            outputs_rate: actor.outputs_rate,
             inputs: actor.inputs.as_ref().map(|inputs| {
                 inputs
                     .iter()
-                    .map(|o| PlainIO::new(o.who(), o.get_hash()))
+                    .map(|i| PlainIO::new(i.who(), i.get_hash()).rate(i.rate()))
                     .collect()
             }),
             outputs: actor.outputs.as_ref().map(|outputs| {
                 outputs
                     .iter()
                     .map(|o| {
+                        let io = PlainIO::new(o.who(), o.get_hash())
+                            .capacity(o.capacity())
+                            .multiplex(o.len())
+                            .rate(o.rate());
                         if o.bootstrap() {
-                            Bootstrap(PlainIO::new(o.who(), o.get_hash()))
+                            Bootstrap(io)
                         } else {
-                            Regular(PlainIO::new(o.who(), o.get_hash()))
+                            Regular(io)
                         }
                     })
                     .collect()
             }),
             hash: 0,
+            clusters: actor.clusters.clone(),
         }
     }
 }
 
 impl<C, const NI: usize, const NO: usize> fmt::Display for Actor<C, NI, NO>
 where
-    C: Update + Send,
+    C: AsyncUpdate,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         writeln!(f, "{}:", self.who().to_uppercase())?;
@@ -82,7 +117,7 @@ where
         Ok(())
     }
 }
-impl<C: Update + Send, const NI: usize, const NO: usize> From<C> for Actor<C, NI, NO> {
+impl<C: AsyncUpdate, const NI: usize, const NO: usize> From<C> for Actor<C, NI, NO> {
     /// Creates a new actor for the client
     fn from(client: C) -> Self {
         Actor::new(Arc::new(Mutex::new(client)))
@@ -90,7 +125,7 @@ impl<C: Update + Send, const NI: usize, const NO: usize> From<C> for Actor<C, NI
 }
 impl<C, S, const NI: usize, const NO: usize> From<(C, S)> for Actor<C, NI, NO>
 where
-    C: Update + Send,
+    C: AsyncUpdate,
     S: Into<String>,
 {
     /// Creates a new named actor for the client
@@ -100,7 +135,7 @@ where
         actor
     }
 }
-impl<C: Update + Send, const NI: usize, const NO: usize> Who<C> for Actor<C, NI, NO> {
+impl<C: AsyncUpdate, const NI: usize, const NO: usize> Who<C> for Actor<C, NI, NO> {
     fn who(&self) -> String {
         self.name
             .as_ref()
@@ -111,7 +146,7 @@ impl<C: Update + Send, const NI: usize, const NO: usize> Who<C> for Actor<C, NI,
 
 impl<C, const NI: usize, const NO: usize> Actor<C, NI, NO>
 where
-    C: Update + Send,
+    C: AsyncUpdate,
 {
     /// Creates a new [Actor] for the given [client](crate::clients)
     pub fn new(client: Arc<Mutex<C>>) -> Self {
@@ -120,131 +155,603 @@ where
             outputs: None,
             client,
             name: None,
+            cancellation: CancellationToken::new(),
+            step: 0,
+            tick: 0,
+            bootstrap_round: 0,
+            profile: None,
+            pacer: None,
+            context: Context::default(),
+            clusters: Vec::new(),
+            halt: None,
+            halted: None,
+            launched: None,
+            started: false,
+            retired_client: None,
+            #[cfg(feature = "checkpoint")]
+            codec: None,
+            transitions: Vec::new(),
+            invalid_transition: None,
+            inputs_rate: NI,
+            outputs_rate: NO,
         }
     }
+    /// Sets the actor inputs and outputs rates, overriding `NI` and `NO`
+    pub(crate) fn set_rates(&mut self, inputs_rate: usize, outputs_rate: usize) {
+        self.inputs_rate = inputs_rate;
+        self.outputs_rate = outputs_rate;
+    }
     pub fn name<S: Into<String>>(self, name: S) -> Self {
         Self {
             name: Some(name.into()),
             ..self
         }
     }
-    /// Gathers all the inputs from other [Actor] outputs
-    async fn collect(&mut self) -> Result<&mut Self> {
+    /// Replaces the client, returning the previous one
+    ///
+    /// When the actor task resumes, the previous client [finishes](crate::Update::on_finish) with [Exit::Retired]
+    /// and the new client [starts](crate::Update::on_start)
+    pub fn swap_client(&mut self, client: Arc<Mutex<C>>) -> Arc<Mutex<C>> {
+        let client = std::mem::replace(&mut self.client, client);
+        if std::mem::take(&mut self.started) {
+            self.retired_client = Some(client.clone());
+        }
+        client
+    }
+    /// Saves the client state into the model [checkpoint](crate::checkpoint)s
+    #[cfg(feature = "checkpoint")]
+    pub fn checkpointed(self) -> Self
+    where
+        C: crate::checkpoint::Checkpoint,
+    {
+        Self {
+            codec: Some(crate::checkpoint::ClientCodec::new()),
+            ..self
+        }
+    }
+    /// Updates the client state
+    async fn update(&mut self, client: &mut C) -> Result<()> {
+        let now = self.profile.is_some().then(Instant::now);
+        let result = match client.update_now() {
+            Some(result) => result,
+            None => AsyncUpdate::update(client).await,
+        };
+        if let (Some(profile), Some(now)) = (self.profile.as_mut(), now) {
+            profile.update(now.elapsed());
+        }
+        result.map_err(|e| ActorError::client::<C>(e).named(self.name.as_deref()))?;
+        self.step += 1;
+        Ok(())
+    }
+    /// Receives the inputs from other [Actor] outputs that are due at the actor sample #`t`
+    ///
+    /// All the inputs are received before the client is locked
+    async fn collect(&mut self, t: usize) -> Result<&mut Self> {
+        let now = self.profile.is_some().then(Instant::now);
         if let Some(inputs) = &mut self.inputs {
-            let futures: Vec<_> = inputs.iter_mut().map(|input| input.recv()).collect();
-            join_all(futures)
-                .await
-                .into_iter()
-                .collect::<Result<Vec<_>>>()?;
+            poll_fn(|cx| -> Poll<Result<()>> {
+                let mut received = true;
+                for input in inputs
+                    .iter_mut()
+                    .filter(|input| t.is_multiple_of(input.rate()))
+                {
+                    received &= input.poll_recv(cx)?.is_ready();
+                }
+                if received {
+                    Poll::Ready(Ok(()))
+                } else {
+                    Poll::Pending
+                }
+            })
+            .await?;
+        }
+        if let (Some(profile), Some(now)) = (self.profile.as_mut(), now) {
+            profile.inputs += now.elapsed();
+        }
+        Ok(self)
+    }
+    /// Reads the inputs received at the actor sample #`t` into the client
+    async fn read(&mut self, t: usize, client: &mut C) -> Result<&mut Self> {
+        if let Some(inputs) = &mut self.inputs {
+            for input in inputs
+                .iter_mut()
+                .filter(|input| t.is_multiple_of(input.rate()))
+            {
+                let result = match input.read_now(client) {
+                    Some(result) => result,
+                    None => input.read(client).await,
+                };
+                result.map_err(|e| e.named(self.name.as_deref()))?;
+            }
         }
         Ok(self)
     }
-    /// Sends the outputs to other [Actor] inputs
-    async fn distribute(&mut self) -> Result<&mut Self> {
+    /// Writes the client data to the outputs that are due at the actor sample #`t`
+    async fn write(&mut self, t: usize, client: &mut C) -> Result<&mut Self> {
         if let Some(outputs) = &mut self.outputs {
-            let futures: Vec<_> = outputs.iter_mut().map(|output| output.send()).collect();
-            join_all(futures)
-                .await
-                .into_iter()
-                .collect::<Result<Vec<_>>>()?;
+            Self::write_outputs(client, outputs, |output| {
+                (t + 1).is_multiple_of(output.rate())
+            })
+            .await
+            .map_err(|e| e.named(self.name.as_deref()))?;
         }
         Ok(self)
     }
-    /// Invokes outputs senders
-    async fn bootstrap(&mut self) -> Result<&mut Self> {
+    /// Sends the outputs that are due at the actor sample #`t` to other [Actor] inputs
+    ///
+    /// The batched outputs are sent at once if `flush` is set
+    async fn distribute(&mut self, t: usize, flush: bool) -> Result<&mut Self> {
+        let now = self.profile.is_some().then(Instant::now);
         if let Some(outputs) = &mut self.outputs {
-            async fn inner(outputs: &mut Vec<Box<dyn OutputObject>>) -> Result<()> {
-                let futures: Vec<_> = outputs
-                    .iter_mut()
-                    .filter(|output| output.bootstrap())
-                    .map(|output| output.send())
-                    .collect();
-                join_all(futures)
-                    .await
-                    .into_iter()
-                    .collect::<Result<Vec<_>>>()?;
-                Ok(())
+            Self::send_written(
+                outputs,
+                |output| (t + 1).is_multiple_of(output.rate()),
+                flush,
+            )
+            .await?;
+            #[cfg(feature = "recorder")]
+            outputs
+                .iter()
+                .filter(|output| (t + 1).is_multiple_of(output.rate()))
+                .for_each(|output| output.record(t, false));
+        }
+        if let (Some(profile), Some(now)) = (self.profile.as_mut(), now) {
+            profile.outputs += now.elapsed();
+        }
+        Ok(self)
+    }
+    /// Writes the client data to the selected outputs
+    async fn write_outputs(
+        client: &mut C,
+        outputs: &mut [Box<dyn OutputObject<C>>],
+        selected: impl Fn(&dyn OutputObject<C>) -> bool,
+    ) -> Result<()> {
+        for output in outputs
+            .iter_mut()
+            .filter(|output| selected(output.as_ref()))
+        {
+            match output.write_now(client) {
+                Some(result) => result?,
+                None => output.write(client).await?,
             }
-            if NO >= NI {
-                inner(outputs).await?;
+        }
+        Ok(())
+    }
+    /// Writes the client data to the selected outputs and sends it to other [Actor] inputs
+    ///
+    /// The client is locked once to write the data of all the outputs and released before sending
+    async fn send(
+        client: &Mutex<C>,
+        outputs: &mut [Box<dyn OutputObject<C>>],
+        selected: impl Fn(&dyn OutputObject<C>) -> bool,
+        flush: bool,
+    ) -> Result<()> {
+        Self::write_outputs(&mut *client.lock().await, outputs, &selected).await?;
+        Self::send_written(outputs, selected, flush).await
+    }
+    /// Sends the data written to the selected outputs to other [Actor] inputs
+    async fn send_written(
+        outputs: &mut [Box<dyn OutputObject<C>>],
+        selected: impl Fn(&dyn OutputObject<C>) -> bool,
+        flush: bool,
+    ) -> Result<()> {
+        poll_fn(|cx| {
+            let mut sent = true;
+            for output in outputs
+                .iter_mut()
+                .filter(|output| selected(output.as_ref()))
+            {
+                sent &= output.poll_send(cx, flush)?.is_ready();
+            }
+            if sent {
+                Poll::Ready(Ok(()))
             } else {
-                for _ in 0..NI / NO {
-                    inner(outputs).await?;
+                Poll::Pending
+            }
+        })
+        .await
+    }
+    /// Sends the data left in the [batch](crate::AddOuput::batch)ed outputs to other [Actor] inputs
+    async fn flush(&mut self) -> Result<()> {
+        match &mut self.outputs {
+            Some(outputs) => Self::send_written(outputs, |_| true, true).await,
+            None => Ok(()),
+        }
+    }
+    /// Performs the actor sample #`t`
+    ///
+    /// The inputs are collected if `t%rate=0` and the outputs are distributed if `(t+1)%rate=0`,
+    /// `rate` being the sampling rate of each input or output.
+    /// The client is updated if `t%NI=0`, or if `t%NO=0` for an [Initiator](super::Initiator),
+    /// `NI` and `NO` being the actor inputs and outputs rates.
+    /// The client is locked once, after the inputs have been received and before the outputs are sent,
+    /// and it is not locked at all if there is nothing to do at `t`.
+    async fn sample(&mut self, flush: bool) -> Result<()> {
+        let t = self.tick;
+        self.context.clock.set(t);
+        if !self.is_due(t) {
+            self.tick += 1;
+            return Ok(());
+        }
+        self.collect(t).await?;
+        {
+            let client = self.client.clone();
+            let mut client = client.lock().await;
+            self.read(t, &mut client).await?;
+            if self.updates_at(t) {
+                self.update(&mut client).await?;
+            }
+            self.write(t, &mut client).await?;
+        }
+        self.distribute(t, flush).await?;
+        self.tick += 1;
+        Ok(())
+    }
+    /// Checks if any input, output or client update is due at the actor sample #`t`
+    fn is_due(&self, t: usize) -> bool {
+        self.updates_at(t)
+            || self
+                .inputs
+                .iter()
+                .flatten()
+                .any(|input| t.is_multiple_of(input.rate()))
+            || self
+                .outputs
+                .iter()
+                .flatten()
+                .any(|output| (t + 1).is_multiple_of(output.rate()))
+    }
+    /// Checks if the client is updated at the actor sample #`t`
+    fn updates_at(&self, t: usize) -> bool {
+        let (ni, no) = (self.inputs_rate, self.outputs_rate);
+        (ni > 0 && t.is_multiple_of(ni)) || (ni == 0 && t.is_multiple_of(no))
+    }
+    /// Returns the number of samples between two client updates
+    fn updates_rate(&self) -> usize {
+        if self.inputs_rate > 0 {
+            self.inputs_rate
+        } else {
+            self.outputs_rate
+        }
+    }
+    /// Performs the actor sample #`t`, paced with the wall clock
+    ///
+    /// An actor without inputs waits for the sample to be due, or for the actor to be cancelled,
+    /// and the completion of each client update is recorded
+    async fn paced_sample(&mut self) -> Result<()> {
+        let t = self.tick;
+        if self.inputs_rate == 0 {
+            if let Some(pacer) = self.pacer.as_ref() {
+                tokio::select! {
+                    _ = self.cancellation.cancelled() => return Err(ActorError::Cancelled),
+                    _ = pacer.wait(t) => (),
                 }
             }
         }
+        self.sample(false).await?;
+        if self.updates_at(t) {
+            if let Some(pacer) = self.pacer.as_mut() {
+                pacer.record(t);
+            }
+        }
+        Ok(())
+    }
+    /// Returns the number of samples sent by a bootstrapped output
+    fn bootstrap_samples(inputs_rate: usize, output: &dyn OutputObject<C>) -> usize {
+        if output.bootstrap() && !output.bootstrapped() {
+            plain::bootstrap_samples(inputs_rate, output.rate())
+        } else {
+            0
+        }
+    }
+    /// Returns the number of bootstrapping rounds
+    fn bootstrap_rounds(&self) -> usize {
+        let inputs_rate = self.inputs_rate;
+        self.outputs.as_ref().map_or(0, |outputs| {
+            outputs
+                .iter()
+                .map(|output| Self::bootstrap_samples(inputs_rate, output.as_ref()))
+                .max()
+                .unwrap_or_default()
+        })
+    }
+    /// Invokes the senders of the bootstrapped outputs with samples left to send
+    async fn bootstrap_once(&mut self) -> Result<&mut Self> {
+        let (inputs_rate, round) = (self.inputs_rate, self.bootstrap_round);
+        if let Some(outputs) = &mut self.outputs {
+            Self::send(
+                &self.client,
+                outputs,
+                |output| Self::bootstrap_samples(inputs_rate, output) > round,
+                true,
+            )
+            .await
+            .map_err(|e| e.named(self.name.as_deref()))?;
+            #[cfg(feature = "recorder")]
+            outputs
+                .iter()
+                .filter(|output| Self::bootstrap_samples(inputs_rate, output.as_ref()) > round)
+                .for_each(|output| output.record(0, true));
+        }
+        self.bootstrap_round += 1;
+        Ok(self)
+    }
+    /// Invokes outputs senders
+    ///
+    /// A bootstrapped output sends as many samples as needed to cover the inputs sampling period
+    async fn bootstrap(&mut self) -> Result<&mut Self> {
+        while self.bootstrap_round < self.bootstrap_rounds() {
+            self.bootstrap_once().await?;
+        }
         Ok(self)
     }
+    /// Returns [ActorError::Cancelled] if the actor [CancellationToken] is cancelled
+    ///
+    /// The token is checked between samples so a sample is never left half done
+    fn check_cancelled(&self) -> Result<()> {
+        if self.cancellation.is_cancelled() {
+            Err(ActorError::Cancelled)
+        } else {
+            Ok(())
+        }
+    }
+    /// Checks if the actor loop halts at the current sample
+    fn halts(&self) -> bool {
+        self.halt
+            .as_ref()
+            .is_some_and(|halt| halt.reached(self.tick))
+    }
+    /// Halts the actor loop after sending the data left in the batched outputs
+    async fn halt(&mut self) -> Result<()> {
+        self.flush().await?;
+        self.halted = Some(Instant::now());
+        Ok(())
+    }
+    /// Checks if the inputs due at the actor sample #`t` can be collected without waiting
+    fn inputs_ready(&self, t: usize) -> bool {
+        self.inputs.as_ref().is_none_or(|inputs| {
+            inputs
+                .iter()
+                .filter(|input| t.is_multiple_of(input.rate()))
+                .all(|input| input.is_ready())
+        })
+    }
+    /// Checks if the outputs due at the actor sample #`t` can be distributed without waiting
+    fn outputs_ready(&self, t: usize) -> bool {
+        self.outputs.as_ref().is_none_or(|outputs| {
+            outputs
+                .iter()
+                .filter(|output| (t + 1).is_multiple_of(output.rate()))
+                .all(|output| output.is_ready())
+        })
+    }
+    /// Checks if the bootstrapped outputs with samples left to send can be distributed without waiting
+    fn bootstrap_ready(&self) -> bool {
+        let inputs_rate = self.inputs_rate;
+        self.outputs.as_ref().is_none_or(|outputs| {
+            outputs
+                .iter()
+                .filter(|output| {
+                    Self::bootstrap_samples(inputs_rate, output.as_ref()) > self.bootstrap_round
+                })
+                .all(|output| output.is_ready())
+        })
+    }
 }
 
 #[async_trait]
 impl<C, const NI: usize, const NO: usize> Task for Actor<C, NI, NO>
 where
-    C: 'static + Update + Send,
+    C: 'static + AsyncUpdate,
 {
     /// Run the actor loop in a dedicated thread
-    fn spawn(mut self) -> tokio::task::JoinHandle<()> {
-        tokio::spawn(async move {
-            self.task().await;
-        })
+    fn spawn(mut self) -> tokio::task::JoinHandle<ActorReport> {
+        tokio::spawn(async move { self.task().await })
     }
     /// Run the actor loop
-    async fn task(&mut self) {
-        match self.bootstrap().await {
-            Err(e) => crate::print_error(format!("{} bootstrapping failed", Who::who(self)), &e),
-            Ok(_) => {
-                if let Err(e) = self.async_run().await {
-                    crate::print_error(format!("{} loop ended", Who::who(self)), &e);
-                }
+    ///
+    /// The loop is interrupted at the first sample boundary after the actor [CancellationToken] is cancelled,
+    /// the data left in the batched outputs is sent and the client [finishes](crate::Update::on_finish).
+    /// A halted actor resumes its loop where it left off.
+    async fn task(&mut self) -> ActorReport {
+        let now = *self.launched.get_or_insert_with(Instant::now);
+        if let (Some(halted), Some(pacer)) = (self.halted.take(), self.pacer.as_mut()) {
+            pacer.delay(halted.elapsed());
+        }
+        let task = async {
+            if let Err(e) = self.start().await {
+                return Exit::Start(e);
+            }
+            if let Err(e) = self.bootstrap().await {
+                return Exit::Bootstrap(e);
+            }
+            match self.async_run().await {
+                Ok(_) => Exit::Loop(ActorError::NoData),
+                Err(e) => Exit::Loop(e),
+            }
+        };
+        let exit = AssertUnwindSafe(task)
+            .catch_unwind()
+            .await
+            .unwrap_or_else(Exit::panic);
+        if self.halted.is_some() {
+            return self.report(exit, now.elapsed());
+        }
+        let exit = self.finish(exit).await;
+        match &exit {
+            Exit::Start(e) => crate::print_error(format!("{} starting failed", Who::who(self)), e),
+            Exit::Bootstrap(e) => {
+                crate::print_error(format!("{} bootstrapping failed", Who::who(self)), e)
+            }
+            Exit::Loop(e) => crate::print_error(format!("{} loop ended", Who::who(self)), e),
+            Exit::Panic(msg) => log::error!("{} panicked: {}", Who::who(self), msg),
+            Exit::Finish(e) => {
+                crate::print_error(format!("{} finishing failed", Who::who(self)), e)
+            }
+            Exit::Retired => log::info!("{} retired", Who::who(self)),
+        }
+        self.report(exit, now.elapsed())
+    }
+    fn report(&self, exit: Exit, elapsed: Duration) -> ActorReport {
+        ActorReport {
+            name: Who::who(self),
+            exit,
+            step: self.step,
+            elapsed,
+            profile: self.profile.clone(),
+            pacing: self.pacer.as_ref().map(|pacer| pacer.stats.clone()),
+        }
+    }
+    fn set_context(&mut self, context: Context) {
+        let clock = Clock::new(context.sampling_frequency, self.updates_rate());
+        self.context = Context { clock, ..context };
+    }
+    /// Invokes the client [on_start](crate::Update::on_start) hook, once per client
+    ///
+    /// A [swap](Actor::swap_client)ped out client [finishes](crate::Update::on_finish) first
+    async fn start(&mut self) -> Result<()> {
+        let context = Context {
+            step: self.step,
+            ..self.context.clone()
+        };
+        if let Some(client) = self.retired_client.take() {
+            let result =
+                AsyncUpdate::on_finish(&mut *client.lock().await, &context, &Exit::Retired)
+                    .await
+                    .map_err(|e| ActorError::client::<C>(e).named(self.name.as_deref()));
+            if let Err(e) = result {
+                crate::print_error(format!("{} finishing failed", Who::who(self)), &e);
+            }
+        }
+        if self.started {
+            return Ok(());
+        }
+        AsyncUpdate::on_start(&mut *self.client.lock().await, &context)
+            .await
+            .map_err(|e| ActorError::client::<C>(e).named(self.name.as_deref()))?;
+        self.started = true;
+        Ok(())
+    }
+    async fn finish(&mut self, exit: Exit) -> Exit {
+        if matches!(&exit, Exit::Loop(_)) {
+            if let Err(e) = self.flush().await {
+                crate::print_error(format!("{} flushing failed", Who::who(self)), &e);
             }
         }
+        let context = Context {
+            step: self.step,
+            ..self.context.clone()
+        };
+        let result = AsyncUpdate::on_finish(&mut *self.client.lock().await, &context, &exit)
+            .await
+            .map_err(|e| ActorError::client::<C>(e).named(self.name.as_deref()));
+        match result {
+            Err(e) if exit.is_normal() => Exit::Finish(e),
+            Err(e) => {
+                crate::print_error(format!("{} finishing failed", Who::who(self)), &e);
+                exit
+            }
+            Ok(_) => exit,
+        }
+    }
+    async fn step_bootstrap(&mut self) -> Result<()> {
+        if self.bootstrap_rounds() > 0 {
+            self.bootstrap_once().await?;
+        }
+        Ok(())
+    }
+    /// Advances the actor by one sample
+    ///
+    /// The actor waits until the inputs and outputs of the sample are ready
+    async fn step(&mut self) -> Result<bool> {
+        if self.bootstrap_round < self.bootstrap_rounds() {
+            if !self.bootstrap_ready() {
+                return Ok(false);
+            }
+            self.bootstrap_once().await?;
+            return Ok(true);
+        }
+        if self.inputs_rate == 0 && self.outputs_rate == 0 {
+            return Err(ActorError::NoData);
+        }
+        let t = self.tick;
+        if !self.inputs_ready(t) || !self.outputs_ready(t) {
+            return Ok(false);
+        }
+        self.sample(true).await?;
+        Ok(true)
+    }
+    fn set_cancellation_token(&mut self, token: CancellationToken) {
+        self.cancellation = token;
+    }
+    fn enable_profiling(&mut self) {
+        self.profile = Some(ActorProfile::default());
+    }
+    fn enable_pacing(&mut self, start: tokio::time::Instant, period: Duration) {
+        self.pacer = Some(Pacer::new(start, period));
+    }
+    fn enclose(&mut self, cluster: &str) {
+        self.clusters.insert(0, cluster.to_string());
+    }
+    #[cfg(feature = "recorder")]
+    fn set_recorder(&mut self, recorder: &crate::recorder::Recorder) {
+        self.outputs
+            .iter_mut()
+            .flatten()
+            .for_each(|output| output.set_recorder(recorder.clone()));
+    }
+    fn take_transitions(&mut self) -> Vec<Box<dyn Task>> {
+        std::mem::take(&mut self.transitions)
     }
 
     /// Starts the actor infinite loop
     async fn async_run(&mut self) -> Result<()> {
-        match (self.inputs.as_ref(), self.outputs.as_ref()) {
-            (Some(_), Some(_)) => {
-                if NO >= NI {
-                    // Decimation
-                    loop {
-                        for _ in 0..NO / NI {
-                            self.collect().await?.client.lock().await.update();
-                        }
-                        self.distribute().await?;
-                    }
-                } else {
-                    // Upsampling
-                    loop {
-                        self.collect().await?.client.lock().await.update();
-                        for _ in 0..NI / NO {
-                            self.distribute().await?;
-                        }
-                    }
-                }
+        if self.inputs_rate == 0 && self.outputs_rate == 0 {
+            return Ok(());
+        }
+        if self.pacer.is_some() {
+            while !self.halts() {
+                self.check_cancelled()?;
+                self.paced_sample().await?;
             }
-            (None, Some(_)) => loop {
-                // Initiator
-                self.client.lock().await.update();
-                self.distribute().await?;
-            },
-            (Some(_), None) => loop {
-                // Terminator
-                self.collect().await?.client.lock().await.update();
-            },
-            (None, None) => Ok(()),
+            return self.halt().await;
         }
+        while !self.halts() {
+            self.check_cancelled()?;
+            self.sample(false).await?;
+        }
+        self.halt().await
     }
     fn check_inputs(&self) -> Result<()> {
+        if let Some(transition) = &self.invalid_transition {
+            return Err(ActorError::InvalidRateTransition(transition.clone()));
+        }
         match self.inputs {
-            Some(_) if NI == 0 => Err(ActorError::SomeInputsZeroRate(Who::who(self))),
-            None if NI > 0 => Err(ActorError::NoInputsPositiveRate(Who::who(self))),
+            Some(_) if self.inputs_rate == 0 => Err(ActorError::SomeInputsZeroRate(Who::who(self))),
+            None if self.inputs_rate > 0 => Err(ActorError::NoInputsPositiveRate(Who::who(self))),
             _ => Ok(()),
         }
     }
     fn check_outputs(&self) -> Result<()> {
         match self.outputs {
-            Some(_) if NO == 0 => Err(ActorError::SomeOutputsZeroRate(Who::who(self))),
-            None if NO > 0 => Err(ActorError::NoOutputsPositiveRate(Who::who(self))),
+            Some(_) if self.outputs_rate == 0 => {
+                Err(ActorError::SomeOutputsZeroRate(Who::who(self)))
+            }
+            Some(ref outputs) if outputs.iter().any(|output| output.rate() == 0) => {
+                Err(ActorError::SomeOutputsZeroRate(Who::who(self)))
+            }
+            Some(ref outputs) => match outputs.iter().find(|output| {
+                output.bootstrap() && !plain::bootstrap_divisible(self.inputs_rate, output.rate())
+            }) {
+                Some(output) => Err(ActorError::NonDivisibleBootstrap(
+                    Who::who(self),
+                    output.who(),
+                    output.rate(),
+                    self.inputs_rate,
+                )),
+                None => Ok(()),
+            },
+            None if self.outputs_rate > 0 => Err(ActorError::NoOutputsPositiveRate(Who::who(self))),
             _ => Ok(()),
         }
     }
@@ -252,7 +759,9 @@ where
         self.inputs.as_ref().map_or(0, |i| i.len())
     }
     fn n_outputs(&self) -> usize {
-        self.outputs.as_ref().map_or(0, |o| o.iter().map(|o| o.len()).sum())
+        self.outputs
+            .as_ref()
+            .map_or(0, |o| o.iter().map(|o| o.len()).sum())
     }
     fn inputs_hashes(&self) -> Vec<u64> {
         self.inputs.as_ref().map_or(Vec::new(), |inputs| {
@@ -270,38 +779,474 @@ where
     fn as_plain(&self) -> PlainActor {
         self.into()
     }
+    fn set_halt(&mut self, halt: Halt) {
+        self.tick = halt.tick();
+        self.halt = Some(halt);
+    }
+    fn halted(&self) -> Option<usize> {
+        self.halted.map(|_| self.tick)
+    }
+    fn as_any_mut(&mut self) -> Option<&mut dyn Any> {
+        Some(self)
+    }
+    fn take_output(&mut self, uid: &str) -> Option<Box<dyn Any + Send>> {
+        let outputs = self.outputs.as_mut()?;
+        let k = outputs.iter().position(|output| output.who() == uid)?;
+        Some(outputs.remove(k).into_any())
+    }
+    fn take_input(&mut self, uid: &str) -> Option<Box<dyn Any + Send>> {
+        let inputs = self.inputs.as_mut()?;
+        let k = inputs.iter().position(|input| input.who() == uid)?;
+        Some(inputs.remove(k).into_any())
+    }
+    fn splice_input(
+        &mut self,
+        hash: u64,
+        mut input: Box<dyn Any + Send>,
+    ) -> Option<Box<dyn Any + Send>> {
+        for this in self.inputs.iter_mut().flatten() {
+            if this.get_hash() == hash {
+                input = this.splice(input)?;
+            }
+        }
+        Some(input)
+    }
+    fn splice_output(
+        &mut self,
+        hash: u64,
+        mut output: Box<dyn Any + Send>,
+    ) -> Option<Box<dyn Any + Send>> {
+        for this in self.outputs.iter_mut().flatten() {
+            if this.get_hash() == hash {
+                output = this.splice(output)?;
+            }
+        }
+        Some(output)
+    }
+    fn prune_outputs(&mut self, hashes: &[u64]) {
+        if let Some(outputs) = self.outputs.as_mut() {
+            outputs.retain_mut(|output| !hashes.contains(&output.get_hash()) || output.prune() > 0);
+        }
+    }
+    #[cfg(feature = "checkpoint")]
+    async fn save_state(&mut self) -> crate::checkpoint::Result<Vec<ActorState>> {
+        let client = match &self.codec {
+            Some(codec) => Some((codec.encode)(&*self.client.lock().await)?),
+            None => None,
+        };
+        let pending = self
+            .inputs
+            .iter_mut()
+            .flatten()
+            .map(|input| Ok((input.get_hash(), input.save()?)))
+            .collect::<crate::checkpoint::Result<Vec<_>>>()?;
+        Ok(vec![ActorState {
+            actor: (&self.as_plain()).into(),
+            step: self.step,
+            bootstrap_round: self.bootstrap_round,
+            client,
+            pending,
+        }])
+    }
+    /// Restores the actor from the state with the same actor name, inputs and outputs
+    #[cfg(feature = "checkpoint")]
+    async fn restore_state(
+        &mut self,
+        states: &mut Vec<ActorState>,
+    ) -> crate::checkpoint::Result<()> {
+        use crate::checkpoint::{ActorKey, CheckpointError};
+        let key = ActorKey::from(&self.as_plain());
+        let k = states
+            .iter()
+            .position(|state| state.actor == key)
+            .ok_or_else(|| CheckpointError::MissingActor(key.name.clone()))?;
+        let state = states.remove(k);
+        match (&self.codec, &state.client) {
+            (Some(codec), Some(bytes)) => (codec.decode)(&mut *self.client.lock().await, bytes)?,
+            (None, None) => (),
+            _ => return Err(CheckpointError::Client(key.name)),
+        }
+        let mut pending = state.pending;
+        if pending.len() != self.n_inputs() {
+            return Err(CheckpointError::PendingInputs {
+                actor: key.name,
+                inputs: self.n_inputs(),
+                pending: pending.len(),
+            });
+        }
+        for input in self.inputs.iter_mut().flatten() {
+            let k = pending
+                .iter()
+                .position(|(hash, _)| *hash == input.get_hash())
+                .ok_or_else(|| CheckpointError::MissingInput {
+                    actor: key.name.clone(),
+                    input: input.who(),
+                })?;
+            input.restore(&pending.remove(k).1)?;
+        }
+        self.step = state.step;
+        self.bootstrap_round = state.bootstrap_round;
+        Ok(())
+    }
 }
 
 impl<C, const NI: usize, const NO: usize> Actor<C, NI, NO>
 where
-    C: 'static + Update + Send,
+    C: 'static + AsyncUpdate,
 {
     /// Adds a new output
     pub fn add_output(&mut self) -> (&mut Actor<C, NI, NO>, ActorOutputBuilder) {
         (self, ActorOutputBuilder::new(1))
     }
     /// Adds an output to an actor
-    pub(crate) fn add_input<T, U>(&mut self, rx: flume::Receiver<Arc<Data<U>>>, hash: u64)
-    where
-        C: Read<U>,
+    ///
+    /// With the `checkpoint` feature, the `codec` of the output data is used to save the samples in flight
+    pub(crate) fn add_input<T, U>(
+        &mut self,
+        rx: flume::Receiver<Batch<U>>,
+        hash: u64,
+        rate: usize,
+        #[cfg(feature = "checkpoint")] codec: Option<crate::io::Codec<T>>,
+    ) where
+        C: AsyncRead<U>,
         T: 'static + Send + Sync,
         U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
     {
-        let input: Input<C, T, U, NI> = Input::new(rx, self.client.clone(), hash);
+        let input: Input<T, U> = Input::new(rx, hash, rate);
+        #[cfg(feature = "checkpoint")]
+        let input = input.codec(codec);
         if let Some(ref mut inputs) = self.inputs {
             inputs.push(Box::new(input));
         } else {
             self.inputs = Some(vec![Box::new(input)]);
         }
     }
+    /// Takes over the output `U` of another actor, with the channels to the inputs it is connected to
+    ///
+    /// The samples the output has already bootstrapped are not sent again
+    pub fn take_output<U>(&mut self, actor: &mut dyn Task) -> Result<&mut Self>
+    where
+        C: AsyncWrite<U>,
+        U: 'static + Send + Sync + UniqueIdentifier,
+        Assoc<U>: Send + Sync,
+    {
+        let uid = type_name::<U>();
+        let output = actor
+            .take_output(uid)
+            .and_then(|output| output.downcast::<Output<Assoc<U>, U>>().ok())
+            .ok_or_else(|| ActorError::MissingOutput(actor.as_plain().client, uid.to_string()))?;
+        self.outputs.get_or_insert_with(Vec::new).push(output);
+        Ok(self)
+    }
+    /// Takes over the input `U` of another actor, with the channel from the output it is connected to
+    pub fn take_input<U>(&mut self, actor: &mut dyn Task) -> Result<&mut Self>
+    where
+        C: AsyncRead<U>,
+        U: 'static + Send + Sync + UniqueIdentifier,
+        Assoc<U>: Send + Sync,
+    {
+        let uid = type_name::<U>();
+        let input = actor
+            .take_input(uid)
+            .and_then(|input| input.downcast::<Input<Assoc<U>, U>>().ok())
+            .ok_or_else(|| ActorError::MissingInput(actor.as_plain().client, uid.to_string()))?;
+        self.inputs.get_or_insert_with(Vec::new).push(input);
+        Ok(self)
+    }
 }
 /*
 impl<C, const NI: usize, const NO: usize> Drop for Actor<C, NI, NO>
 where
-    C: Update + Send,
+    C: AsyncUpdate,
 {
     fn drop(&mut self) {
         log::info!("{} dropped!", self.who());
     }
 }
 */
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        actor::{Context, Exit},
+        io::{AsyncWrite, Data, Read},
+        model::ModelError,
+        prelude::*,
+        ActorError, ClientResult, TryUpdate, Update,
+    };
+    use async_trait::async_trait;
+    use std::{sync::Arc, time::Duration};
+    use tokio_util::sync::CancellationToken;
+
+    #[derive(UID)]
+    enum Gated {}
+
+    /// Source writing the values sent through a channel
+    struct Gate(flume::Receiver<f64>);
+    impl Update for Gate {}
+    #[async_trait]
+    impl AsyncWrite<Gated> for Gate {
+        async fn write(&mut self) -> ClientResult<Option<Arc<Data<Gated>>>> {
+            Ok(self
+                .0
+                .recv_async()
+                .await
+                .ok()
+                .map(|x| Arc::new(Data::new(vec![x]))))
+        }
+    }
+
+    /// Sink signaling each value it reads through a channel
+    struct Notifying {
+        data: Vec<f64>,
+        tx: flume::Sender<()>,
+    }
+    impl Update for Notifying {}
+    impl Read<Gated> for Notifying {
+        fn read(&mut self, data: Arc<Data<Gated>>) {
+            self.data.extend(data.iter());
+            self.tx.send(()).unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn client_unlocked_while_waiting_for_inputs() {
+        let (tx, rx) = flume::unbounded();
+        let (read_tx, read_rx) = flume::unbounded();
+        let mut source: Initiator<_> = Gate(rx).into();
+        let notifying = Notifying {
+            data: vec![],
+            tx: read_tx,
+        }
+        .into_arcx();
+        let mut sink = Terminator::<_>::new(notifying.clone());
+        source.add_output().build::<Gated>().into_input(&mut sink);
+        let model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run();
+        tx.send(1.).unwrap();
+        let timeout = Duration::from_secs(5);
+        tokio::time::timeout(timeout, read_rx.recv_async())
+            .await
+            .expect("the sink did not read the sample")
+            .unwrap();
+        // the source waits for the next value, so the sink waits for its input
+        let client = tokio::time::timeout(timeout, notifying.lock())
+            .await
+            .expect("the sink client is locked while waiting for its input");
+        assert_eq!(client.data, vec![1.]);
+        drop(client);
+        drop(tx);
+        model.await.unwrap();
+    }
+
+    /// Source stopping the model after writing `n` values
+    struct Stopping {
+        n: usize,
+        token: flume::Receiver<CancellationToken>,
+        exit: Option<String>,
+    }
+    impl Update for Stopping {
+        fn on_finish(&mut self, _context: &Context, exit: &Exit) {
+            self.exit = Some(exit.to_string());
+        }
+    }
+    #[async_trait]
+    impl AsyncWrite<Gated> for Stopping {
+        async fn write(&mut self) -> ClientResult<Option<Arc<Data<Gated>>>> {
+            self.n -= 1;
+            if self.n == 0 {
+                self.token.recv_async().await.unwrap().cancel();
+            }
+            Ok(Some(Arc::new(Data::new(vec![self.n as f64]))))
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_actor_finishes_its_sample() {
+        let (tx, rx) = flume::bounded(1);
+        let stopping = Stopping {
+            n: 3,
+            token: rx,
+            exit: None,
+        }
+        .into_arcx();
+        let mut source = Initiator::<_>::new(stopping.clone());
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        source
+            .add_output()
+            .batch(10)
+            .build::<Gated>()
+            .into_input(&mut sink);
+        let model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run();
+        tx.send(model.cancellation_token()).unwrap();
+        let model = tokio::time::timeout(Duration::from_secs(5), model.wait())
+            .await
+            .expect("the cancelled model did not complete")
+            .unwrap();
+        // the batched samples are flushed but the sink stops after its first sample
+        assert_eq!(**logging.lock().await, vec![2.]);
+        assert!(stopping.lock().await.exit.is_some());
+        assert!(model
+            .reports()
+            .iter()
+            .all(|report| matches!(report.exit, Exit::Loop(ActorError::Cancelled))));
+    }
+
+    #[tokio::test]
+    async fn batched_outputs_sent_every_step() {
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        source
+            .add_output()
+            .batch(4)
+            .build::<Gated>()
+            .into_input(&mut sink);
+        let mut model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .stepping()
+            .await;
+        model.step(3).await.unwrap();
+        assert_eq!(logging.lock().await.len(), 3);
+        model.complete().await.unwrap();
+        assert_eq!(logging.lock().await.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn client_not_locked_between_updates() {
+        let mut source: Initiator<_, 5> = Signals::new(1, 20).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_, 5>::new(logging.clone());
+        source.add_output().build::<Gated>().into_input(&mut sink);
+        let mut model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .stepping()
+            .await;
+        model.step(5).await.unwrap();
+        assert_eq!(logging.lock().await.len(), 1);
+        // nothing is due to the sink until its next input at sample #5
+        let client = logging.lock().await;
+        tokio::time::timeout(Duration::from_secs(5), model.step(4))
+            .await
+            .expect("the sink client is locked between its updates")
+            .unwrap();
+        drop(client);
+        model.complete().await.unwrap();
+        assert_eq!(logging.lock().await.len(), 20);
+    }
+
+    #[tokio::test]
+    async fn three_to_two_rates() {
+        let mut source: Initiator<_, 3> = Signals::new(1, 6)
+            .signals(Signal::Ramp { a: 1., b: 0. })
+            .into();
+        let mut sampler: Actor<_, 3, 2> = Sampler::<Vec<f64>, Gated>::default().into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_, 2>::new(logging.clone());
+        source
+            .add_output()
+            .build::<Gated>()
+            .into_input(&mut sampler);
+        sampler.add_output().build::<Gated>().into_input(&mut sink);
+        Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        // the sampler reads a sample every 3 ticks and writes every 2 ticks,
+        // over the 18 ticks of the 6 samples of the source
+        assert_eq!(
+            logging.lock().await.to_vec(),
+            vec![0., 1., 1., 2., 3., 3., 4., 5., 5.]
+        );
+    }
+
+    #[test]
+    fn non_divisible_bootstrap() {
+        let mut source: Initiator<_, 3> = Signals::new(1, 6).into();
+        let mut sampler: Actor<_, 3, 2> = Sampler::<Vec<f64>, Gated>::default().into();
+        let mut sink = Terminator::<_, 2>::new(Logging::<f64>::default().into_arcx());
+        source
+            .add_output()
+            .build::<Gated>()
+            .into_input(&mut sampler);
+        sampler
+            .add_output()
+            .bootstrap()
+            .build::<Gated>()
+            .into_input(&mut sink);
+        let model = Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)]);
+        assert!(matches!(
+            model.check(),
+            Err(ModelError::ActorIO(ActorError::NonDivisibleBootstrap(
+                _,
+                _,
+                2,
+                3
+            )))
+        ));
+    }
+
+    /// Sink failing after `n` updates
+    struct Countdown(usize);
+    impl TryUpdate for Countdown {
+        fn try_update(&mut self) -> ClientResult<()> {
+            self.0 = self.0.checked_sub(1).ok_or("countdown is over")?;
+            Ok(())
+        }
+    }
+    impl Read<Gated> for Countdown {
+        fn read(&mut self, _: Arc<Data<Gated>>) {}
+    }
+
+    #[tokio::test]
+    async fn client_errors_named_after_actors() {
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        let mut first: Terminator<_> = (Countdown(2), "first").into();
+        let mut second: Terminator<_> = (Countdown(3), "second").into();
+        let mut unnamed: Terminator<_> = Countdown(4).into();
+        source
+            .add_output()
+            .multiplex(3)
+            .build::<Gated>()
+            .into_input(&mut first)
+            .into_input(&mut second)
+            .into_input(&mut unnamed);
+        let model = Model::new(vec![
+            Box::new(source),
+            Box::new(first),
+            Box::new(second),
+            Box::new(unnamed),
+        ])
+        .check()
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+        let mut names: Vec<_> = model
+            .abnormal_exits()
+            .map(|report| match &report.exit {
+                Exit::Loop(ActorError::Client { name, .. }) => name.clone(),
+                exit => panic!("expected a client error, found {exit}"),
+            })
+            .collect();
+        names.sort();
+        assert_eq!(
+            names,
+            vec![
+                "first".to_string(),
+                std::any::type_name::<Countdown>().to_string(),
+                "second".to_string()
+            ]
+        );
+    }
+}
@@ -0,0 +1,33 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering::SeqCst},
+    Arc,
+};
+
+/// Step boundary at which the actor loops of a [Running](crate::model::Running) model halt
+///
+/// An actor publishes its sample index before checking it against the boundary
+/// and the model sets the boundary before reading the actors sample index,
+/// so either the actor halts at the boundary or the model sees the actor past it.
+#[derive(Debug, Clone)]
+pub struct Halt {
+    boundary: Arc<AtomicUsize>,
+    tick: Arc<AtomicUsize>,
+}
+impl Halt {
+    /// Creates the handle of an actor starting at sample #`tick`
+    pub(crate) fn new(boundary: Arc<AtomicUsize>, tick: usize) -> Self {
+        Self {
+            boundary,
+            tick: Arc::new(AtomicUsize::new(tick)),
+        }
+    }
+    /// Returns the last sample index published by the actor
+    pub(crate) fn tick(&self) -> usize {
+        self.tick.load(SeqCst)
+    }
+    /// Publishes the actor sample index `tick` and checks if the boundary is reached
+    pub(crate) fn reached(&self, tick: usize) -> bool {
+        self.tick.store(tick, SeqCst);
+        tick >= self.boundary.load(SeqCst)
+    }
+}
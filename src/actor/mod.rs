@@ -25,6 +25,89 @@ use gmt_dos_actors::prelude::*;
 let source: Initiator<_> = (Signals::new(1, 100), "My Signal").into();
 ```
 
+The inputs and outputs of an [Actor] are sampled at the actor inputs and outputs rates.
+An output can be given its own rate with [rate](crate::AddOuput::rate) and
+an input connected with [into_input_at](crate::IntoInputs::into_input_at) is sampled at the rate of the output,
+whatever the inputs rate of the actor, as long as it is a multiple of it.
+Here the `controller` reads the fast `LoadCells` input every sample and the slow `Commands` input every 10 samples:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::prelude::*;
+#[derive(UID)]
+enum LoadCells {};
+#[derive(UID)]
+enum Commands {};
+let mut load_cells: Initiator<_> = Signals::new(1, 100).into();
+let mut commands: Initiator<_, 10> = Signals::new(1, 10).into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut controller = Terminator::<_>::new(logging.clone());
+load_cells.add_output().build::<LoadCells>().into_input(&mut controller);
+commands.add_output().build::<Commands>().into_input_at(&mut controller);
+Model::new(vec![Box::new(load_cells), Box::new(commands), Box::new(controller)])
+    .check()?
+    .run()
+    .await?;
+assert_eq!(logging.lock().await.len(), 110);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+The rates do not have to be multiple of each other, an actor with inputs and outputs rates of 2 and 5
+converts a 1kHz input into a 400Hz output, for a 2kHz simulation sampling rate:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::prelude::*;
+#[derive(UID)]
+enum Source {};
+let mut source: Initiator<_, 2> = Signals::new(1, 100).into();
+let mut sampler: Actor<_, 2, 5> = Sampler::<Vec<f64>, Source>::default().into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_, 5>::new(logging.clone());
+source.add_output().build::<Source>().into_input(&mut sampler);
+sampler.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+    .check()?
+    .run()
+    .await?;
+assert_eq!(logging.lock().await.len(), 40);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+A [bootstrap](crate::AddOuput::bootstrap)ped output sends enough samples to cover the inputs sampling period,
+i.e. the ratio of the inputs rate to the output rate, or a single sample if the output is slower than the inputs.
+The [model check](crate::model::Model::check) fails if the rates of a bootstrapped output and of the actor inputs
+are not multiple of one another.
+
+An output can send its data several consecutive samples at a time with [batch](crate::AddOuput::batch),
+cutting down the channels synchronization overhead of cheap clients.
+The clients still read, update and write one sample at a time, so the results are the same as without batching.
+An input waits for the whole batch, so batching is meant for the feed-forward parts of a model:
+an output within a feedback loop must not be batched
+and the parallel paths between two actors must be batched alike or be [unbounded](crate::AddOuput::unbounded).
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{clients::Integrator, model::ModelError, prelude::*};
+#[derive(UID)]
+enum Source {};
+async fn integrate(k: usize) -> Result<Vec<f64>, ModelError> {
+    let mut source: Initiator<_> = Signals::new(1, 1000).signals(Signal::Constant(1.)).into();
+    let mut integrator: Actor<_> = Integrator::<Source>::new(1).gain(0.5).into();
+    let logging = Logging::<f64>::default().into_arcx();
+    let mut sink = Terminator::<_>::new(logging.clone());
+    source.add_output().batch(k).build::<Source>().into_input(&mut integrator);
+    integrator.add_output().batch(k).build::<Source>().into_input(&mut sink);
+    Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+        .check()?
+        .run()
+        .await?;
+    let data = logging.lock().await.to_vec();
+    Ok(data)
+}
+assert_eq!(integrate(1).await?, integrate(64).await?);
+# Ok::<(), ModelError>(())
+# });
+```
+
 If the client must remain available for later use, it must be wrapped inside a [Mutex] within an [Arc].
 This can be easily done with the [into_arcx] method of the [ArcMutex] trait that has a blanket implementation for all type that implements the [Update] trait.
 ```
@@ -45,16 +128,187 @@ let sink = Terminator::<_>::new(logging.clone());
 
 use std::fmt::Display;
 
-use crate::Result;
+use crate::{ClientResult, Result};
 use async_trait::async_trait;
 pub(crate) mod core;
 pub use self::core::Actor;
 pub(crate) mod plain;
 pub use plain::PlainActor;
+mod report;
+pub use report::{ActorReport, Exit};
+mod profile;
+pub use profile::ActorProfile;
+mod pacing;
+pub use pacing::ActorPacing;
+mod halt;
+pub use halt::Halt;
+mod offload;
+pub use offload::Offload;
+mod clock;
+pub use clock::Clock;
+mod context;
+pub use context::Context;
+pub(crate) use pacing::Pacer;
 
 /// Actor client state update interface
 pub trait Update {
     fn update(&mut self) {}
+    /// Invoked by the [Actor] before the first sample of the simulation
+    fn on_start(&mut self, _context: &Context) {}
+    /// Invoked by the [Actor] once its task has ended for the given reason
+    fn on_finish(&mut self, _context: &Context, _exit: &Exit) {}
+}
+/**
+Actor client fallible state update interface
+
+Every [Update] client is also a [TryUpdate] client.
+An error ends the actor loop with [ActorError::Client](crate::ActorError::Client),
+the error is reported in the [Completed](crate::model::Completed) model.
+
+```
+use gmt_dos_actors::{
+    actor::Exit,
+    io::{Data, Read},
+    prelude::*,
+    ActorError, ClientResult, TryUpdate,
+};
+use std::sync::Arc;
+# tokio_test::block_on(async {
+#[derive(UID)]
+enum Source {};
+struct Countdown(usize);
+impl TryUpdate for Countdown {
+    fn try_update(&mut self) -> ClientResult<()> {
+        self.0 = self.0.checked_sub(1).ok_or("countdown is over")?;
+        Ok(())
+    }
+}
+impl Read<Source> for Countdown {
+    fn read(&mut self, _: Arc<Data<Source>>) {}
+}
+let mut source: Initiator<_> = Signals::new(1, 10).into();
+let mut sink: Terminator<_> = Countdown(3).into();
+source.add_output().build::<Source>().into_input(&mut sink);
+let model = Model::new(vec![Box::new(source), Box::new(sink)])
+    .check()?
+    .run()
+    .wait()
+    .await?;
+let report = model.abnormal_exits().next().unwrap();
+assert!(matches!(report.exit, Exit::Loop(ActorError::Client { .. })));
+assert_eq!(report.step, 3);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+pub trait TryUpdate {
+    fn try_update(&mut self) -> ClientResult<()>;
+    /// Invoked by the [Actor] before the first sample of the simulation
+    fn on_start(&mut self, _context: &Context) -> ClientResult<()> {
+        Ok(())
+    }
+    /// Invoked by the [Actor] once its task has ended for the given reason
+    ///
+    /// A client saving its data, to a file for example, does it here rather than in [Drop]:
+    /// the data is saved when the model completes, possibly only if the [Exit] is normal,
+    /// and a failure is reported as an [Exit::Finish]
+    fn on_finish(&mut self, _context: &Context, _exit: &Exit) -> ClientResult<()> {
+        Ok(())
+    }
+}
+impl<T: Update> TryUpdate for T {
+    fn try_update(&mut self) -> ClientResult<()> {
+        self.update();
+        Ok(())
+    }
+    fn on_start(&mut self, context: &Context) -> ClientResult<()> {
+        Update::on_start(self, context);
+        Ok(())
+    }
+    fn on_finish(&mut self, context: &Context, exit: &Exit) -> ClientResult<()> {
+        Update::on_finish(self, context, exit);
+        Ok(())
+    }
+}
+/**
+Actor client asynchronous state update interface
+
+The [Actor] awaits the update of its client, every [TryUpdate] client is also an [AsyncUpdate] client.
+
+```
+use gmt_dos_actors::{
+    io::{AsyncRead, Data},
+    prelude::*,
+    AsyncUpdate, ClientResult,
+};
+use async_trait::async_trait;
+use std::{sync::Arc, time::Duration};
+# tokio_test::block_on(async {
+#[derive(UID)]
+enum Source {};
+#[derive(Default)]
+struct Delay(Vec<f64>);
+#[async_trait]
+impl AsyncRead<Source> for Delay {
+    async fn read(&mut self, data: Arc<Data<Source>>) -> ClientResult<()> {
+        self.0.extend_from_slice(&data);
+        Ok(())
+    }
+}
+#[async_trait]
+impl AsyncUpdate for Delay {
+    async fn update(&mut self) -> ClientResult<()> {
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        Ok(())
+    }
+}
+let mut source: Initiator<_> = Signals::new(1, 5).signals(Signal::Constant(1.)).into();
+let delay = Delay::default().into_arcx();
+let mut sink = Terminator::<_>::new(delay.clone());
+source.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sink)])
+    .check()?
+    .run()
+    .await?;
+assert_eq!(delay.lock().await.0, vec![1f64; 5]);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+#[async_trait]
+pub trait AsyncUpdate: Send {
+    async fn update(&mut self) -> ClientResult<()> {
+        Ok(())
+    }
+    /// Updates the client state without awaiting if the client is synchronous
+    ///
+    /// Returns `None` if the client must be awaited, which is the default
+    fn update_now(&mut self) -> Option<ClientResult<()>> {
+        None
+    }
+    /// Invoked by the [Actor] before the first sample of the simulation
+    async fn on_start(&mut self, _context: &Context) -> ClientResult<()> {
+        Ok(())
+    }
+    /// Invoked by the [Actor] once its task has ended for the given reason
+    async fn on_finish(&mut self, _context: &Context, _exit: &Exit) -> ClientResult<()> {
+        Ok(())
+    }
+}
+#[async_trait]
+impl<T: TryUpdate + Send> AsyncUpdate for T {
+    async fn update(&mut self) -> ClientResult<()> {
+        self.try_update()
+    }
+    fn update_now(&mut self) -> Option<ClientResult<()>> {
+        Some(self.try_update())
+    }
+    async fn on_start(&mut self, context: &Context) -> ClientResult<()> {
+        TryUpdate::on_start(self, context)
+    }
+    async fn on_finish(&mut self, context: &Context, exit: &Exit) -> ClientResult<()> {
+        TryUpdate::on_finish(self, context, exit)
+    }
 }
 
 /// Type alias for an actor without outputs
@@ -64,6 +318,10 @@ pub type Initiator<C, const NO: usize = 1> = Actor<C, 0, NO>;
 /// Type alias for an actor without inputs and outputs
 pub type NoNo<C> = Actor<C, 0, 0>;
 
+/// Interface of the tasks run by a [Model](crate::model::Model)
+///
+/// Only the methods without a default implementation are needed for a task other than an [Actor],
+/// the other methods are the model runtime features an [Actor] supports and the default ones do nothing
 #[async_trait]
 pub trait Task: Display + Send {
     /// Runs the [Actor] infinite loop
@@ -72,7 +330,7 @@ pub trait Task: Display + Send {
     /// end of a channel is dropped
     async fn async_run(&mut self) -> Result<()>;
     /// Run the actor loop in a dedicated thread
-    fn spawn(self) -> tokio::task::JoinHandle<()>;
+    fn spawn(self) -> tokio::task::JoinHandle<ActorReport>;
     /**
     Validates the inputs
 
@@ -88,10 +346,242 @@ pub trait Task: Display + Send {
     */
     fn check_outputs(&self) -> Result<()>;
     /// Run the actor loop
-    async fn task(&mut self);
+    ///
+    /// Returns a report on how and why the actor task ended
+    async fn task(&mut self) -> ActorReport;
     fn n_inputs(&self) -> usize;
     fn n_outputs(&self) -> usize;
     fn inputs_hashes(&self) -> Vec<u64>;
     fn outputs_hashes(&self) -> Vec<u64>;
     fn as_plain(&self) -> PlainActor;
+    /// Returns the actor task report
+    fn report(&self, exit: Exit, elapsed: std::time::Duration) -> ActorReport {
+        ActorReport {
+            name: self.as_plain().client,
+            exit,
+            step: 0,
+            elapsed,
+            profile: None,
+            pacing: None,
+        }
+    }
+    /// Sets the simulation context passed to the client hooks
+    ///
+    /// The actor gets its own [Clock] at the context sampling frequency
+    fn set_context(&mut self, _context: Context) {}
+    /// Invokes the client [on_start](Update::on_start) hook
+    async fn start(&mut self) -> Result<()> {
+        Ok(())
+    }
+    /// Invokes the client [on_finish](Update::on_finish) hook
+    ///
+    /// Returns the reason for the actor to end its task, updated if the hook fails after a normal ending
+    async fn finish(&mut self, exit: Exit) -> Exit {
+        exit
+    }
+    /// Sends the bootstrapped outputs before the actor is [step](Task::step)ped
+    async fn step_bootstrap(&mut self) -> Result<()> {
+        Ok(())
+    }
+    /**
+    Advances the actor by one sample of the simulation sampling rate
+
+    Returns `false` if the actor is waiting either for its inputs or for its outputs to be read,
+    a task that cannot be stepped never progresses
+    */
+    async fn step(&mut self) -> Result<bool> {
+        Ok(false)
+    }
+    /// Sets the token that stops the actor loop when cancelled
+    fn set_cancellation_token(&mut self, _token: tokio_util::sync::CancellationToken) {}
+    /// Records the time spent in the client update and waiting on the inputs and outputs
+    fn enable_profiling(&mut self) {}
+    /// Paces the actor samples with the wall clock, one sample every `period` from `start`
+    ///
+    /// Only the actors without inputs wait for their samples to be due
+    fn enable_pacing(&mut self, _start: tokio::time::Instant, _period: std::time::Duration) {}
+    /// Encloses the actor within a [SubModel](crate::model::SubModel) cluster
+    fn enclose(&mut self, _cluster: &str) {}
+    /// Records the data sent by the actor outputs
+    #[cfg(feature = "recorder")]
+    fn set_recorder(&mut self, _recorder: &crate::recorder::Recorder) {}
+    /// Takes the rate transition actors inserted in front of the actor inputs
+    ///
+    /// The [Model](crate::model::Model) runs them alongside the actor
+    fn take_transitions(&mut self) -> Vec<Box<dyn Task>> {
+        Vec::new()
+    }
+    /// Takes the actors the task is made of, `None` if the task is a single actor
+    ///
+    /// The [Model](crate::model::Model) runs them instead of the task, see [SubModel](crate::model::SubModel)
+    fn take_tasks(&mut self) -> Option<Vec<Box<dyn Task>>> {
+        None
+    }
+    /// Sets the step boundary at which the actor loop halts
+    ///
+    /// The actor starts at the sample published by the [Halt] handle
+    fn set_halt(&mut self, _halt: Halt) {}
+    /// Returns the sample at which the actor loop halted, if it did
+    fn halted(&self) -> Option<usize> {
+        None
+    }
+    /// Returns the task as [Any](std::any::Any) to be downcasted to its concrete type
+    fn as_any_mut(&mut self) -> Option<&mut dyn std::any::Any> {
+        None
+    }
+    /// Removes the output with the identifier type name `uid`, to be taken over by another actor
+    fn take_output(&mut self, _uid: &str) -> Option<Box<dyn std::any::Any + Send>> {
+        None
+    }
+    /// Removes the input with the identifier type name `uid`, to be taken over by another actor
+    fn take_input(&mut self, _uid: &str) -> Option<Box<dyn std::any::Any + Send>> {
+        None
+    }
+    /// Replaces the input with the given `hash` by the [taken](Task::take_input) `input` of a [Port](crate::model::Port)
+    ///
+    /// The input is given back if the task has no such input
+    #[doc(hidden)]
+    fn splice_input(
+        &mut self,
+        _hash: u64,
+        input: Box<dyn std::any::Any + Send>,
+    ) -> Option<Box<dyn std::any::Any + Send>> {
+        Some(input)
+    }
+    /// Replaces the channels of the output with the given `hash` by the channels of the [taken](Task::take_output) `output` of a [Port](crate::model::Port)
+    ///
+    /// The output is given back if the task has no such output
+    #[doc(hidden)]
+    fn splice_output(
+        &mut self,
+        _hash: u64,
+        output: Box<dyn std::any::Any + Send>,
+    ) -> Option<Box<dyn std::any::Any + Send>> {
+        Some(output)
+    }
+    /// Removes the channels of the outputs with the given hashes whose input has been dropped
+    fn prune_outputs(&mut self, _hashes: &[u64]) {}
+    /// Returns the [checkpoint](crate::checkpoint) of the actors the task is made of
+    ///
+    /// The actors must be halted at a step boundary
+    #[cfg(feature = "checkpoint")]
+    async fn save_state(
+        &mut self,
+    ) -> crate::checkpoint::Result<Vec<crate::checkpoint::ActorState>> {
+        Ok(Vec::new())
+    }
+    /// Restores the actors the task is made of, taking their state out of `states`
+    #[cfg(feature = "checkpoint")]
+    async fn restore_state(
+        &mut self,
+        _states: &mut Vec<crate::checkpoint::ActorState>,
+    ) -> crate::checkpoint::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns the actors a task is made of, each followed by its rate transition actors
+pub(crate) fn flatten(mut task: Box<dyn Task>) -> Vec<Box<dyn Task>> {
+    match task.take_tasks() {
+        Some(tasks) => tasks.into_iter().flat_map(flatten).collect(),
+        None => {
+            let transitions = task.take_transitions();
+            std::iter::once(task).chain(transitions).collect()
+        }
+    }
+}
+
+/// [Task] that can be downcasted to its concrete type
+pub(crate) trait AnyTask: Task {
+    fn as_task(&self) -> &dyn Task;
+    fn as_task_mut(&mut self) -> &mut dyn Task;
+    #[cfg_attr(not(feature = "registry"), allow(dead_code))]
+    fn into_task(self: Box<Self>) -> Box<dyn Task>;
+}
+impl<T: Task + 'static> AnyTask for T {
+    fn as_task(&self) -> &dyn Task {
+        self
+    }
+    fn as_task_mut(&mut self) -> &mut dyn Task {
+        self
+    }
+    fn into_task(self: Box<Self>) -> Box<dyn Task> {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Client recording the calls to its hooks
+    #[derive(Default)]
+    struct Hooks(Vec<String>);
+    impl Update for Hooks {
+        fn on_start(&mut self, _context: &Context) {
+            self.0.push("start".into());
+        }
+        fn update(&mut self) {
+            self.0.push("update".into());
+        }
+        fn on_finish(&mut self, _context: &Context, exit: &Exit) {
+            self.0.push(format!("finish: {exit}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn update_chain() {
+        let mut hooks = Hooks::default();
+        let context = Context::default();
+        AsyncUpdate::on_start(&mut hooks, &context).await.unwrap();
+        // the synchronous clients are never awaited
+        assert!(matches!(hooks.update_now(), Some(Ok(()))));
+        AsyncUpdate::update(&mut hooks).await.unwrap();
+        AsyncUpdate::on_finish(&mut hooks, &context, &Exit::Retired)
+            .await
+            .unwrap();
+        assert_eq!(
+            hooks.0,
+            vec![
+                "start".to_string(),
+                "update".to_string(),
+                "update".to_string(),
+                format!("finish: {}", Exit::Retired)
+            ]
+        );
+    }
+
+    /// Client failing its update after `n` updates
+    struct Countdown(usize);
+    impl TryUpdate for Countdown {
+        fn try_update(&mut self) -> ClientResult<()> {
+            self.0 = self.0.checked_sub(1).ok_or("countdown is over")?;
+            Ok(())
+        }
+        fn on_finish(&mut self, _context: &Context, exit: &Exit) -> ClientResult<()> {
+            if exit.is_normal() {
+                Ok(())
+            } else {
+                Err("abnormal exit".into())
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn try_update_chain() {
+        let mut countdown = Countdown(1);
+        assert!(matches!(countdown.update_now(), Some(Ok(()))));
+        assert!(AsyncUpdate::update(&mut countdown).await.is_err());
+        assert!(matches!(countdown.update_now(), Some(Err(_))));
+        let context = Context::default();
+        assert!(
+            AsyncUpdate::on_finish(&mut countdown, &context, &Exit::Retired)
+                .await
+                .is_ok()
+        );
+        let exit = Exit::Loop(crate::ActorError::client::<Countdown>("failed".into()));
+        assert!(AsyncUpdate::on_finish(&mut countdown, &context, &exit)
+            .await
+            .is_err());
+    }
 }
@@ -0,0 +1,183 @@
+use super::{AsyncUpdate, Context, Exit, TryUpdate};
+use crate::{
+    io::{Data, Read, UniqueIdentifier, Write},
+    ClientResult, Who,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+/**
+Client wrapper offloading the client update to the blocking threads pool
+
+The [update](TryUpdate::try_update) of a CPU-heavy or blocking client is run with [spawn_blocking](tokio::task::spawn_blocking)
+so the other actors are not starved while the client is updated.
+The inputs and outputs of the client are read and written as usual.
+If the actor is cancelled while the client is updated, the update runs to completion
+and the client [finishes](TryUpdate::on_finish) afterwards.
+
+```
+use gmt_dos_actors::{actor::Offload, clients::Integrator, prelude::*};
+# tokio_test::block_on(async {
+#[derive(UID)]
+enum Source {};
+let mut source: Initiator<_> = Signals::new(1, 5).signals(Signal::Constant(1.)).into();
+let mut integrator: Actor<_> = Offload::new(Integrator::<Source>::new(1).gain(0.5)).into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+source.add_output().build::<Source>().into_input(&mut integrator);
+integrator.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+    .check()?
+    .run()
+    .await?;
+assert_eq!(**logging.lock().await, vec![-0.5, -1., -1.5, -2., -2.5]);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+pub struct Offload<C> {
+    client: Arc<Mutex<C>>,
+}
+impl<C> Offload<C> {
+    /// Wraps the client
+    pub fn new(client: C) -> Self {
+        Self {
+            client: Arc::new(Mutex::new(client)),
+        }
+    }
+    /// Returns the client
+    ///
+    /// Returns [None] if the client is still updated after the actor has been cancelled
+    pub fn into_inner(self) -> Option<C> {
+        Arc::try_unwrap(self.client)
+            .ok()
+            .map(|client| client.into_inner())
+    }
+}
+impl<C> From<C> for Offload<C> {
+    fn from(client: C) -> Self {
+        Self::new(client)
+    }
+}
+impl<C> Who<C> for Offload<C> {}
+#[async_trait]
+impl<C> AsyncUpdate for Offload<C>
+where
+    C: 'static + TryUpdate + Send,
+{
+    async fn update(&mut self) -> ClientResult<()> {
+        // the blocking task owns the lock, so the client is released when the update ends
+        // even if this future is dropped before
+        let mut client = self.client.clone().lock_owned().await;
+        match tokio::task::spawn_blocking(move || client.try_update()).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(format!("{} update cancelled: {e}", Who::<C>::who(self)).into()),
+        }
+    }
+    async fn on_start(&mut self, context: &Context) -> ClientResult<()> {
+        self.client.lock().await.on_start(context)
+    }
+    async fn on_finish(&mut self, context: &Context, exit: &Exit) -> ClientResult<()> {
+        self.client.lock().await.on_finish(context, exit)
+    }
+}
+impl<C, U> Read<U> for Offload<C>
+where
+    C: Read<U>,
+    U: UniqueIdentifier,
+{
+    /// Reads the data into the client
+    ///
+    /// The data is discarded if the client is still updated after the actor has been cancelled
+    fn read(&mut self, data: Arc<Data<U>>) {
+        match self.client.try_lock() {
+            Ok(mut client) => client.read(data),
+            Err(_) => log::warn!("{} is still updated, input discarded", Who::<C>::who(self)),
+        }
+    }
+}
+impl<C, U> Write<U> for Offload<C>
+where
+    C: Write<U>,
+    U: UniqueIdentifier,
+{
+    /// Writes the client data
+    ///
+    /// Returns [None] if the client is still updated after the actor has been cancelled
+    fn write(&mut self) -> Option<Arc<Data<U>>> {
+        match self.client.try_lock() {
+            Ok(mut client) => client.write(),
+            Err(_) => {
+                log::warn!("{} is still updated, no output", Who::<C>::who(self));
+                None
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ActorError, Update};
+    use std::{sync::mpsc, time::Duration};
+
+    #[derive(crate::UID)]
+    enum Count {}
+
+    /// Client counting its updates, each update waiting for a signal
+    struct Blocked {
+        rx: mpsc::Receiver<()>,
+        updates: usize,
+    }
+    impl Update for Blocked {
+        fn update(&mut self) {
+            self.rx.recv().unwrap();
+            self.updates += 1;
+        }
+    }
+    impl Write<Count> for Blocked {
+        fn write(&mut self) -> Option<Arc<Data<Count>>> {
+            Some(Arc::new(Data::new(vec![self.updates as f64])))
+        }
+    }
+
+    #[tokio::test]
+    async fn cancelled_update_keeps_the_client() {
+        let (tx, rx) = mpsc::channel();
+        let mut offload = Offload::new(Blocked { rx, updates: 0 });
+        let update = AsyncUpdate::update(&mut offload);
+        assert!(tokio::time::timeout(Duration::from_millis(10), update)
+            .await
+            .is_err());
+        // the client is still updated
+        assert!(Write::<Count>::write(&mut offload).is_none());
+        tx.send(()).unwrap();
+        let exit = Exit::Loop(ActorError::Cancelled);
+        AsyncUpdate::on_finish(&mut offload, &Context::default(), &exit)
+            .await
+            .unwrap();
+        let data = Write::<Count>::write(&mut offload).unwrap();
+        assert_eq!(**data, vec![1.]);
+        tx.send(()).unwrap();
+        AsyncUpdate::update(&mut offload).await.unwrap();
+        assert_eq!(offload.into_inner().unwrap().updates, 2);
+    }
+
+    /// Client failing its update
+    struct Failing;
+    impl TryUpdate for Failing {
+        fn try_update(&mut self) -> ClientResult<()> {
+            Err("update failed".into())
+        }
+    }
+
+    #[tokio::test]
+    async fn update_error() {
+        let mut offload = Offload::new(Failing);
+        let e = AsyncUpdate::update(&mut offload).await.unwrap_err();
+        assert_eq!(e.to_string(), "update failed");
+        assert!(offload.into_inner().is_some());
+    }
+}
@@ -0,0 +1,93 @@
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// [Actor](crate::Actor) wall clock pacing statistics
+///
+/// The lateness of a client update is the time it completes
+/// relative to the schedule set by the first update of the actor, one sample every period.
+/// An update that completes more than one period late is an overrun.
+#[derive(Debug, Default, Clone)]
+pub struct ActorPacing {
+    /// # of paced client updates
+    pub samples: usize,
+    /// # of client updates completed more than one period late
+    pub overruns: usize,
+    /// Largest lateness
+    pub lateness_max: Duration,
+    lateness_sum: f64,
+    lateness_sq_sum: f64,
+}
+impl ActorPacing {
+    /// Returns the mean lateness in seconds, negative if the updates complete ahead of schedule
+    pub fn lateness_mean(&self) -> f64 {
+        if self.samples == 0 {
+            0f64
+        } else {
+            self.lateness_sum / self.samples as f64
+        }
+    }
+    /// Returns the jitter i.e. the standard deviation of the lateness in seconds
+    pub fn jitter(&self) -> f64 {
+        if self.samples == 0 {
+            0f64
+        } else {
+            let mean = self.lateness_mean();
+            (self.lateness_sq_sum / self.samples as f64 - mean * mean)
+                .max(0f64)
+                .sqrt()
+        }
+    }
+}
+
+/// Paces the samples of an [Actor](crate::Actor) with the wall clock
+#[derive(Debug)]
+pub(crate) struct Pacer {
+    start: Instant,
+    period: Duration,
+    origin: Option<(usize, Instant)>,
+    pub(crate) stats: ActorPacing,
+}
+impl Pacer {
+    pub fn new(start: Instant, period: Duration) -> Self {
+        Self {
+            start,
+            period,
+            origin: None,
+            stats: Default::default(),
+        }
+    }
+    /// Returns `n` periods
+    fn periods(&self, n: usize) -> Duration {
+        Duration::from_nanos((self.period.as_nanos() * n as u128) as u64)
+    }
+    /// Delays the schedule by `delay`
+    pub fn delay(&mut self, delay: Duration) {
+        self.start += delay;
+        if let Some((_, origin)) = self.origin.as_mut() {
+            *origin += delay;
+        }
+    }
+    /// Waits until the sample #`t` is due
+    pub async fn wait(&self, t: usize) {
+        tokio::time::sleep_until(self.start + self.periods(t)).await;
+    }
+    /// Records the completion of the client update at sample #`t`
+    pub fn record(&mut self, t: usize) {
+        let now = Instant::now();
+        let (t0, origin) = *self.origin.get_or_insert((t, now));
+        let scheduled = origin + self.periods(t - t0);
+        let lateness = if now >= scheduled {
+            let late = now - scheduled;
+            if late > self.period {
+                self.stats.overruns += 1;
+            }
+            self.stats.lateness_max = self.stats.lateness_max.max(late);
+            late.as_secs_f64()
+        } else {
+            -(scheduled - now).as_secs_f64()
+        };
+        self.stats.samples += 1;
+        self.stats.lateness_sum += lateness;
+        self.stats.lateness_sq_sum += lateness * lateness;
+    }
+}
@@ -1,21 +1,42 @@
-#[derive(Debug, Hash)]
+#[derive(Debug, Clone, Hash)]
 #[doc(hidden)]
 pub struct PlainIO {
     pub name: String,
     pub hash: u64,
+    pub capacity: Option<usize>,
+    pub multiplex: usize,
+    pub rate: usize,
 }
 impl PlainIO {
     pub fn new(name: String, hash: u64) -> Self {
-        Self { name, hash }
+        Self {
+            name,
+            hash,
+            capacity: None,
+            multiplex: 1,
+            rate: 1,
+        }
+    }
+    /// Sets the capacity of the channel, `None` if unbounded
+    pub fn capacity(self, capacity: Option<usize>) -> Self {
+        Self { capacity, ..self }
+    }
+    /// Sets the number of channels
+    pub fn multiplex(self, multiplex: usize) -> Self {
+        Self { multiplex, ..self }
+    }
+    /// Sets the sampling rate
+    pub fn rate(self, rate: usize) -> Self {
+        Self { rate, ..self }
     }
 }
-#[derive(Debug, Hash)]
+#[derive(Debug, Clone, Hash)]
 #[doc(hidden)]
 pub enum PlainOutput {
     Bootstrap(PlainIO),
     Regular(PlainIO),
 }
-#[derive(Debug, Hash)]
+#[derive(Debug, Clone, Hash)]
 #[doc(hidden)]
 pub struct PlainActor {
     pub client: String,
@@ -24,4 +45,49 @@ pub struct PlainActor {
     pub inputs: Option<Vec<PlainIO>>,
     pub outputs: Option<Vec<PlainOutput>>,
     pub hash: u64,
+    pub clusters: Vec<String>,
+}
+/// Returns the number of samples sent before the actor loop by a bootstrapped output
+/// sampled at `rate` by an actor with inputs sampled at `inputs_rate`
+///
+/// The samples cover the inputs sampling period, the rates must be multiple of one another
+pub(crate) fn bootstrap_samples(inputs_rate: usize, rate: usize) -> usize {
+    debug_assert!(
+        bootstrap_divisible(inputs_rate, rate),
+        "bootstrap rates {inputs_rate} and {rate} are not multiple of one another"
+    );
+    inputs_rate.checked_div(rate).unwrap_or_default().max(1)
+}
+/// Checks if the rate of a bootstrapped output and the inputs rate of its actor are multiple of one another
+pub(crate) fn bootstrap_divisible(inputs_rate: usize, rate: usize) -> bool {
+    inputs_rate == 0
+        || rate == 0
+        || inputs_rate.is_multiple_of(rate)
+        || rate.is_multiple_of(inputs_rate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bootstrap_samples_cover_the_inputs_period() {
+        assert_eq!(bootstrap_samples(10, 5), 2);
+        assert_eq!(bootstrap_samples(10, 1), 10);
+        assert_eq!(bootstrap_samples(4, 4), 1);
+        // a single sample for an output slower than the inputs
+        assert_eq!(bootstrap_samples(2, 10), 1);
+        // a single sample without inputs or for an unset output rate
+        assert_eq!(bootstrap_samples(0, 5), 1);
+        assert_eq!(bootstrap_samples(5, 0), 1);
+    }
+
+    #[test]
+    fn bootstrap_divisibility() {
+        assert!(bootstrap_divisible(10, 5));
+        assert!(bootstrap_divisible(5, 10));
+        assert!(bootstrap_divisible(0, 3));
+        assert!(!bootstrap_divisible(3, 2));
+        assert!(!bootstrap_divisible(4, 6));
+    }
 }
@@ -0,0 +1,36 @@
+use std::time::Duration;
+
+/// [Actor](crate::Actor) runtime profile
+///
+/// The profile records the time spent updating the client
+/// and the time spent receiving the inputs and sending the outputs,
+/// the latter includes the time waiting on the channels but not the time spent by the client
+/// reading the inputs and writing the outputs
+#[derive(Debug, Default, Clone)]
+pub struct ActorProfile {
+    /// # of client updates
+    pub iterations: usize,
+    /// Total time spent in client updates
+    pub update: Duration,
+    /// Longest client update
+    pub update_max: Duration,
+    /// Total time spent receiving the inputs
+    pub inputs: Duration,
+    /// Total time spent sending the outputs
+    pub outputs: Duration,
+}
+impl ActorProfile {
+    /// Records a client update
+    pub(crate) fn update(&mut self, elapsed: Duration) {
+        self.iterations += 1;
+        self.update += elapsed;
+        self.update_max = self.update_max.max(elapsed);
+    }
+    /// Returns the mean client update time
+    pub fn update_mean(&self) -> Duration {
+        u32::try_from(self.iterations)
+            .ok()
+            .and_then(|n| self.update.checked_div(n))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,89 @@
+use super::{ActorPacing, ActorProfile};
+use crate::ActorError;
+use std::{any::Any, fmt, time::Duration};
+
+/// Reason for an [Actor](crate::Actor) to end its task
+#[derive(Debug)]
+pub enum Exit {
+    /// The client failed to start
+    Start(ActorError),
+    /// The actor failed to bootstrap its outputs
+    Bootstrap(ActorError),
+    /// The actor left its loop
+    Loop(ActorError),
+    /// The actor task panicked with the given message
+    Panic(String),
+    /// The client failed to finish after the actor left its loop normally
+    Finish(ActorError),
+    /// The actor, or its client, was retired from a [reconfigure](crate::model::Model::reconfigure)d model
+    Retired,
+}
+impl Exit {
+    /// Returns the panic message from a panic payload
+    pub(crate) fn panic(payload: Box<dyn Any + Send>) -> Self {
+        let msg = payload
+            .downcast_ref::<&str>()
+            .map(|msg| msg.to_string())
+            .or_else(|| payload.downcast_ref::<String>().cloned())
+            .unwrap_or_else(|| "unknown panic payload".to_string());
+        Exit::Panic(msg)
+    }
+    /// Checks if the actor ended because it ran out of data, because the model was stopped
+    /// or because it was retired
+    pub fn is_normal(&self) -> bool {
+        use ActorError::*;
+        matches!(
+            self,
+            Exit::Loop(DropRecv(_) | DropSend(_) | Disconnected(_) | NoData | Cancelled)
+                | Exit::Retired
+        )
+    }
+}
+impl fmt::Display for Exit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Exit::Start(e) => write!(f, "starting failed: {e}"),
+            Exit::Bootstrap(e) => write!(f, "bootstrapping failed: {e}"),
+            Exit::Loop(e) => write!(f, "loop ended: {e}"),
+            Exit::Panic(msg) => write!(f, "panicked: {msg}"),
+            Exit::Finish(e) => write!(f, "finishing failed: {e}"),
+            Exit::Retired => write!(f, "retired"),
+        }
+    }
+}
+
+/// [Actor](crate::Actor) task report
+#[derive(Debug)]
+pub struct ActorReport {
+    /// Actor name
+    pub name: String,
+    /// Reason for the actor to end its task
+    pub exit: Exit,
+    /// # of client updates
+    pub step: usize,
+    /// Actor task wall time
+    pub elapsed: Duration,
+    /// Actor runtime profile, if profiling is enabled
+    pub profile: Option<ActorProfile>,
+    /// Actor pacing statistics, if pacing is enabled
+    pub pacing: Option<ActorPacing>,
+}
+impl ActorReport {
+    /// Checks if the actor ended because it ran out of data, because the model was stopped
+    /// or because it was retired
+    pub fn is_normal(&self) -> bool {
+        self.exit.is_normal()
+    }
+}
+impl fmt::Display for ActorReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} after {} steps in {}",
+            self.name,
+            self.exit,
+            self.step,
+            humantime::format_duration(self.elapsed)
+        )
+    }
+}
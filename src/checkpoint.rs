@@ -0,0 +1,277 @@
+/*!
+# Model checkpointing
+
+A [Running](crate::model::Running) model is [pause](crate::model::Model::pause)d at a step boundary
+and the state of the [Paused](crate::model::Paused) model is written to a file with [checkpoint](crate::model::Model::checkpoint).
+Later, the same model is rebuilt and [restore](crate::model::Model::restore)d from the file before it is run again,
+resuming where the paused model left off.
+
+The state of a client is saved only if the client implements the [Checkpoint] trait
+and if its actor is [checkpointed](crate::Actor::checkpointed).
+The data in flight, received by the actors inputs and not yet read by the clients, is saved as well,
+so the resumed model gives the same results than a model running without interruption:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{clients::Integrator, model::{ModelError, Ready}, prelude::*};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+#[derive(UID)]
+enum Source {};
+fn build(logging: Arc<Mutex<Logging<f64>>>) -> Result<Model<Ready>, ModelError> {
+    let mut source: Initiator<_> = Actor::from(Signals::new(1, 100).signals(Signal::Ramp { a: 1., b: 0. }))
+        .checkpointed();
+    let mut integrator: Actor<_> = Actor::from(Integrator::<Source>::new(1).gain(0.5))
+        .checkpointed();
+    let mut sink = Terminator::<_>::new(logging).checkpointed();
+    source.add_output().build::<Source>().into_input(&mut integrator);
+    integrator
+        .add_output()
+        .bootstrap()
+        .batch(8)
+        .build_recorded::<Source>()
+        .into_input(&mut sink);
+    Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)]).check()
+}
+
+let logging = Logging::<f64>::default().into_arcx();
+build(logging.clone())?.run().await?;
+
+let path = std::env::temp_dir().join("integrator.ckpt");
+let mut paused = build(Logging::<f64>::default().into_arcx())?
+    .run()
+    .pause(42)
+    .await?;
+let step = paused.step();
+paused.checkpoint(&path).await?;
+
+let restored = Logging::<f64>::default().into_arcx();
+build(restored.clone())?.restore(&path).await?.run().await?;
+assert!(step >= 42);
+assert_eq!(*logging.lock().await.as_slice(), *restored.lock().await.as_slice());
+# Ok::<(), ModelError>(())
+# });
+```
+
+The actors that ended before the model was paused are left out of the restored model,
+the actors fed by them read the data the ended actors sent before their inputs are disconnected.
+
+The samples in flight are saved only if the output that sent them is built with [build_recorded](crate::AddOuput::build_recorded),
+checkpointing fails if an input has samples in flight from another output.
+*/
+
+use crate::actor::plain::{PlainActor, PlainOutput};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::Path,
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum CheckpointError {
+    #[error("failed to read or to write the checkpoint")]
+    Io(#[from] std::io::Error),
+    #[error("failed to encode or to decode the checkpoint")]
+    Codec(#[from] bincode::Error),
+    #[error("no state of actor {0} in the checkpoint")]
+    MissingActor(String),
+    #[error("no actor in the model for the checkpointed actor {0}")]
+    UnknownActor(String),
+    #[error("actor {0} is checkpointed either in the model or in the checkpoint but not in both")]
+    Client(String),
+    #[error("input {0} has samples in flight but its output is not built with build_recorded")]
+    Unrecorded(String),
+    #[error("actor {actor} has {inputs} input(s) but the checkpoint has samples for {pending}")]
+    PendingInputs {
+        actor: String,
+        inputs: usize,
+        pending: usize,
+    },
+    #[error("no samples of actor {actor} input {input} in the checkpoint")]
+    MissingInput { actor: String, input: String },
+}
+pub type Result<R> = std::result::Result<R, CheckpointError>;
+
+/// Client state checkpointing interface
+///
+/// The state is the part of the client that changes while the model runs,
+/// the configuration of the client is given again when the model is rebuilt
+pub trait Checkpoint {
+    type State: Serialize + DeserializeOwned;
+    /// Returns the client state
+    fn state(&self) -> Self::State;
+    /// Sets the client state
+    fn restore(&mut self, state: Self::State);
+}
+
+/// Client state encoder and decoder of a [checkpointed](crate::Actor::checkpointed) actor
+pub(crate) struct ClientCodec<C> {
+    pub encode: fn(&C) -> bincode::Result<Vec<u8>>,
+    pub decode: fn(&mut C, &[u8]) -> bincode::Result<()>,
+}
+impl<C: Checkpoint> ClientCodec<C> {
+    pub fn new() -> Self {
+        Self {
+            encode: |client| bincode::serialize(&client.state()),
+            decode: |client, bytes| {
+                client.restore(bincode::deserialize(bytes)?);
+                Ok(())
+            },
+        }
+    }
+}
+
+/// Actor identified by its name and by its inputs and outputs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActorKey {
+    /// Actor name
+    pub name: String,
+    /// Hashes of the inputs
+    pub inputs: Vec<u64>,
+    /// Hashes of the outputs
+    pub outputs: Vec<u64>,
+}
+impl From<&PlainActor> for ActorKey {
+    fn from(actor: &PlainActor) -> Self {
+        Self {
+            name: actor.client.clone(),
+            inputs: actor.inputs.iter().flatten().map(|io| io.hash).collect(),
+            outputs: actor
+                .outputs
+                .iter()
+                .flatten()
+                .map(|output| match output {
+                    PlainOutput::Bootstrap(io) | PlainOutput::Regular(io) => io.hash,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Checkpointed actor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActorState {
+    /// Actor name, inputs and outputs
+    pub actor: ActorKey,
+    /// Number of client updates
+    pub step: usize,
+    /// Number of bootstrapping rounds
+    pub bootstrap_round: usize,
+    /// Encoded client [state](Checkpoint::state), `None` if the actor is not [checkpointed](crate::Actor::checkpointed)
+    pub client: Option<Vec<u8>>,
+    /// Encoded samples received by each input and not yet read by the client, keyed by the input hash
+    pub pending: Vec<(u64, Vec<Vec<u8>>)>,
+}
+/// Checkpointed model
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelCheckpoint {
+    /// Step boundary at which the model was paused
+    pub step: usize,
+    /// Actors halted at the step boundary
+    pub actors: Vec<ActorState>,
+    /// Actors that ended before the step boundary
+    pub ended: Vec<ActorKey>,
+}
+impl ModelCheckpoint {
+    /// Writes the checkpoint to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let file = BufWriter::new(File::create(path)?);
+        bincode::serialize_into(file, self)?;
+        Ok(())
+    }
+    /// Loads a checkpoint from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let file = BufReader::new(File::open(path)?);
+        Ok(bincode::deserialize_from(file)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clients::Integrator,
+        model::{Model, ModelError, Ready},
+        prelude::*,
+    };
+
+    #[derive(UID)]
+    enum Source {}
+
+    fn build(recorded: bool) -> Model<Ready> {
+        let mut source: Initiator<_> =
+            Actor::from(Signals::new(1, 100).signals(Signal::Ramp { a: 1., b: 0. })).checkpointed();
+        let mut integrator: Actor<_> =
+            Actor::from(Integrator::<Source>::new(1).gain(0.5)).checkpointed();
+        let mut sink = Terminator::<_>::new(Logging::<f64>::default().into_arcx()).checkpointed();
+        source
+            .add_output()
+            .build::<Source>()
+            .into_input(&mut integrator);
+        let output = integrator.add_output().bootstrap().batch(8);
+        let output = if recorded {
+            output.build_recorded::<Source>()
+        } else {
+            output.build::<Source>()
+        };
+        output.into_input(&mut sink);
+        Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+            .check()
+            .unwrap()
+    }
+
+    /// Checkpoints the model paused at step 42
+    async fn checkpoint(path: &Path, recorded: bool) -> std::result::Result<(), ModelError> {
+        let mut paused = build(recorded).run().pause(42).await?;
+        let result = paused.checkpoint(path).await;
+        paused.resume().await?;
+        result
+    }
+
+    /// Returns the state of the sink
+    fn sink(checkpoint: &mut ModelCheckpoint) -> &mut ActorState {
+        checkpoint
+            .actors
+            .iter_mut()
+            .find(|state| state.actor.name.contains("Logging"))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn unrecorded_samples_in_flight() {
+        let path = std::env::temp_dir().join("unrecorded_samples_in_flight.ckpt");
+        assert!(matches!(
+            checkpoint(&path, false).await,
+            Err(ModelError::Checkpoint(CheckpointError::Unrecorded(_)))
+        ));
+    }
+
+    #[tokio::test]
+    async fn pending_samples_keyed_by_input() {
+        let path = std::env::temp_dir().join("pending_samples_keyed_by_input.ckpt");
+        checkpoint(&path, true).await.unwrap();
+        let mut model = ModelCheckpoint::load(&path).unwrap();
+        assert!(!sink(&mut model).pending[0].1.is_empty());
+        assert!(build(true).restore(&path).await.is_ok());
+
+        let mut unknown = model.clone();
+        sink(&mut unknown).pending[0].0 ^= 1;
+        unknown.save(&path).unwrap();
+        assert!(matches!(
+            build(true).restore(&path).await,
+            Err(ModelError::Checkpoint(CheckpointError::MissingInput { .. }))
+        ));
+
+        let mut missing = model.clone();
+        sink(&mut missing).pending.clear();
+        missing.save(&path).unwrap();
+        assert!(matches!(
+            build(true).restore(&path).await,
+            Err(ModelError::Checkpoint(CheckpointError::PendingInputs {
+                inputs: 1,
+                pending: 0,
+                ..
+            }))
+        ));
+    }
+}
@@ -4,12 +4,14 @@ use crate::{
 };
 use std::{
     marker::PhantomData,
-    mem,
     ops::{AddAssign, DivAssign},
     sync::Arc,
 };
 
 /// Rate transitionner
+///
+/// Writes the average of the inputs read since the last write,
+/// or the last average if no input has been read since
 #[derive(Debug)]
 pub struct Average<T, U: UniqueIdentifier<Data = Vec<T>>, V: UniqueIdentifier<Data = Vec<T>> = U> {
     data: Vec<T>,
@@ -23,7 +25,7 @@ where
     U: UniqueIdentifier<Data = Vec<T>>,
     V: UniqueIdentifier<Data = Vec<T>>,
 {
-    /// Creates a new sampler with initial condition
+    /// Creates a new averager with `n_data` null initial values
     pub fn new(n_data: usize) -> Self {
         Self {
             data: vec![T::default(); n_data],
@@ -44,10 +46,15 @@ where
     V: UniqueIdentifier<Data = Vec<T>>,
 {
     fn read(&mut self, data: Arc<Data<U>>) {
-        self.data
-            .iter_mut()
-            .zip(&**data)
-            .for_each(|(u, &x)| *u += x);
+        if self.count == 0 {
+            self.data.clear();
+            self.data.extend_from_slice(&data);
+        } else {
+            self.data
+                .iter_mut()
+                .zip(&**data)
+                .for_each(|(u, &x)| *u += x);
+        }
         self.count += 1;
     }
 }
@@ -59,15 +66,50 @@ where
 {
     fn write(&mut self) -> Option<Arc<Data<V>>> {
         if self.count > 0 {
-            if let Ok(count) = T::try_from(self.count) {
-                self.data.iter_mut().for_each(|x| *x /= count);
-                self.count = 0;
-                Some(Arc::new(Data::new(mem::take(&mut self.data))))
-            } else {
-                None
-            }
-        } else {
-            None
+            let count = T::try_from(self.count).ok()?;
+            self.data.iter_mut().for_each(|x| *x /= count);
+            self.count = 0;
+        }
+        Some(Arc::new(Data::new(self.data.clone())))
+    }
+}
+#[cfg(feature = "checkpoint")]
+impl<T, U, V> crate::checkpoint::Checkpoint for Average<T, U, V>
+where
+    T: Clone + crate::io::Record,
+    U: UniqueIdentifier<Data = Vec<T>>,
+    V: UniqueIdentifier<Data = Vec<T>>,
+{
+    type State = (Vec<T>, u32);
+    fn state(&self) -> Self::State {
+        (self.data.clone(), self.count)
+    }
+    fn restore(&mut self, (data, count): Self::State) {
+        self.data = data;
+        self.count = count;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(crate::UID)]
+    enum Samples {}
+
+    #[test]
+    fn holds_last_average() {
+        let mut average = Average::<f64, Samples>::new(2);
+        let mut outputs = vec![];
+        for samples in [vec![], vec![1., 3.], vec![], vec![4.]] {
+            samples.into_iter().for_each(|x| {
+                Read::<Samples>::read(&mut average, Arc::new(Data::new(vec![x, -x])))
+            });
+            outputs.push(Write::<Samples>::write(&mut average).unwrap().to_vec());
         }
+        assert_eq!(
+            outputs,
+            vec![vec![0., 0.], vec![2., -2.], vec![2., -2.], vec![4., -4.]]
+        );
     }
 }
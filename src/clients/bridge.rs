@@ -0,0 +1,270 @@
+/*!
+# Cross-process actor bridge
+
+A [Transmitter] client serializes the data it reads and writes it to a socket or a pipe,
+a [Receiver] client reads the data from the other end of the socket or the pipe and deserializes it.
+A model can then be split in several models running in different processes or on different machines,
+the data type of the bridged [UID](crate::UID) must implement [serde]'s `Serialize` and `Deserialize`.
+
+The [Receiver] returns no data once the [Transmitter] is dropped, ending the receiving model.
+A failure to connect, to send or to receive the data ends the actor loop with a [client](crate::ActorError::Client) error.
+
+```
+use gmt_dos_actors::{clients::bridge::{Receiver, Transmitter}, prelude::*};
+use std::net::TcpListener;
+#[derive(UID)]
+enum Bridged {}
+
+let runtime = tokio::runtime::Builder::new_multi_thread()
+    .worker_threads(2)
+    .enable_all()
+    .build()?;
+runtime.block_on(async {
+    let listener = TcpListener::bind("127.0.0.1:0")?;
+    let address = listener.local_addr()?;
+
+    // receiving model
+    let mut receiver: Initiator<_> = Receiver::<Bridged, _>::tcp(listener).into();
+    let logging = Logging::<f64>::default().into_arcx();
+    let mut sink = Terminator::<_>::new(logging.clone());
+    receiver.add_output().build::<Bridged>().into_input(&mut sink);
+    let receiving = Model::new(vec![Box::new(receiver), Box::new(sink)])
+        .check()?
+        .run();
+
+    // transmitting model
+    let mut source: Initiator<_> = Signals::new(1, 10).signals(Signal::Constant(1.)).into();
+    let mut transmitter: Terminator<_> = Transmitter::<Bridged, _>::tcp(address)?.into();
+    source.add_output().build::<Bridged>().into_input(&mut transmitter);
+    Model::new(vec![Box::new(source), Box::new(transmitter)])
+        .check()?
+        .run()
+        .await?;
+
+    receiving.await?;
+    assert_eq!(**logging.lock().await, vec![1f64; 10]);
+    Ok::<(), Box<dyn std::error::Error>>(())
+})?;
+# Ok::<(), Box<dyn std::error::Error>>(())
+```
+*/
+
+use crate::{
+    io::{Assoc, Data, Read, UniqueIdentifier, Write},
+    ClientResult, TryUpdate,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    io::{self, BufReader, BufWriter},
+    net::{TcpListener, TcpStream, ToSocketAddrs},
+    sync::Arc,
+};
+#[cfg(unix)]
+use std::{
+    os::unix::net::{UnixListener, UnixStream},
+    path::Path,
+};
+use tokio::runtime::{Handle, RuntimeFlavor};
+
+/// Runs a blocking I/O operation without stalling the other actors of a multi-threaded runtime
+fn blocking<R>(f: impl FnOnce() -> R) -> R {
+    match Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// Bridge client serializing the data it reads to a writer
+pub struct Transmitter<U: UniqueIdentifier, W: io::Write> {
+    writer: BufWriter<W>,
+    data: Option<Arc<Data<U>>>,
+}
+impl<U: UniqueIdentifier, W: io::Write> Transmitter<U, W> {
+    /// Creates a new transmitter writing to `writer`
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: BufWriter::new(writer),
+            data: None,
+        }
+    }
+}
+impl<U: UniqueIdentifier> Transmitter<U, TcpStream> {
+    /// Creates a new transmitter connected to a TCP socket
+    pub fn tcp<A: ToSocketAddrs>(address: A) -> io::Result<Self> {
+        let stream = TcpStream::connect(address)?;
+        stream.set_nodelay(true)?;
+        Ok(Self::new(stream))
+    }
+}
+#[cfg(unix)]
+impl<U: UniqueIdentifier> Transmitter<U, UnixStream> {
+    /// Creates a new transmitter connected to a Unix socket
+    pub fn unix<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        Ok(Self::new(UnixStream::connect(path)?))
+    }
+}
+impl<U, W> TryUpdate for Transmitter<U, W>
+where
+    U: UniqueIdentifier,
+    Assoc<U>: Serialize,
+    W: io::Write,
+{
+    /// Sends the last data read, returns an error if sending fails
+    fn try_update(&mut self) -> ClientResult<()> {
+        if let Some(data) = self.data.take() {
+            let writer = &mut self.writer;
+            blocking(|| {
+                bincode::serialize_into(&mut *writer, &**data)?;
+                io::Write::flush(writer).map_err(bincode::Error::from)
+            })?;
+        }
+        Ok(())
+    }
+}
+impl<U, W> Read<U> for Transmitter<U, W>
+where
+    U: UniqueIdentifier,
+    W: io::Write,
+{
+    fn read(&mut self, data: Arc<Data<U>>) {
+        self.data = Some(data);
+    }
+}
+
+/// Bridge client deserializing the data it writes from a reader
+pub struct Receiver<U: UniqueIdentifier, R: io::Read> {
+    reader: Option<BufReader<R>>,
+    accept: Option<Box<dyn FnOnce() -> io::Result<R> + Send>>,
+    data: Option<Arc<Data<U>>>,
+}
+impl<U: UniqueIdentifier, R: io::Read> Receiver<U, R> {
+    /// Creates a new receiver reading from `reader`
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Some(BufReader::new(reader)),
+            accept: None,
+            data: None,
+        }
+    }
+    /// Returns the reader, waiting for the connection if needed
+    fn reader(&mut self) -> io::Result<Option<&mut BufReader<R>>> {
+        if let Some(accept) = self.accept.take() {
+            self.reader = Some(BufReader::new(blocking(accept)?));
+        }
+        Ok(self.reader.as_mut())
+    }
+}
+impl<U: UniqueIdentifier> Receiver<U, TcpStream> {
+    /// Creates a new receiver for the first connection to the TCP listener
+    ///
+    /// The connection is accepted when the receiver is first asked for data
+    pub fn tcp(listener: TcpListener) -> Self {
+        Self {
+            reader: None,
+            accept: Some(Box::new(move || {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(stream)
+            })),
+            data: None,
+        }
+    }
+}
+#[cfg(unix)]
+impl<U: UniqueIdentifier> Receiver<U, UnixStream> {
+    /// Creates a new receiver for the first connection to the Unix socket listener
+    ///
+    /// The connection is accepted when the receiver is first asked for data
+    pub fn unix(listener: UnixListener) -> Self {
+        Self {
+            reader: None,
+            accept: Some(Box::new(move || {
+                listener.accept().map(|(stream, _)| stream)
+            })),
+            data: None,
+        }
+    }
+}
+impl<U, R> TryUpdate for Receiver<U, R>
+where
+    U: UniqueIdentifier,
+    Assoc<U>: DeserializeOwned,
+    R: io::Read,
+{
+    /// Receives the next data, none once the transmitter is closed
+    ///
+    /// An error is returned if the connection fails or if the data cannot be received
+    fn try_update(&mut self) -> ClientResult<()> {
+        let Some(reader) = self.reader()? else {
+            return Ok(());
+        };
+        match blocking(|| bincode::deserialize_from(reader)) {
+            Ok(data) => self.data = Some(Arc::new(Data::new(data))),
+            Err(e) => {
+                self.reader = None;
+                if !matches!(*e, bincode::ErrorKind::Io(ref e) if e.kind() == io::ErrorKind::UnexpectedEof)
+                {
+                    return Err(e);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+impl<U, R> Write<U> for Receiver<U, R>
+where
+    U: UniqueIdentifier,
+    R: io::Read,
+{
+    fn write(&mut self) -> Option<Arc<Data<U>>> {
+        self.data.take()
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::UID;
+
+    #[derive(UID)]
+    enum Bridged {}
+
+    #[test]
+    fn unix_socket() {
+        let path = std::env::temp_dir().join(format!("bridge-{}.sock", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let mut receiver = Receiver::<Bridged, _>::unix(UnixListener::bind(&path).unwrap());
+        let mut transmitter = Transmitter::<Bridged, _>::unix(&path).unwrap();
+        for i in 0..3 {
+            transmitter.read(Arc::new(Data::new(vec![i as f64; 2])));
+            transmitter.try_update().unwrap();
+        }
+        drop(transmitter);
+        let received: Vec<Vec<f64>> = std::iter::from_fn(|| {
+            receiver.try_update().unwrap();
+            <Receiver<Bridged, _> as Write<Bridged>>::write(&mut receiver)
+        })
+        .map(|data| (*data).clone())
+        .collect();
+        assert_eq!(received, vec![vec![0.; 2], vec![1.; 2], vec![2.; 2]]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn io_errors_are_returned() {
+        let (tx, rx) = UnixStream::pair().unwrap();
+        drop(rx);
+        let mut transmitter = Transmitter::<Bridged, _>::new(tx);
+        transmitter.read(Arc::new(Data::new(vec![0.; 2])));
+        assert!(transmitter.try_update().is_err());
+
+        let mut receiver = Receiver::<Bridged, UnixStream> {
+            reader: None,
+            accept: Some(Box::new(|| Err(io::ErrorKind::ConnectionRefused.into()))),
+            data: None,
+        };
+        assert!(receiver.try_update().is_err());
+    }
+}
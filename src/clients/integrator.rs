@@ -1,6 +1,6 @@
 use crate::{
     io::{Data, Read, UniqueIdentifier, Write},
-    Update,
+    ClientResult, Update,
 };
 use std::{
     marker::PhantomData,
@@ -38,15 +38,28 @@ where
         }
     }
     /// Sets the gain vector
+    ///
+    /// # Panics
+    /// If the length of the gain vector does not match the integrator size, see [try_gain_vector](Integrator::try_gain_vector)
     pub fn gain_vector(self, gain: Vec<T>) -> Self {
-        assert_eq!(
-            gain.len(),
-            self.mem.len(),
-            "gain vector length error: expected {} found {}",
-            gain.len(),
-            self.mem.len()
-        );
-        Self { gain, ..self }
+        match self.try_gain_vector(gain) {
+            Ok(this) => this,
+            Err(e) => panic!("{e}"),
+        }
+    }
+    /// Sets the gain vector
+    ///
+    /// Returns an error if the length of the gain vector does not match the integrator size
+    pub fn try_gain_vector(self, gain: Vec<T>) -> ClientResult<Self> {
+        if gain.len() != self.mem.len() {
+            return Err(format!(
+                "gain vector length error: expected {} found {}",
+                self.mem.len(),
+                gain.len()
+            )
+            .into());
+        }
+        Ok(Self { gain, ..self })
     }
     /// Sets the integrator zero point
     pub fn zero(self, zero: Vec<T>) -> Self {
@@ -84,3 +97,80 @@ where
         Some(Arc::new(Data::new(y)))
     }
 }
+#[cfg(feature = "checkpoint")]
+impl<T, U> crate::checkpoint::Checkpoint for Integrator<U>
+where
+    T: Clone + crate::io::Record,
+    U: UniqueIdentifier<Data = Vec<T>>,
+{
+    type State = Vec<T>;
+    fn state(&self) -> Self::State {
+        self.mem.clone()
+    }
+    fn restore(&mut self, mem: Self::State) {
+        self.mem = mem;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        testing::{assert_close, Driver},
+        UID,
+    };
+
+    #[derive(UID)]
+    enum E {}
+    #[derive(UID)]
+    enum U {}
+
+    #[test]
+    fn integrate() {
+        let mut driver = Driver::new(Integrator::<E>::new(2).gain(0.5))
+            .input::<E>(vec![vec![1., -2.]; 4])
+            .output::<U>();
+        assert_eq!(driver.run(10).unwrap(), 4);
+        assert_close(
+            driver.outputs::<U>(),
+            &[vec![-0.5, 1.], vec![-1., 2.], vec![-1.5, 3.], vec![-2., 4.]],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn zero_point() {
+        let mut driver = Driver::new(Integrator::<E>::new(1).gain(1.).zero(vec![1.]))
+            .input::<E>(vec![vec![1.]; 3])
+            .output::<U>();
+        driver.run(3).unwrap();
+        assert_close(driver.outputs::<U>(), &vec![vec![1.]; 3], 1e-12);
+    }
+
+    #[test]
+    fn decimated() {
+        let mut driver = Driver::new(Integrator::<E>::new(1).gain(1.))
+            .rates(1, 2)
+            .input::<E>(vec![vec![1.]; 6])
+            .output::<U>();
+        driver.run(6).unwrap();
+        assert_close(
+            driver.outputs::<U>(),
+            &[vec![-2.], vec![-4.], vec![-6.]],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn gain_vector_length() {
+        assert!(Integrator::<E>::new(2).try_gain_vector(vec![1.; 2]).is_ok());
+        let e = Integrator::<E>::new(2)
+            .try_gain_vector(vec![1.; 3])
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "gain vector length error: expected 2 found 3"
+        );
+    }
+}
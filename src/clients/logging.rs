@@ -1,4 +1,5 @@
 use crate::{
+    actor::{Clock, Context, Exit},
     io::{Data, Read, UniqueIdentifier},
     Update,
 };
@@ -6,12 +7,15 @@ use std::{any::type_name, fmt::Display, sync::Arc};
 
 /// Simple data logging
 ///
-/// Accumulates all the inputs in a single [Vec]
+/// Accumulates all the inputs in a single [Vec],
+/// with the simulation time of each sample if the logger is [stamped](Logging::stamped)
 #[derive(Debug)]
 pub struct Logging<T> {
     data: Vec<T>,
     n_sample: usize,
     n_entry: usize,
+    time: Option<Vec<f64>>,
+    clock: Clock,
 }
 
 impl<T> std::ops::Deref for Logging<T> {
@@ -27,6 +31,8 @@ impl<T> Default for Logging<T> {
             n_entry: 1,
             data: Vec::new(),
             n_sample: 0,
+            time: None,
+            clock: Clock::default(),
         }
     }
 }
@@ -42,6 +48,17 @@ impl<T> Logging<T> {
             ..self
         }
     }
+    /// Records the simulation [time](Clock::time) of each sample
+    pub fn stamped(self) -> Self {
+        Self {
+            time: Some(Vec::new()),
+            ..self
+        }
+    }
+    /// Returns the simulation time of each sample, if the logger is [stamped](Logging::stamped)
+    pub fn time(&self) -> Option<&[f64]> {
+        self.time.as_deref()
+    }
     /// Returns the # of time samples
     pub fn len(&self) -> usize {
         self.n_sample / self.n_entry
@@ -72,11 +89,40 @@ impl<T> Display for Logging<T> {
     }
 }
 
-impl<T> Update for Logging<T> {}
+impl<T> Update for Logging<T> {
+    fn on_start(&mut self, context: &Context) {
+        self.clock = context.clock.clone();
+    }
+    fn on_finish(&mut self, context: &Context, _exit: &Exit) {
+        log::info!(
+            "{} logged {} samples in {} steps",
+            type_name::<Self>(),
+            self.len(),
+            context.step
+        );
+    }
+}
 impl<T: Clone, U: UniqueIdentifier<Data = Vec<T>>> Read<U> for Logging<T> {
     fn read(&mut self, data: Arc<Data<U>>) {
         log::debug!("receive {} input: {:}", type_name::<U>(), data.len(),);
         self.data.extend((**data).clone());
+        if let Some(time) = self.time.as_mut() {
+            if self.n_sample.is_multiple_of(self.n_entry) {
+                time.push(self.clock.time());
+            }
+        }
         self.n_sample += 1;
     }
 }
+#[cfg(feature = "checkpoint")]
+impl<T: Clone + crate::io::Record> crate::checkpoint::Checkpoint for Logging<T> {
+    type State = (Vec<T>, usize, Option<Vec<f64>>);
+    fn state(&self) -> Self::State {
+        (self.data.clone(), self.n_sample, self.time.clone())
+    }
+    fn restore(&mut self, (data, n_sample, time): Self::State) {
+        self.data = data;
+        self.n_sample = n_sample;
+        self.time = time;
+    }
+}
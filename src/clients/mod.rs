@@ -32,12 +32,31 @@ A 2 outputs signal made of a constant and a sinusoid for 100 steps
 use gmt_dos_actors::prelude::*;
 let signal = Signals::new(2, 100)
                .output_signal(0, Signal::Constant(3.14))
-               .output_signal(1, Signal::Sinusoid{
-                                        amplitude: 1f64,
-                                        sampling_frequency_hz: 1000f64,
-                                        frequency_hz: 20f64,
-                                        phase_s: 0f64
-               });
+               .output_signal(1, Signal::sinusoid(1f64, 20f64, 0f64));
+```
+
+The sinusoid is sampled at the updates frequency of the actor, derived from the model sampling frequency,
+unless its sampling frequency is set; the actor fails to start if neither of them is known
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::prelude::*;
+#[derive(UID)]
+enum Sine {};
+let mut source: Initiator<_> = Signals::new(1, 4)
+    .signals(Signal::sinusoid(1f64, 250f64, 0f64))
+    .into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+source.add_output().build::<Sine>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sink)])
+    .sampling_frequency(1e3)
+    .check()?
+    .run()
+    .await?;
+let sine: Vec<_> = logging.lock().await.iter().map(|x| x.round()).collect();
+assert_eq!(sine, vec![0., 1., 0., -1.]);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
 ```
 ## Rate transitionner
 
@@ -129,6 +148,11 @@ pub use smooth::{Smooth, Weight};
 mod average;
 #[doc(inline)]
 pub use average::Average;
+pub mod transition;
+#[doc(inline)]
+pub use transition::{Averaging, Decimation, SampleAndHold};
+#[cfg(feature = "bridge")]
+pub mod bridge;
 
 #[derive(Debug)]
 pub(crate) struct ProgressBar {
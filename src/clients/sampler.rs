@@ -42,3 +42,18 @@ impl<T: Clone, U: UniqueIdentifier<Data = T>, V: UniqueIdentifier<Data = T>> Wri
         Some(Arc::new(Data::new((**self.input).clone())))
     }
 }
+#[cfg(feature = "checkpoint")]
+impl<T, U, V> crate::checkpoint::Checkpoint for Sampler<T, U, V>
+where
+    T: Clone + crate::io::Record,
+    U: UniqueIdentifier<Data = T>,
+    V: UniqueIdentifier<Data = T>,
+{
+    type State = T;
+    fn state(&self) -> Self::State {
+        (**self.input).clone()
+    }
+    fn restore(&mut self, input: Self::State) {
+        self.input = Arc::new(Data::new(input));
+    }
+}
@@ -1,7 +1,8 @@
 use super::ProgressBar;
 use crate::{
+    actor::Context,
     io::{Data, UniqueIdentifier, Write},
-    Update,
+    ClientResult, TryUpdate,
 };
 use linya::{Bar, Progress};
 use std::{
@@ -18,18 +19,22 @@ pub enum Signal {
     /// A constant signal
     Constant(f64),
     /// A sinusoidal signal
+    ///
+    /// If the sampling frequency is not set, it is set from the actor [Clock](crate::actor::Clock) when the model starts
     Sinusoid {
         amplitude: f64,
-        sampling_frequency_hz: f64,
+        sampling_frequency_hz: Option<f64>,
         frequency_hz: f64,
         phase_s: f64,
     },
     /// A ramp of the for y=ax+b
     Ramp { a: f64, b: f64 },
     /// A sigmoid
+    ///
+    /// If the sampling frequency is not set, it is set from the actor [Clock](crate::actor::Clock) when the model starts
     Sigmoid {
         amplitude: f64,
-        sampling_frequency_hz: f64,
+        sampling_frequency_hz: Option<f64>,
     },
     /// White noise
     #[cfg(feature = "noise")]
@@ -62,7 +67,27 @@ impl Signal {
     }
 }
 impl Signal {
+    /// Creates a sinusoidal signal sampled at the actor updates frequency
+    pub fn sinusoid(amplitude: f64, frequency_hz: f64, phase_s: f64) -> Self {
+        Signal::Sinusoid {
+            amplitude,
+            sampling_frequency_hz: None,
+            frequency_hz,
+            phase_s,
+        }
+    }
+    /// Creates a sigmoid signal sampled at the actor updates frequency
+    pub fn sigmoid(amplitude: f64) -> Self {
+        Signal::Sigmoid {
+            amplitude,
+            sampling_frequency_hz: None,
+        }
+    }
     /// Returns the signal value at step `i`
+    ///
+    /// # Panics
+    ///
+    /// Panics if the sampling frequency of a sinusoid or of a sigmoid is not set
     pub fn get(&self, i: usize) -> f64 {
         use Signal::*;
         match self {
@@ -75,8 +100,8 @@ impl Signal {
             } => {
                 (2f64
                     * std::f64::consts::PI
-                    * (phase_s + i as f64 * frequency_hz / sampling_frequency_hz))
-                    .sin()
+                    * (phase_s + i as f64 * frequency_hz / Self::fs(sampling_frequency_hz)))
+                .sin()
                     * amplitude
             }
             Ramp { a, b } => a * i as f64 + b,
@@ -84,7 +109,7 @@ impl Signal {
                 amplitude,
                 sampling_frequency_hz,
             } => {
-                let u = i as f64 / sampling_frequency_hz - 0.75;
+                let u = i as f64 / Self::fs(sampling_frequency_hz) - 0.75;
                 let r = (1. + (-5. * u).exp()).recip();
                 amplitude * r * r
             }
@@ -161,6 +186,49 @@ impl From<(&[f64], usize)> for Signals {
     }
 }
 
+impl Signal {
+    fn fs(sampling_frequency_hz: &Option<f64>) -> f64 {
+        sampling_frequency_hz.expect("the signal sampling frequency is not set")
+    }
+    /// Sets the sampling frequency of the signal to the actor updates frequency, if it is not set
+    ///
+    /// Returns an error if neither of them is known
+    fn set_sampling_frequency(&mut self, update_frequency: Option<f64>) -> ClientResult<()> {
+        match self {
+            Signal::Sinusoid {
+                sampling_frequency_hz,
+                ..
+            }
+            | Signal::Sigmoid {
+                sampling_frequency_hz,
+                ..
+            } => match (*sampling_frequency_hz, update_frequency) {
+                (Some(fs), Some(update_frequency))
+                    if (fs - update_frequency).abs() > f64::EPSILON * update_frequency =>
+                {
+                    log::warn!(
+                        "signal sampling frequency ({fs}Hz) differs from the actor updates frequency ({update_frequency}Hz)"
+                    );
+                    Ok(())
+                }
+                (Some(_), _) => Ok(()),
+                (None, Some(update_frequency)) => {
+                    *sampling_frequency_hz = Some(update_frequency);
+                    Ok(())
+                }
+                (None, None) => Err(
+                    "the signal sampling frequency is not set and the model sampling frequency is not known"
+                        .into(),
+                ),
+            },
+            Signal::Composite(signals) => signals
+                .iter_mut()
+                .try_for_each(|signal| signal.set_sampling_frequency(update_frequency)),
+            _ => Ok(()),
+        }
+    }
+}
+
 impl Add for Signal {
     type Output = Signal;
 
@@ -174,11 +242,18 @@ impl Add for Signal {
     }
 }
 
-impl Update for Signals {
-    fn update(&mut self) {
+impl TryUpdate for Signals {
+    fn on_start(&mut self, context: &Context) -> ClientResult<()> {
+        let fs = context.clock.update_frequency();
+        self.signals
+            .iter_mut()
+            .try_for_each(|signal| signal.set_sampling_frequency(fs))
+    }
+    fn try_update(&mut self) -> ClientResult<()> {
         if let Some(pb) = self.progress_bar.as_mut() {
             pb.progress.lock().unwrap().inc_and_draw(&pb.bar, 1)
         }
+        Ok(())
     }
 }
 impl<U: UniqueIdentifier<Data = Vec<f64>>> Write<U> for Signals {
@@ -194,6 +269,16 @@ impl<U: UniqueIdentifier<Data = Vec<f64>>> Write<U> for Signals {
         }
     }
 }
+#[cfg(feature = "checkpoint")]
+impl crate::checkpoint::Checkpoint for Signals {
+    type State = usize;
+    fn state(&self) -> Self::State {
+        self.step
+    }
+    fn restore(&mut self, step: Self::State) {
+        self.step = step;
+    }
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum SignalsError {
@@ -220,11 +305,16 @@ impl From<Signals> for Result<OneSignal, SignalsError> {
         }
     }
 }
-impl Update for OneSignal {
-    fn update(&mut self) {
+impl TryUpdate for OneSignal {
+    fn on_start(&mut self, context: &Context) -> ClientResult<()> {
+        self.signal
+            .set_sampling_frequency(context.clock.update_frequency())
+    }
+    fn try_update(&mut self) -> ClientResult<()> {
         if let Some(pb) = self.progress_bar.as_mut() {
             pb.progress.lock().unwrap().inc_and_draw(&pb.bar, 1)
         }
+        Ok(())
     }
 }
 impl<U: UniqueIdentifier<Data = f64>> Write<U> for OneSignal {
@@ -239,3 +329,79 @@ impl<U: UniqueIdentifier<Data = f64>> Write<U> for OneSignal {
         }
     }
 }
+#[cfg(feature = "checkpoint")]
+impl crate::checkpoint::Checkpoint for OneSignal {
+    type State = usize;
+    fn state(&self) -> Self::State {
+        self.step
+    }
+    fn restore(&mut self, step: Self::State) {
+        self.step = step;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::Clock;
+
+    /// Returns the context of an actor updated every 2 samples at 1kHz
+    fn context() -> Context {
+        Context {
+            clock: Clock::new(Some(1e3), 2),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn unset_sampling_frequency() {
+        let mut signals = Signals::new(1, 10).signals(Signal::sinusoid(1., 125., 0.));
+        TryUpdate::on_start(&mut signals, &context()).unwrap();
+        assert!(matches!(
+            signals.signals[0],
+            Signal::Sinusoid {
+                sampling_frequency_hz: Some(fs),
+                ..
+            } if fs == 500.
+        ));
+        assert!((signals.signals[0].get(1) - 1.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn set_sampling_frequency() {
+        let signal = Signal::Sigmoid {
+            amplitude: 1.,
+            sampling_frequency_hz: Some(1e3),
+        };
+        let mut signals = Signals::new(1, 10).signals(signal + Signal::sigmoid(1.));
+        TryUpdate::on_start(&mut signals, &context()).unwrap();
+        let Signal::Composite(signals) = &signals.signals[0] else {
+            panic!("expected a composite signal")
+        };
+        let fs: Vec<_> = signals
+            .iter()
+            .map(|signal| match signal {
+                Signal::Sigmoid {
+                    sampling_frequency_hz,
+                    ..
+                } => *sampling_frequency_hz,
+                _ => panic!("expected a sigmoid"),
+            })
+            .collect();
+        assert_eq!(fs, vec![Some(1e3), Some(500.)]);
+    }
+
+    #[test]
+    fn unknown_sampling_frequency() {
+        let mut signals = Signals::new(1, 10).signals(Signal::sinusoid(1., 125., 0.));
+        assert!(TryUpdate::on_start(&mut signals, &Context::default()).is_err());
+        let mut signals = Signals::new(1, 10).signals(Signal::Sinusoid {
+            amplitude: 1.,
+            sampling_frequency_hz: Some(1e3),
+            frequency_hz: 250.,
+            phase_s: 0.,
+        });
+        TryUpdate::on_start(&mut signals, &Context::default()).unwrap();
+        assert!((signals.signals[0].get(1) - 1.).abs() < 1e-12);
+    }
+}
@@ -45,3 +45,15 @@ impl<U: UniqueIdentifier<Data = Vec<f64>>> Write<U> for Smooth {
         Some(Arc::new(Data::new(y)))
     }
 }
+#[cfg(feature = "checkpoint")]
+impl crate::checkpoint::Checkpoint for Smooth {
+    type State = (f64, Vec<f64>, Option<Vec<f64>>);
+    fn state(&self) -> Self::State {
+        (self.weight, self.data.clone(), self.data0.clone())
+    }
+    fn restore(&mut self, (weight, data, data0): Self::State) {
+        self.weight = weight;
+        self.data = data;
+        self.data0 = data0;
+    }
+}
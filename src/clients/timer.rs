@@ -46,6 +46,16 @@ impl Update for Timer {
         self.tick -= 1;
     }
 }
+#[cfg(feature = "checkpoint")]
+impl crate::checkpoint::Checkpoint for Timer {
+    type State = usize;
+    fn state(&self) -> Self::State {
+        self.tick
+    }
+    fn restore(&mut self, tick: Self::State) {
+        self.tick = tick;
+    }
+}
 pub enum Tick {}
 pub type Void = ();
 impl UniqueIdentifier for Tick {
@@ -0,0 +1,171 @@
+/*!
+# Rate transition policies
+
+The policies of the rate transition actors inserted by [into_input_with](crate::IntoInputs::into_input_with)
+between an output and an input sampled at different rates:
+ - [SampleAndHold] upsamples the output, holding each sample until the next one,
+ - [Decimation] downsamples the output, keeping the last sample,
+ - [Averaging] downsamples the output, averaging the samples.
+
+The [model check](crate::model::Model::check) fails if a policy is used in the wrong direction,
+e.g. [SampleAndHold] between a 1kHz output and a 100Hz input.
+
+A 1kHz source is averaged down to the 100Hz sampling rate of a logger:
+```
+use gmt_dos_actors::{clients::Averaging, prelude::*};
+# tokio_test::block_on(async {
+#[derive(UID)]
+enum Ramp {};
+let mut source: Initiator<_> = Signals::new(1, 100)
+    .signals(Signal::Ramp { a: 1., b: 0. })
+    .into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_, 10>::new(logging.clone());
+source
+    .add_output()
+    .build::<Ramp>()
+    .into_input_with(&mut sink, Averaging);
+let model = Model::new(vec![Box::new(source), Box::new(sink)]).check()?;
+assert_eq!(model.n_actors(), 3);
+assert!(model.graph().unwrap().to_string().contains("Averaging (1:10)"));
+model.run().await?;
+assert_eq!(*logging.lock().await.as_slice(), [4.5, 14.5, 24.5, 34.5, 44.5, 54.5, 64.5, 74.5, 84.5, 94.5]);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+
+use super::{Average, Sampler};
+use crate::{io::UniqueIdentifier, RateTransition};
+use std::ops::{AddAssign, DivAssign};
+
+/// Sample-and-hold rate transition policy
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SampleAndHold;
+impl<T, U> RateTransition<U> for SampleAndHold
+where
+    T: 'static + Default + Clone + Send + Sync,
+    U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
+{
+    type Client = Sampler<T, U>;
+    fn name(&self) -> &str {
+        "SampleAndHold"
+    }
+    fn client(self) -> Self::Client {
+        Sampler::default()
+    }
+    /// Accepts upsampling only
+    fn accepts(&self, rate: usize, inputs_rate: usize) -> bool {
+        rate > inputs_rate
+    }
+}
+
+/// Decimation rate transition policy
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Decimation;
+impl<T, U> RateTransition<U> for Decimation
+where
+    T: 'static + Default + Clone + Send + Sync,
+    U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
+{
+    type Client = Sampler<T, U>;
+    fn name(&self) -> &str {
+        "Decimation"
+    }
+    fn client(self) -> Self::Client {
+        Sampler::default()
+    }
+    /// Accepts downsampling only
+    fn accepts(&self, rate: usize, inputs_rate: usize) -> bool {
+        rate < inputs_rate
+    }
+}
+
+/// Averaging rate transition policy
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Averaging;
+impl<T, U> RateTransition<U> for Averaging
+where
+    T: 'static + Default + Copy + AddAssign + DivAssign + TryFrom<u32> + Send + Sync,
+    U: 'static + Send + Sync + UniqueIdentifier<Data = Vec<T>>,
+{
+    type Client = Average<T, U>;
+    fn name(&self) -> &str {
+        "Averaging"
+    }
+    fn client(self) -> Self::Client {
+        Average::new(0)
+    }
+    /// Accepts downsampling only
+    fn accepts(&self, rate: usize, inputs_rate: usize) -> bool {
+        rate < inputs_rate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model::ModelError, prelude::*, ActorError};
+
+    #[derive(UID)]
+    enum Ramp {}
+
+    /// Returns the ramp logged at the inputs rate `NI` of the logger
+    async fn transition<P: RateTransition<Ramp>, const NO: usize, const NI: usize>(
+        policy: P,
+    ) -> Vec<f64> {
+        let mut source: Initiator<_, NO> = Signals::new(1, 20)
+            .signals(Signal::Ramp { a: 1., b: 0. })
+            .into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_, NI>::new(logging.clone());
+        source
+            .add_output()
+            .build::<Ramp>()
+            .into_input_with(&mut sink, policy);
+        Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        let data = logging.lock().await.to_vec();
+        data
+    }
+
+    #[tokio::test]
+    async fn decimation() {
+        let data = transition::<_, 1, 5>(Decimation).await;
+        assert_eq!(data, [4., 9., 14., 19.]);
+    }
+
+    #[tokio::test]
+    async fn sample_and_hold() {
+        let data = transition::<_, 5, 1>(SampleAndHold).await;
+        assert_eq!(data.len(), 100);
+        assert!(data.iter().zip(0..).all(|(x, i)| *x == (i / 5) as f64));
+    }
+
+    #[test]
+    fn wrong_direction() {
+        fn check<P: RateTransition<Ramp>, const NO: usize, const NI: usize>(policy: P) -> bool {
+            let mut source: Initiator<_, NO> = Signals::new(1, 20).into();
+            let mut sink = Terminator::<_, NI>::new(Logging::<f64>::default().into_arcx());
+            source
+                .add_output()
+                .build::<Ramp>()
+                .into_input_with(&mut sink, policy);
+            match Model::new(vec![Box::new(source), Box::new(sink)]).check() {
+                Err(ModelError::ActorIO(ActorError::InvalidRateTransition(_))) => false,
+                Err(e) => panic!("unexpected error: {e}"),
+                Ok(_) => true,
+            }
+        }
+        assert!(check::<_, 5, 1>(SampleAndHold));
+        assert!(!check::<_, 1, 5>(SampleAndHold));
+        assert!(check::<_, 1, 5>(Decimation));
+        assert!(!check::<_, 5, 1>(Decimation));
+        assert!(check::<_, 1, 5>(Averaging));
+        assert!(!check::<_, 5, 1>(Averaging));
+    }
+}
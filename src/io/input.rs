@@ -1,40 +1,88 @@
-use super::{Read, S};
-use crate::{Result, UniqueIdentifier, Who};
+#[cfg(feature = "checkpoint")]
+use super::Codec;
+use super::{AsyncRead, Batch, S};
+use crate::{ActorError, Result, UniqueIdentifier, Who};
 use async_trait::async_trait;
-use flume::Receiver;
-use std::{fmt::Display, sync::Arc};
-use tokio::sync::Mutex;
+use flume::{r#async::RecvFut, Receiver, TryRecvError};
+use std::{
+    any::Any,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 /// [Actor](crate::Actor)s input
-pub(crate) struct Input<C, T, U, const N: usize>
+///
+/// The data is received without locking the client and read by the client afterwards,
+/// the samples of a batch are read one at a time
+pub(crate) struct Input<T, U>
 where
-    U: UniqueIdentifier<Data = T>,
-    C: Read<U>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
-    rx: Receiver<S<U>>,
-    client: Arc<Mutex<C>>,
+    rx: Receiver<Batch<U>>,
+    pending: Option<RecvFut<'static, Batch<U>>>,
+    batch: Option<(Batch<U>, usize)>,
+    data: Option<S<U>>,
     hash: u64,
+    rate: usize,
+    #[cfg(feature = "checkpoint")]
+    codec: Option<Codec<T>>,
 }
-impl<C, T, U, const N: usize> Input<C, T, U, N>
+impl<T, U> Input<T, U>
 where
-    U: UniqueIdentifier<Data = T>,
-    C: Read<U>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
-    /// Creates a new intput from a [Receiver], an [Actor] client and an identifier [hash]
-    pub fn new(rx: Receiver<S<U>>, client: Arc<Mutex<C>>, hash: u64) -> Self {
-        Self { rx, client, hash }
+    /// Creates a new intput from a [Receiver], an identifier [hash] and a sampling rate
+    pub fn new(rx: Receiver<Batch<U>>, hash: u64, rate: usize) -> Self {
+        Self {
+            rx,
+            pending: None,
+            batch: None,
+            data: None,
+            hash,
+            rate,
+            #[cfg(feature = "checkpoint")]
+            codec: None,
+        }
+    }
+    /// Sets the [Codec] of the samples saved in [checkpoint](crate::checkpoint)s
+    #[cfg(feature = "checkpoint")]
+    pub fn codec(self, codec: Option<Codec<T>>) -> Self {
+        Self { codec, ..self }
+    }
+    /// Takes the next sample of the batch, if any
+    fn next(&mut self) -> Option<S<U>> {
+        let (batch, i) = self.batch.as_mut()?;
+        match batch.get(*i) {
+            Some(data) => {
+                *i += 1;
+                Some(data)
+            }
+            None => {
+                self.batch = None;
+                None
+            }
+        }
+    }
+    /// Stores the first sample of a new batch
+    fn received(&mut self, batch: Batch<U>) {
+        self.batch = Some((batch, 0));
+        self.data = self.next();
     }
 }
-impl<C, T, U, const N: usize> Who<U> for Input<C, T, U, N>
+impl<T, U> Who<U> for Input<T, U>
 where
-    C: Read<U>,
-    U: UniqueIdentifier<Data = T>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
 }
-impl<C, T, U, const N: usize> Display for Input<C, T, U, N>
+impl<T, U> Display for Input<T, U>
 where
-    C: Read<U>,
-    U: UniqueIdentifier<Data = T>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{:>24}: {}", self.hash, self.who())
@@ -42,29 +90,97 @@ where
 }
 
 #[async_trait]
-pub(crate) trait InputObject: Display + Send + Sync {
+pub(crate) trait InputObject<C>: Display + Send + Sync {
     /// Receives output data
-    async fn recv(&mut self) -> Result<()>;
+    ///
+    /// Returns [Poll::Ready] once the data has been received
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>>;
+    /// Reads the received data into a synchronous client
+    ///
+    /// Returns `None` if the client must be awaited with [read](InputObject::read)
+    fn read_now(&mut self, client: &mut C) -> Option<Result<()>>;
+    /// Reads the received data into the client
+    async fn read(&mut self, client: &mut C) -> Result<()>;
     /// Returns the input UID
     fn who(&self) -> String;
     /// Gets the input hash
     fn get_hash(&self) -> u64;
+    /// Checks if [recv](InputObject::recv) returns without waiting
+    fn is_ready(&self) -> bool;
+    /// Returns the input sampling rate
+    fn rate(&self) -> usize;
+    /// Returns the input, with its channel, to be taken over by another actor
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send>;
+    /// Replaces the input by another `input` of the same data, connected to another output
+    ///
+    /// The other input is given back if it does not receive the same data
+    fn splice(&mut self, input: Box<dyn Any + Send>) -> Option<Box<dyn Any + Send>>;
+    /// Encodes the samples received and not yet read by the client
+    ///
+    /// The samples waiting in the channel are moved into the input, so they are still read once the actor resumes.
+    /// Returns an error if there are samples but the output is not built with [build_recorded](crate::AddOuput::build_recorded)
+    #[cfg(feature = "checkpoint")]
+    fn save(&mut self) -> crate::checkpoint::Result<Vec<Vec<u8>>>;
+    /// Decodes [saved](InputObject::save) samples, to be read before the samples received from the channel
+    #[cfg(feature = "checkpoint")]
+    fn restore(&mut self, samples: &[Vec<u8>]) -> crate::checkpoint::Result<()>;
 }
 
 #[async_trait]
-impl<C, T, U, const N: usize> InputObject for Input<C, T, U, N>
+impl<C, T, U> InputObject<C> for Input<T, U>
 where
-    C: Read<U> + Send,
-    T: Send + Sync,
-    U: Send + Sync + UniqueIdentifier<Data = T>,
+    C: AsyncRead<U>,
+    T: 'static + Send + Sync,
+    U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
 {
-    async fn recv(&mut self) -> Result<()> {
-        log::debug!("{} receiving", Who::who(self));
-        log::debug!("{} receiving (locking client)", Who::who(self));
-        let mut client = self.client.lock().await;
-        log::debug!("{} receiving (client locked)", Who::who(self));
-        (*client).read(self.rx.recv_async().await?);
-        log::debug!("{} received", Who::who(self));
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<()>> {
+        if self.data.is_some() {
+            return Poll::Ready(Ok(()));
+        }
+        if let Some(data) = self.next() {
+            self.data = Some(data);
+            return Poll::Ready(Ok(()));
+        }
+        let pending = match self.pending.as_mut() {
+            Some(pending) => pending,
+            None => match self.rx.try_recv() {
+                Ok(batch) => {
+                    self.received(batch);
+                    return Poll::Ready(Ok(()));
+                }
+                Err(TryRecvError::Disconnected) => {
+                    return Poll::Ready(Err(flume::RecvError::Disconnected.into()))
+                }
+                Err(TryRecvError::Empty) => {
+                    log::debug!("{} waiting", Who::who(self));
+                    self.pending.insert(self.rx.clone().into_recv_async())
+                }
+            },
+        };
+        let batch = std::task::ready!(Pin::new(pending).poll(cx));
+        self.pending = None;
+        Poll::Ready(
+            batch
+                .map(|batch| self.received(batch))
+                .map_err(ActorError::from),
+        )
+    }
+    fn read_now(&mut self, client: &mut C) -> Option<Result<()>> {
+        let data = self.data.take()?;
+        match client.read_now(data) {
+            Ok(result) => Some(result.map_err(ActorError::client::<C>)),
+            Err(data) => {
+                self.data = Some(data);
+                None
+            }
+        }
+    }
+    async fn read(&mut self, client: &mut C) -> Result<()> {
+        if let Some(data) = self.data.take() {
+            AsyncRead::read(client, data)
+                .await
+                .map_err(ActorError::client::<C>)?;
+        }
         Ok(())
     }
     fn who(&self) -> String {
@@ -73,4 +189,68 @@ where
     fn get_hash(&self) -> u64 {
         self.hash
     }
+    fn is_ready(&self) -> bool {
+        self.data.is_some()
+            || self
+                .batch
+                .as_ref()
+                .is_some_and(|(batch, i)| *i < batch.len())
+            || !self.rx.is_empty()
+            || self.rx.is_disconnected()
+    }
+    fn rate(&self) -> usize {
+        self.rate
+    }
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send> {
+        self
+    }
+    fn splice(&mut self, input: Box<dyn Any + Send>) -> Option<Box<dyn Any + Send>> {
+        match input.downcast::<Self>() {
+            Ok(input) => {
+                *self = *input;
+                None
+            }
+            Err(input) => Some(input),
+        }
+    }
+    #[cfg(feature = "checkpoint")]
+    fn save(&mut self) -> crate::checkpoint::Result<Vec<Vec<u8>>> {
+        let mut samples: Vec<S<U>> = self.data.take().into_iter().collect();
+        while let Some(data) = self.next() {
+            samples.push(data);
+        }
+        while let Ok(batch) = self.rx.try_recv() {
+            samples.extend((0..batch.len()).filter_map(|i| batch.get(i)));
+        }
+        let bytes = match (self.codec, samples.is_empty()) {
+            (_, true) => Ok(vec![]),
+            (Some(codec), false) => samples
+                .iter()
+                .map(|data| (codec.encode)(data))
+                .collect::<bincode::Result<Vec<_>>>()
+                .map_err(Into::into),
+            (None, false) => Err(crate::checkpoint::CheckpointError::Unrecorded(Who::who(
+                self,
+            ))),
+        };
+        if !samples.is_empty() {
+            self.batch = Some((Batch::drain(&mut samples), 0));
+        }
+        bytes
+    }
+    #[cfg(feature = "checkpoint")]
+    fn restore(&mut self, samples: &[Vec<u8>]) -> crate::checkpoint::Result<()> {
+        if samples.is_empty() {
+            return Ok(());
+        }
+        let codec = self
+            .codec
+            .ok_or_else(|| crate::checkpoint::CheckpointError::Unrecorded(Who::who(self)))?;
+        let mut samples = samples
+            .iter()
+            .map(|bytes| (codec.decode)(bytes).map(|data| S::new(super::Data::new(data))))
+            .collect::<bincode::Result<Vec<S<U>>>>()?;
+        self.batch = Some((Batch::drain(&mut samples), 0));
+        Ok(())
+    }
 }
@@ -9,23 +9,30 @@ Each input and output has a reference to the [Actor] client that reads data from
  the input and write data to the output only if the client implements the [Read]
 and [Write] traits.
 
+A [Write] client can reuse the data it has already sent with a [Recycler].
+
 [Actor]: crate::Actor
 [bounded]: https://docs.rs/flume/latest/flume/fn.bounded
 [unbounded]: https://docs.rs/flume/latest/flume/fn.unbounded
 */
 
-use crate::Who;
+use crate::{ClientResult, Who};
+use async_trait::async_trait;
 use std::{
     fmt,
+    future::Future,
     marker::PhantomData,
     ops::{Deref, DerefMut},
+    pin::Pin,
     sync::Arc,
 };
 
 mod input;
 pub(crate) use input::{Input, InputObject};
 mod output;
-pub(crate) use output::{Output, OutputObject};
+pub(crate) use output::{Output, OutputBuilder, OutputObject};
+mod recycle;
+pub use recycle::Recycler;
 
 pub(crate) type Assoc<U> = <U as UniqueIdentifier>::Data;
 
@@ -117,6 +124,84 @@ impl<T: Default, U: UniqueIdentifier<Data = Vec<T>>> Default for Data<U> {
 
 pub(crate) type S<U> = Arc<Data<U>>;
 
+/// Consecutive samples of output data sent at once through a channel
+///
+/// An output sends a batch of a single sample unless it is [batch](crate::AddOuput::batch)ed
+pub struct Batch<U: UniqueIdentifier>(Samples<U>);
+enum Samples<U: UniqueIdentifier> {
+    One(S<U>),
+    Many(Arc<[S<U>]>),
+}
+impl<U: UniqueIdentifier> Batch<U> {
+    /// Creates a batch from the queued samples, emptying the queue
+    pub(crate) fn drain(queue: &mut Vec<S<U>>) -> Self {
+        if queue.len() == 1 {
+            Self(Samples::One(queue.remove(0)))
+        } else {
+            Self(Samples::Many(queue.drain(..).collect()))
+        }
+    }
+    /// Returns the number of samples in the batch
+    pub fn len(&self) -> usize {
+        match &self.0 {
+            Samples::One(_) => 1,
+            Samples::Many(samples) => samples.len(),
+        }
+    }
+    /// Checks if the batch has no samples
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+    /// Returns the sample #`i` of the batch
+    pub fn get(&self, i: usize) -> Option<S<U>> {
+        match &self.0 {
+            Samples::One(data) => (i == 0).then(|| data.clone()),
+            Samples::Many(samples) => samples.get(i).cloned(),
+        }
+    }
+}
+impl<U: UniqueIdentifier> Clone for Batch<U> {
+    fn clone(&self) -> Self {
+        match &self.0 {
+            Samples::One(data) => Self(Samples::One(data.clone())),
+            Samples::Many(samples) => Self(Samples::Many(samples.clone())),
+        }
+    }
+}
+
+/// Output data that can be [recorded](crate::recorder) or [checkpointed](crate::checkpoint)
+///
+/// The data of the outputs built with [build_recorded](crate::AddOuput::build_recorded) must be serializable
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+pub trait Record: serde::Serialize + serde::de::DeserializeOwned {}
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+impl<T: serde::Serialize + serde::de::DeserializeOwned> Record for T {}
+
+/// Encoder and decoder of the data of an output built with [build_recorded](crate::AddOuput::build_recorded)
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+pub(crate) struct Codec<T> {
+    pub encode: fn(&T) -> bincode::Result<Vec<u8>>,
+    #[cfg_attr(not(feature = "checkpoint"), allow(dead_code))]
+    pub decode: fn(&[u8]) -> bincode::Result<T>,
+}
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+impl<T: Record> Codec<T> {
+    pub fn new() -> Self {
+        Self {
+            encode: |data| bincode::serialize(data),
+            decode: |bytes| bincode::deserialize(bytes),
+        }
+    }
+}
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+impl<T> Clone for Codec<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+impl<T> Copy for Codec<T> {}
+
 /// Client input data reader interface
 pub trait Read<U: UniqueIdentifier> {
     /// Read data from an input
@@ -126,16 +211,163 @@ pub trait Read<U: UniqueIdentifier> {
 pub trait Write<U: UniqueIdentifier> {
     fn write(&mut self) -> Option<Arc<Data<U>>>;
 }
+/// Client fallible input data reader interface
+///
+/// Every [Read] client is also a [TryRead] client
+pub trait TryRead<U: UniqueIdentifier> {
+    /// Read data from an input
+    fn try_read(&mut self, data: Arc<Data<U>>) -> ClientResult<()>;
+}
+impl<T: Read<U>, U: UniqueIdentifier> TryRead<U> for T {
+    fn try_read(&mut self, data: Arc<Data<U>>) -> ClientResult<()> {
+        self.read(data);
+        Ok(())
+    }
+}
+/// Client fallible output data writer interface
+///
+/// Every [Write] client is also a [TryWrite] client
+pub trait TryWrite<U: UniqueIdentifier> {
+    fn try_write(&mut self) -> ClientResult<Option<Arc<Data<U>>>>;
+}
+impl<T: Write<U>, U: UniqueIdentifier> TryWrite<U> for T {
+    fn try_write(&mut self) -> ClientResult<Option<Arc<Data<U>>>> {
+        Ok(self.write())
+    }
+}
+/// Client asynchronous input data reader interface
+///
+/// Every [TryRead] client is also an [AsyncRead] client
+#[async_trait]
+pub trait AsyncRead<U: UniqueIdentifier>: Send {
+    /// Read data from an input
+    async fn read(&mut self, data: Arc<Data<U>>) -> ClientResult<()>;
+    /// Reads data from an input without awaiting if the client is synchronous
+    ///
+    /// The data is given back if the client must be awaited, which is the default
+    fn read_now(&mut self, data: Arc<Data<U>>) -> Result<ClientResult<()>, Arc<Data<U>>> {
+        Err(data)
+    }
+}
+// `#[async_trait]` cannot be used here: it moves the data into the returned `Send` future,
+// which would require `U::Data: Send + Sync`, a bound the synchronous readers don't have.
+// The expansion is written out instead, with the data read before the future is created.
+impl<T: TryRead<U> + Send, U: UniqueIdentifier> AsyncRead<U> for T {
+    fn read_now(&mut self, data: Arc<Data<U>>) -> Result<ClientResult<()>, Arc<Data<U>>> {
+        Ok(self.try_read(data))
+    }
+    fn read<'life0, 'async_trait>(
+        &'life0 mut self,
+        data: Arc<Data<U>>,
+    ) -> Pin<Box<dyn Future<Output = ClientResult<()>> + Send + 'async_trait>>
+    where
+        'life0: 'async_trait,
+        Self: 'async_trait,
+    {
+        Box::pin(std::future::ready(self.try_read(data)))
+    }
+}
+/// Client asynchronous output data writer interface
+///
+/// Every [TryWrite] client is also an [AsyncWrite] client
+#[async_trait]
+pub trait AsyncWrite<U: UniqueIdentifier>: Send {
+    async fn write(&mut self) -> ClientResult<Option<Arc<Data<U>>>>;
+    /// Writes data to an output without awaiting if the client is synchronous
+    ///
+    /// Returns `None` if the client must be awaited, which is the default
+    fn write_now(&mut self) -> Option<ClientResult<Option<Arc<Data<U>>>>> {
+        None
+    }
+}
+#[async_trait]
+impl<T: TryWrite<U> + Send, U: UniqueIdentifier> AsyncWrite<U> for T {
+    async fn write(&mut self) -> ClientResult<Option<Arc<Data<U>>>> {
+        self.try_write()
+    }
+    fn write_now(&mut self) -> Option<ClientResult<Option<Arc<Data<U>>>>> {
+        Some(self.try_write())
+    }
+}
 
 #[cfg(test)]
 mod tests {
+    use super::{AsyncRead, AsyncWrite, Data, TryRead, TryWrite};
     use crate as uid;
+    use crate::{ClientResult, UniqueIdentifier};
+    use std::sync::Arc;
     use uid_derive::UID;
 
     #[derive(UID)]
     #[uid(data = "u8")]
     pub enum A {}
 
+    /// Client reading and writing the data of `A`
+    #[derive(Default)]
+    struct Echo(Option<u8>);
+    impl super::Read<A> for Echo {
+        fn read(&mut self, data: Arc<Data<A>>) {
+            self.0 = Some(**data);
+        }
+    }
+    impl super::Write<A> for Echo {
+        fn write(&mut self) -> Option<Arc<Data<A>>> {
+            self.0.take().map(|x| Arc::new(Data::new(x)))
+        }
+    }
+
+    #[tokio::test]
+    async fn read_write_chain() {
+        let mut echo = Echo::default();
+        assert!(TryRead::<A>::try_read(&mut echo, Arc::new(Data::new(1))).is_ok());
+        assert_eq!(**TryWrite::<A>::try_write(&mut echo).unwrap().unwrap(), 1);
+        // the synchronous clients are never awaited
+        assert!(AsyncRead::<A>::read_now(&mut echo, Arc::new(Data::new(2))).is_ok());
+        let data = AsyncWrite::<A>::write_now(&mut echo).unwrap().unwrap();
+        assert_eq!(**data.unwrap(), 2);
+        AsyncRead::<A>::read(&mut echo, Arc::new(Data::new(3)))
+            .await
+            .unwrap();
+        let data = AsyncWrite::<A>::write(&mut echo).await.unwrap();
+        assert_eq!(**data.unwrap(), 3);
+        assert!(AsyncWrite::<A>::write(&mut echo).await.unwrap().is_none());
+    }
+
+    #[derive(UID)]
+    #[uid(data = "std::rc::Rc<f64>")]
+    enum Shared {}
+
+    /// Fallible client reading data that is neither `Send` nor `Sync`
+    #[derive(Default)]
+    struct Positive(f64);
+    impl TryRead<Shared> for Positive {
+        fn try_read(&mut self, data: Arc<Data<Shared>>) -> ClientResult<()> {
+            if ***data < 0. {
+                return Err("negative data".into());
+            }
+            self.0 = ***data;
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn try_read_chain() {
+        let mut client = Positive::default();
+        let data = |x: f64| Arc::new(Data::<Shared>::new(std::rc::Rc::new(x)));
+        AsyncRead::<Shared>::read(&mut client, data(1.))
+            .await
+            .unwrap();
+        assert_eq!(client.0, 1.);
+        assert!(matches!(
+            AsyncRead::<Shared>::read_now(&mut client, data(-1.)),
+            Ok(Err(_))
+        ));
+        assert!(AsyncRead::<Shared>::read(&mut client, data(-1.))
+            .await
+            .is_err());
+        assert_eq!(client.0, 1.);
+    }
+
     #[test]
     fn impl_uid() {
         enum U {}
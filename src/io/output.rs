@@ -1,82 +1,158 @@
-use super::{Assoc, Write, S};
-use crate::{ActorError, Result, UniqueIdentifier, Who};
+use super::{Assoc, AsyncWrite, Batch, S};
+use crate::{ActorError, ClientResult, Result, UniqueIdentifier, Who};
 use async_trait::async_trait;
-use flume::Sender;
-use futures::future::join_all;
-use std::{fmt::Display, sync::Arc};
-use tokio::sync::Mutex;
+use flume::{r#async::SendFut, Sender, TrySendError};
+use std::{
+    any::Any,
+    fmt::Display,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
-pub(crate) struct OutputBuilder<C, T, U, const N: usize>
+#[cfg(any(feature = "recorder", feature = "checkpoint"))]
+use super::Codec;
+#[cfg(feature = "recorder")]
+use crate::recorder::Recorder;
+
+pub(crate) struct OutputBuilder<T, U>
 where
-    U: UniqueIdentifier<Data = T>,
-    C: Write<U>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
-    tx: Vec<Sender<S<U>>>,
-    client: Arc<Mutex<C>>,
+    tx: Vec<Sender<Batch<U>>>,
     bootstrap: bool,
+    rate: usize,
+    batch: usize,
+    #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+    codec: Option<Codec<T>>,
 }
-impl<C, T, U, const N: usize> OutputBuilder<C, T, U, N>
+impl<T, U> OutputBuilder<T, U>
 where
-    U: UniqueIdentifier<Data = T>,
-    C: Write<U>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
-    pub fn new(client: Arc<Mutex<C>>) -> Self {
+    pub fn new() -> Self {
         Self {
             tx: Vec::new(),
-            client,
             bootstrap: false,
+            rate: 1,
+            batch: 1,
+            #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+            codec: None,
         }
     }
-    pub fn senders(self, tx: Vec<Sender<S<U>>>) -> Self {
+    pub fn senders(self, tx: Vec<Sender<Batch<U>>>) -> Self {
         Self { tx, ..self }
     }
     pub fn bootstrap(self, bootstrap: bool) -> Self {
         Self { bootstrap, ..self }
     }
-    pub fn build(self) -> Output<C, T, U, N> {
+    pub fn rate(self, rate: usize) -> Self {
+        Self { rate, ..self }
+    }
+    pub fn batch(self, batch: usize) -> Self {
+        Self {
+            batch: batch.max(1),
+            ..self
+        }
+    }
+    #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+    pub fn codec(self, codec: Codec<T>) -> Self {
+        Self {
+            codec: Some(codec),
+            ..self
+        }
+    }
+    pub fn build(self) -> Output<T, U> {
         Output {
             data: None,
+            unsent: false,
+            queue: Vec::with_capacity(self.batch),
+            pending: self.tx.iter().map(|_| None).collect(),
             tx: self.tx,
-            client: self.client,
             bootstrap: self.bootstrap,
+            bootstrapped: false,
             hash: 0,
+            rate: self.rate,
+            batch: self.batch,
+            #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+            codec: self.codec,
+            #[cfg(feature = "recorder")]
+            recorder: None,
         }
     }
 }
 
 /// [Actor](crate::Actor)s output
-pub(crate) struct Output<C, T, U, const N: usize>
+///
+/// The data is written by the client and sent afterwards without locking the client,
+/// a batched output queues the data and sends it `batch` samples at a time
+pub(crate) struct Output<T, U>
 where
-    U: UniqueIdentifier<Data = T>,
-    C: Write<U>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
     data: Option<S<U>>,
-    tx: Vec<Sender<S<U>>>,
-    client: Arc<Mutex<C>>,
+    unsent: bool,
+    queue: Vec<S<U>>,
+    tx: Vec<Sender<Batch<U>>>,
+    pending: Vec<Option<SendFut<'static, Batch<U>>>>,
     bootstrap: bool,
+    bootstrapped: bool,
     hash: u64,
+    rate: usize,
+    batch: usize,
+    #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+    codec: Option<Codec<T>>,
+    #[cfg(feature = "recorder")]
+    recorder: Option<Recorder>,
 }
-impl<C, T, U, const N: usize> Output<C, T, U, N>
+impl<T, U> Output<T, U>
 where
-    U: UniqueIdentifier<Data = T>,
-    C: Write<U>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
     /// Creates a new output from a [Sender] and data [Default]
-    pub fn builder(client: Arc<Mutex<C>>) -> OutputBuilder<C, T, U, N> {
-        OutputBuilder::new(client)
+    pub fn builder() -> OutputBuilder<T, U> {
+        OutputBuilder::new()
+    }
+    /// Stores the data written by the client
+    fn written<C>(&mut self, data: ClientResult<Option<S<U>>>) -> Result<()> {
+        self.data = data.map_err(ActorError::client::<C>)?;
+        if self.data.is_some() {
+            self.unsent = true;
+            Ok(())
+        } else {
+            for tx in &self.tx {
+                drop(tx);
+            }
+            Err(ActorError::Disconnected(Who::who(self)))
+        }
+    }
+    /// Releases the data once queued
+    ///
+    /// The data is kept for the recorder only, so the client can recycle it
+    fn release(&mut self) {
+        #[cfg(feature = "recorder")]
+        let keep = self.recorder.is_some();
+        #[cfg(not(feature = "recorder"))]
+        let keep = false;
+        if !keep {
+            self.data = None;
+        }
     }
 }
-impl<C, T, U, const N: usize> Who<U> for Output<C, T, U, N>
+impl<T, U> Who<U> for Output<T, U>
 where
-    C: Write<U>,
-    U: UniqueIdentifier<Data = T>,
+    T: 'static,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
 }
-impl<C, T, U, const N: usize> Display for Output<C, T, U, N>
+impl<T, U> Display for Output<T, U>
 where
-    C: Write<U> + Send,
-    T: Send + Sync,
-    U: UniqueIdentifier<Data = T>,
+    T: 'static + Send + Sync,
+    U: 'static + UniqueIdentifier<Data = T>,
 {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         if self.bootstrap {
@@ -85,59 +161,134 @@ where
                 "{:>24}: {} x{} (bootstrap)",
                 self.hash,
                 Who::who(self),
-                self.len()
+                self.tx.len()
             )
         } else {
-            write!(f, "{:>24}: {} x{}", self.hash, Who::who(self), self.len())
+            write!(
+                f,
+                "{:>24}: {} x{}",
+                self.hash,
+                Who::who(self),
+                self.tx.len()
+            )
         }
     }
 }
 
 #[async_trait]
-pub(crate) trait OutputObject: Display + Send + Sync {
-    async fn send(&mut self) -> Result<()>;
+pub(crate) trait OutputObject<C>: Display + Send + Sync {
+    /// Writes the data of a synchronous client to the output
+    ///
+    /// Returns `None` if the client must be awaited with [write](OutputObject::write)
+    fn write_now(&mut self, client: &mut C) -> Option<Result<()>>;
+    /// Writes the client data to the output
+    async fn write(&mut self, client: &mut C) -> Result<()>;
+    /// Sends the data written to the output
+    ///
+    /// Returns [Poll::Ready] once the data has been sent to all the inputs,
+    /// or queued if the output is batched, the batch is not complete and `flush` is not set
+    fn poll_send(&mut self, cx: &mut Context<'_>, flush: bool) -> Poll<Result<()>>;
     fn bootstrap(&self) -> bool;
+    /// Checks if the bootstrapped samples have been sent by the actor the output was [taken](OutputObject::into_any) from
+    fn bootstrapped(&self) -> bool;
     fn len(&self) -> usize;
     fn who(&self) -> String;
     fn set_hash(&mut self, hash: u64);
     fn get_hash(&self) -> u64;
+    /// Checks if [poll_send](OutputObject::poll_send) returns without waiting
+    fn is_ready(&self) -> bool;
+    /// Returns the smallest capacity of the channels, `None` if unbounded
+    fn capacity(&self) -> Option<usize>;
+    /// Returns the output sampling rate
+    fn rate(&self) -> usize;
+    /// Sets the [Recorder] of the data sent by the output, if the output is recorded
+    #[cfg(feature = "recorder")]
+    fn set_recorder(&mut self, recorder: Recorder);
+    /// Records the last data sent at sample #`step`
+    #[cfg(feature = "recorder")]
+    fn record(&self, step: usize, bootstrap: bool);
+    /// Returns the [Codec] of the output data, if the output is built with [build_recorded](crate::AddOuput::build_recorded)
+    #[cfg(feature = "checkpoint")]
+    fn codec(&self) -> Option<Box<dyn Any + Send>>;
+    /// Removes the channels whose input has been dropped
+    ///
+    /// Returns the number of channels left
+    fn prune(&mut self) -> usize;
+    /// Returns the output, with its channels, to be taken over by another actor
+    fn into_any(self: Box<Self>) -> Box<dyn Any + Send>;
+    /// Sends to the inputs of another `output` of the same data instead
+    ///
+    /// The output takes the bootstrap, rate and batch settings of the other output.
+    /// The other output is given back if it does not send the same data
+    /// or if the output is connected to more than one input
+    fn splice(&mut self, output: Box<dyn Any + Send>) -> Option<Box<dyn Any + Send>>;
 }
 #[async_trait]
-impl<C, T, U, const N: usize> OutputObject for Output<C, T, U, N>
+impl<C, T, U> OutputObject<C> for Output<T, U>
 where
-    C: Write<U> + Send,
-    T: Send + Sync,
-    U: Send + Sync + UniqueIdentifier<Data = T>,
+    C: AsyncWrite<U>,
+    T: 'static + Send + Sync,
+    U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
     Assoc<U>: Send + Sync,
 {
+    fn write_now(&mut self, client: &mut C) -> Option<Result<()>> {
+        let data = client.write_now()?;
+        Some(self.written::<C>(data))
+    }
+    /// Writes output data
+    async fn write(&mut self, client: &mut C) -> Result<()> {
+        let data = AsyncWrite::write(client).await;
+        self.written::<C>(data)
+    }
     /// Sends output data
-    async fn send(&mut self) -> Result<()> {
-        self.data = (*self.client.lock().await).write();
-        if let Some(data) = &self.data {
-            log::debug!("{} sending", Who::who(self));
-            let futures: Vec<_> = self
-                .tx
-                .iter()
-                .map(|tx| tx.send_async(data.clone()))
-                .collect();
-            join_all(futures)
-                .await
-                .into_iter()
-                .collect::<std::result::Result<Vec<()>, flume::SendError<_>>>()
-                .map_err(|_| flume::SendError(()))?;
-            log::debug!("{} sent", Who::who(self));
-            Ok(())
-        } else {
-            for tx in &self.tx {
-                drop(tx);
+    ///
+    /// The batch of queued data is sent to the inputs with room for it and it is awaited for the others
+    fn poll_send(&mut self, cx: &mut Context<'_>, flush: bool) -> Poll<Result<()>> {
+        if self.unsent {
+            self.unsent = false;
+            if let Some(data) = &self.data {
+                self.queue.push(data.clone());
+            }
+            self.release();
+        }
+        if !self.queue.is_empty() && (self.queue.len() >= self.batch || flush) {
+            let batch = Batch::drain(&mut self.queue);
+            log::debug!("{} sending {} sample(s)", Who::who(self), batch.len());
+            for (tx, pending) in self.tx.iter().zip(self.pending.iter_mut()) {
+                match tx.try_send(batch.clone()) {
+                    Ok(_) => (),
+                    Err(TrySendError::Full(batch)) => {
+                        *pending = Some(tx.clone().into_send_async(batch))
+                    }
+                    Err(TrySendError::Disconnected(_)) => {
+                        return Poll::Ready(Err(flume::SendError(()).into()))
+                    }
+                }
+            }
+        }
+        let mut sent = true;
+        for pending in self.pending.iter_mut() {
+            if let Some(send) = pending.as_mut() {
+                match Pin::new(send).poll(cx) {
+                    Poll::Ready(Ok(_)) => *pending = None,
+                    Poll::Ready(Err(_)) => return Poll::Ready(Err(flume::SendError(()).into())),
+                    Poll::Pending => sent = false,
+                }
             }
-            Err(ActorError::Disconnected(Who::who(self)))
+        }
+        if sent {
+            Poll::Ready(Ok(()))
+        } else {
+            Poll::Pending
         }
     }
     /// Bootstraps output
     fn bootstrap(&self) -> bool {
         self.bootstrap
     }
+    fn bootstrapped(&self) -> bool {
+        self.bootstrapped
+    }
     fn who(&self) -> String {
         Who::who(self)
     }
@@ -151,4 +302,75 @@ where
     fn get_hash(&self) -> u64 {
         self.hash
     }
+    fn is_ready(&self) -> bool {
+        self.tx
+            .iter()
+            .all(|tx| !tx.is_full() || tx.is_disconnected())
+    }
+    fn capacity(&self) -> Option<usize> {
+        self.tx.iter().filter_map(|tx| tx.capacity()).min()
+    }
+    fn rate(&self) -> usize {
+        self.rate
+    }
+    #[cfg(feature = "recorder")]
+    fn set_recorder(&mut self, recorder: Recorder) {
+        if self.codec.is_some() {
+            self.recorder = Some(recorder);
+        }
+    }
+    #[cfg(feature = "recorder")]
+    fn record(&self, step: usize, bootstrap: bool) {
+        if let (Some(recorder), Some(codec), Some(data)) = (&self.recorder, self.codec, &self.data)
+        {
+            match (codec.encode)(data) {
+                Ok(bytes) => recorder.record(self.hash, step, bootstrap, bytes),
+                Err(e) => log::warn!("failed to record {}: {e}", Who::who(self)),
+            }
+        }
+    }
+    #[cfg(feature = "checkpoint")]
+    fn codec(&self) -> Option<Box<dyn Any + Send>> {
+        self.codec
+            .map(|codec| Box::new(codec) as Box<dyn Any + Send>)
+    }
+    fn prune(&mut self) -> usize {
+        let connected: Vec<_> = self.tx.iter().map(|tx| !tx.is_disconnected()).collect();
+        let mut keep = connected.iter();
+        self.tx.retain(|_| *keep.next().unwrap());
+        let mut keep = connected.iter();
+        self.pending.retain(|_| *keep.next().unwrap());
+        self.tx.len()
+    }
+    fn into_any(mut self: Box<Self>) -> Box<dyn Any + Send> {
+        self.bootstrapped = true;
+        self
+    }
+    fn splice(&mut self, output: Box<dyn Any + Send>) -> Option<Box<dyn Any + Send>> {
+        if self.tx.len() != 1 {
+            return Some(output);
+        }
+        match output.downcast::<Self>() {
+            Ok(output) => {
+                let Self {
+                    tx,
+                    pending,
+                    hash,
+                    bootstrap,
+                    rate,
+                    batch,
+                    ..
+                } = *output;
+                self.tx = tx;
+                self.pending = pending;
+                self.hash = hash;
+                // the other output is sampled at the rate of the inputs it sends to
+                self.bootstrap |= bootstrap;
+                self.rate = rate;
+                self.batch = batch;
+                None
+            }
+            Err(output) => Some(output),
+        }
+    }
 }
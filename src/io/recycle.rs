@@ -0,0 +1,149 @@
+use super::{Assoc, Data, UniqueIdentifier};
+use std::sync::Arc;
+
+/**
+Output data buffers recycler
+
+A [Write](super::Write) client that allocates new data at each step can write it into a buffer of the recycler instead.
+The recycler keeps the buffers it hands out and reuses a buffer once all the inputs it has been sent to have dropped it.
+A recycled buffer still holds the data previously written into it.
+
+```
+use gmt_dos_actors::{
+    io::{Data, Recycler, UniqueIdentifier, Write},
+    Update,
+};
+use std::sync::Arc;
+
+enum Ramp {}
+impl UniqueIdentifier for Ramp {
+    type Data = Vec<f64>;
+}
+#[derive(Default)]
+struct Source {
+    step: usize,
+    buffers: Recycler<Ramp>,
+}
+impl Update for Source {}
+impl Write<Ramp> for Source {
+    fn write(&mut self) -> Option<Arc<Data<Ramp>>> {
+        self.step += 1;
+        let step = self.step as f64;
+        Some(self.buffers.recycle(|data| {
+            data.clear();
+            data.extend((0..3).map(|i| i as f64 * step));
+        }))
+    }
+}
+
+let mut source = Source::default();
+let data = <Source as Write<Ramp>>::write(&mut source).unwrap();
+assert_eq!(**data, vec![0., 1., 2.]);
+drop(data);
+let data = <Source as Write<Ramp>>::write(&mut source).unwrap();
+assert_eq!(**data, vec![0., 2., 4.]);
+assert_eq!(source.buffers.len(), 1);
+```
+*/
+pub struct Recycler<U: UniqueIdentifier> {
+    buffers: Vec<Arc<Data<U>>>,
+    capacity: usize,
+}
+impl<U: UniqueIdentifier> Default for Recycler<U> {
+    fn default() -> Self {
+        Self::new(4)
+    }
+}
+impl<U: UniqueIdentifier> Recycler<U> {
+    /// Creates a new recycler keeping up to `capacity` buffers
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            buffers: Vec::with_capacity(capacity),
+            capacity: capacity.max(1),
+        }
+    }
+    /// Returns the number of buffers in the recycler
+    pub fn len(&self) -> usize {
+        self.buffers.len()
+    }
+    /// Checks if the recycler has no buffers
+    pub fn is_empty(&self) -> bool {
+        self.buffers.is_empty()
+    }
+    /// Fills a buffer with `fill` and returns it
+    ///
+    /// The buffer is either a recycled one or a new one with the [Default] data,
+    /// if all the buffers are still in use, a new buffer replaces the oldest one within the recycler
+    pub fn recycle(&mut self, fill: impl FnOnce(&mut Assoc<U>)) -> Arc<Data<U>>
+    where
+        Assoc<U>: Default,
+    {
+        let k = match self
+            .buffers
+            .iter_mut()
+            .position(|buffer| Arc::get_mut(buffer).is_some())
+        {
+            Some(k) => k,
+            None => {
+                if self.buffers.len() == self.capacity {
+                    self.buffers.remove(0);
+                }
+                self.buffers.push(Arc::new(Data::new(Default::default())));
+                self.buffers.len() - 1
+            }
+        };
+        let buffer = &mut self.buffers[k];
+        // the buffer is either a free one or the one that was just created,
+        // so the recycler is the only owner and the buffer is always filled
+        let data = Arc::get_mut(buffer);
+        debug_assert!(data.is_some(), "the recycled buffer is shared");
+        if let Some(data) = data {
+            fill(data);
+        }
+        buffer.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(crate::UID)]
+    #[uid(data = "usize")]
+    enum Count {}
+
+    #[test]
+    fn reuse_after_drop() {
+        let mut recycler = Recycler::<Count>::new(2);
+        let first = recycler.recycle(|x| *x = 1);
+        let second = recycler.recycle(|x| *x = 2);
+        assert_eq!(recycler.len(), 2);
+        drop(first);
+        let third = recycler.recycle(|x| *x += 10);
+        // the buffer of the first data is reused with its previous data
+        assert_eq!(**third, 11);
+        assert!(Arc::ptr_eq(&third, &recycler.buffers[0]));
+        assert_eq!(**second, 2);
+        assert_eq!(recycler.len(), 2);
+    }
+
+    #[test]
+    fn eviction_at_capacity() {
+        let mut recycler = Recycler::<Count>::new(2);
+        let held: Vec<_> = (1..=3).map(|i| recycler.recycle(|x| *x = i)).collect();
+        // the oldest buffer is evicted from the recycler but not from its holder
+        assert_eq!(recycler.len(), 2);
+        assert!(!recycler
+            .buffers
+            .iter()
+            .any(|buffer| Arc::ptr_eq(buffer, &held[0])));
+        assert_eq!(
+            held.iter().map(|data| ***data).collect::<Vec<_>>(),
+            [1, 2, 3]
+        );
+        drop(held);
+        let data = recycler.recycle(|x| *x *= 10);
+        assert_eq!(**data, 20);
+        assert_eq!(recycler.len(), 2);
+    }
+}
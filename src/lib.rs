@@ -22,7 +22,8 @@ Each [Actor] performs the same [task](Actor::task), within an infinite loop, con
  2. updating the client state
  3. sending the outputs if any
 
-The loop exits when one of the following error happens: [ActorError::NoData], [ActorError::DropSend], [ActorError::DropRecv].
+The loop exits when one of the following error happens: [ActorError::NoData], [ActorError::DropSend], [ActorError::DropRecv],
+or when the model is [stopped](crate::model::Model::stop) ([ActorError::Cancelled]).
 
 ### Sampling rates
 
@@ -33,6 +34,9 @@ The rates `NI` or `NO` are defined as the ratio between the simulation sampling
 If `NI>NO`, outputs are upsampled with a simple sample-and-hold for `NI/NO` samples.
 If `NO>NI`, outputs are decimated by a factor `NO/NI`
 
+An output is connected to an input with a different rate with [into_input_with](IntoInputs::into_input_with),
+inserting a rate transition actor in between (see [transition](crate::clients::transition)).
+
 For a 1000Hz simulation sampling frequency, the following table gives some examples of inputs/outputs sampling frequencies and rate:
 
 | Inputs `[Hz]` | Ouputs `[Hz]` | NI | NO | Upsampling | Decimation |
@@ -51,6 +55,11 @@ and the client must implement some of the following traits:
  - [read](crate::io::Read) if the actor has some inputs,
  - [update](Update), this trait must always be implemented (but the default empty implementation is acceptable)
 
+Clients that may fail implement the fallible variants [TryWrite](crate::io::TryWrite), [TryRead](crate::io::TryRead) and [TryUpdate] instead,
+an error ends the actor loop with [ActorError::Client] and is reported when the model completes.
+I/O-bound clients may implement the asynchronous variants [AsyncWrite](crate::io::AsyncWrite), [AsyncRead](crate::io::AsyncRead) and [AsyncUpdate] instead,
+and the update of CPU-heavy clients may be offloaded to the blocking threads pool with [Offload](crate::actor::Offload).
+
 ## Model
 
 An integrated model is build as follows:
@@ -78,12 +87,19 @@ use tokio::sync::Mutex;
 pub use uid_derive::UID;
 
 pub mod actor;
+#[cfg(feature = "checkpoint")]
+pub mod checkpoint;
 #[cfg(feature = "clients")]
 pub mod clients;
 pub mod io;
 pub mod model;
+#[cfg(feature = "recorder")]
+pub mod recorder;
+#[cfg(feature = "registry")]
+pub mod registry;
+pub mod testing;
 #[doc(inline)]
-pub use actor::{Actor, Initiator, Task, Terminator, Update};
+pub use actor::{Actor, AsyncUpdate, Initiator, Task, Terminator, TryUpdate, Update};
 pub use io::UniqueIdentifier;
 
 #[derive(thiserror::Error, Debug)]
@@ -112,20 +128,84 @@ pub enum ActorError {
     NoOutputsPositiveRate(String),
     #[error("Orphan output in {0} actor")]
     OrphanOutput(String),
+    #[error("actor task cancelled")]
+    Cancelled,
+    #[error("{0} has no output {1}")]
+    MissingOutput(String, String),
+    #[error("{0} has no input {1}")]
+    MissingInput(String, String),
+    #[error("invalid rate transition: {0}")]
+    InvalidRateTransition(String),
+    #[error("{0} bootstrapped output {1} rate ({2}) and inputs rate ({3}) are not multiple of one another")]
+    NonDivisibleBootstrap(String, String, usize, usize),
+    #[error("client {name} failed: {source}")]
+    Client {
+        name: String,
+        #[source]
+        source: ClientError,
+    },
+}
+impl ActorError {
+    /// Wraps the error of a client of type `C`
+    ///
+    /// The error is named after the client type until it is [named](ActorError::named) after its actor
+    pub(crate) fn client<C>(source: ClientError) -> Self {
+        ActorError::Client {
+            name: type_name::<C>().to_string(),
+            source,
+        }
+    }
+    /// Names a client error after the actor `name`, if the actor has a name
+    pub(crate) fn named(self, name: Option<&str>) -> Self {
+        match (self, name) {
+            (ActorError::Client { source, .. }, Some(name)) => ActorError::Client {
+                name: name.to_string(),
+                source,
+            },
+            (e, _) => e,
+        }
+    }
 }
 pub type Result<R> = std::result::Result<R, ActorError>;
+/// Error returned by the fallible clients
+pub type ClientError = Box<dyn std::error::Error + Send + Sync>;
+/// Result of the fallible clients methods
+pub type ClientResult<R> = std::result::Result<R, ClientError>;
 
 /// Assign inputs to actors
 pub trait IntoInputs<'a, T, U, CO, const NO: usize, const NI: usize>
 where
     T: 'static + Send + Sync,
     U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
-    CO: 'static + Update + Send + io::Write<U>,
+    CO: 'static + AsyncUpdate + io::AsyncWrite<U>,
 {
     /// Creates a new input for 'actor' from the last 'Receiver'
     fn into_input<CI, const N: usize>(self, actor: &mut Actor<CI, NO, N>) -> Self
     where
-        CI: 'static + Update + Send + io::Read<U>,
+        CI: 'static + AsyncUpdate + io::AsyncRead<U>,
+        Self: Sized;
+    /// Creates a new input for 'actor' from the last 'Receiver' regardless of the actor inputs rate
+    ///
+    /// The input is sampled at the rate of the output, that rate must be a multiple of the actor inputs rate
+    fn into_input_at<CI, const M: usize, const N: usize>(self, actor: &mut Actor<CI, M, N>) -> Self
+    where
+        CI: 'static + AsyncUpdate + io::AsyncRead<U>,
+        Self: Sized;
+    /// Creates a new input for 'actor' from the last 'Receiver' through a rate transition actor
+    ///
+    /// If the output rate and the actor inputs rate differ, an actor with the client of the rate transition [policy](RateTransition)
+    /// is inserted in between, reading the output at its rate and writing to the actor input at the actor inputs rate.
+    /// The inserted actor is added to the model with the actor.
+    /// The [model check](crate::model::Model::check) fails if the policy does not [accept](RateTransition::accepts) the transition.
+    /// Otherwise, the input is created as with [into_input](IntoInputs::into_input)
+    fn into_input_with<CI, P, const M: usize, const N: usize>(
+        self,
+        actor: &mut Actor<CI, M, N>,
+        policy: P,
+    ) -> Self
+    where
+        CI: 'static + AsyncUpdate + io::AsyncRead<U>,
+        P: RateTransition<U>,
         Self: Sized;
     /// Returns an error if there are any unassigned receivers
     ///
@@ -135,9 +215,11 @@ where
         Self: Sized;
 }
 // Unique hash for a pair of input/output
-fn hashio<CO, const NO: usize, const NI: usize>(output_actor: &mut Actor<CO, NI, NO>) -> u64
+pub(crate) fn hashio<CO, const NO: usize, const NI: usize>(
+    output_actor: &mut Actor<CO, NI, NO>,
+) -> u64
 where
-    CO: Update + Send,
+    CO: AsyncUpdate,
 {
     let mut hasher = DefaultHasher::new();
     output_actor.who().hash(&mut hasher);
@@ -157,22 +239,117 @@ where
     output.set_hash(hash);
     hash
 }
+// Sampling rate of the last output
+pub(crate) fn rateio<CO, const NO: usize, const NI: usize>(
+    output_actor: &Actor<CO, NI, NO>,
+) -> usize
+where
+    CO: AsyncUpdate,
+{
+    output_actor
+        .outputs
+        .as_ref()
+        .and_then(|o| o.last())
+        .map_or(output_actor.outputs_rate, |output| output.rate())
+}
+// Codec of the data of the last output
+#[cfg(feature = "checkpoint")]
+pub(crate) fn codecio<T, CO, const NO: usize, const NI: usize>(
+    output_actor: &Actor<CO, NI, NO>,
+) -> Option<io::Codec<T>>
+where
+    T: 'static,
+    CO: AsyncUpdate,
+{
+    output_actor
+        .outputs
+        .as_ref()
+        .and_then(|o| o.last())
+        .and_then(|output| output.codec())
+        .and_then(|codec| codec.downcast::<io::Codec<T>>().ok())
+        .map(|codec| *codec)
+}
+// Adds an input to 'input_actor' from a 'Receiver' of the last output of 'output_actor'
+pub(crate) fn connect<
+    T,
+    U,
+    CO,
+    CI,
+    const NO: usize,
+    const NI: usize,
+    const M: usize,
+    const N: usize,
+>(
+    output_actor: &mut Actor<CO, NI, NO>,
+    rx: Rx<U>,
+    input_actor: &mut Actor<CI, M, N>,
+) where
+    T: 'static + Send + Sync,
+    U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
+    CO: AsyncUpdate,
+    CI: 'static + AsyncUpdate + io::AsyncRead<U>,
+{
+    let (hash, rate) = (hashio(output_actor), rateio(output_actor));
+    #[cfg(feature = "checkpoint")]
+    input_actor.add_input(rx, hash, rate, codecio(output_actor));
+    #[cfg(not(feature = "checkpoint"))]
+    input_actor.add_input(rx, hash, rate);
+}
 impl<'a, T, U, CO, const NO: usize, const NI: usize> IntoInputs<'a, T, U, CO, NO, NI>
-    for (
-        &'a mut Actor<CO, NI, NO>,
-        Vec<flume::Receiver<Arc<io::Data<U>>>>,
-    )
+    for (&'a mut Actor<CO, NI, NO>, Vec<Rx<U>>)
 where
     T: 'static + Send + Sync,
     U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
-    CO: 'static + Update + Send + io::Write<U>,
+    CO: 'static + AsyncUpdate + io::AsyncWrite<U>,
 {
-    fn into_input<CI, const N: usize>(mut self, actor: &mut Actor<CI, NO, N>) -> Self
+    fn into_input<CI, const N: usize>(self, actor: &mut Actor<CI, NO, N>) -> Self
     where
-        CI: 'static + Update + Send + io::Read<U>,
+        CI: 'static + AsyncUpdate + io::AsyncRead<U>,
+    {
+        self.into_input_at(actor)
+    }
+    fn into_input_at<CI, const M: usize, const N: usize>(
+        mut self,
+        actor: &mut Actor<CI, M, N>,
+    ) -> Self
+    where
+        CI: 'static + AsyncUpdate + io::AsyncRead<U>,
     {
         if let Some(recv) = self.1.pop() {
-            actor.add_input(recv, hashio(self.0))
+            connect(self.0, recv, actor)
+        }
+        self
+    }
+    fn into_input_with<CI, P, const M: usize, const N: usize>(
+        mut self,
+        actor: &mut Actor<CI, M, N>,
+        policy: P,
+    ) -> Self
+    where
+        CI: 'static + AsyncUpdate + io::AsyncRead<U>,
+        P: RateTransition<U>,
+    {
+        let (rate, inputs_rate) = (rateio(self.0), actor.inputs_rate);
+        if rate == inputs_rate {
+            return self.into_input_at(actor);
+        }
+        if let Some(recv) = self.1.pop() {
+            if !policy.accepts(rate, inputs_rate) && actor.invalid_transition.is_none() {
+                actor.invalid_transition = Some(format!(
+                    "{} from rate {rate} to {inputs_rate} into {}",
+                    policy.name(),
+                    actor.who()
+                ));
+            }
+            let name = format!("{} ({rate}:{inputs_rate})", policy.name());
+            let mut transition = Actor::<P::Client>::from((policy.client(), name));
+            transition.set_rates(rate, inputs_rate);
+            connect(self.0, recv, &mut transition);
+            transition
+                .add_output()
+                .build::<U>()
+                .into_input_at(&mut *actor);
+            actor.transitions.push(Box::new(transition));
         }
         self
     }
@@ -184,6 +361,20 @@ where
         }
     }
 }
+/// Rate transition policy of [into_input_with](IntoInputs::into_input_with)
+///
+/// The policy gives the client of the actor inserted between an output and an input with different rates
+pub trait RateTransition<U: UniqueIdentifier> {
+    type Client: 'static + AsyncUpdate + io::AsyncRead<U> + io::AsyncWrite<U>;
+    /// Returns the policy name
+    fn name(&self) -> &str;
+    /// Returns the rate transition client
+    fn client(self) -> Self::Client;
+    /// Checks if the policy applies to the transition from the output `rate` to the actor `inputs_rate`
+    fn accepts(&self, _rate: usize, _inputs_rate: usize) -> bool {
+        true
+    }
+}
 /// Interface for data logging types
 pub trait Entry<U: UniqueIdentifier> {
     /// Adds an entry to the logger
@@ -193,7 +384,7 @@ pub trait Entry<U: UniqueIdentifier> {
 #[async_trait]
 pub trait IntoLogsN<CI, const N: usize, const NO: usize>
 where
-    CI: Update + Send,
+    CI: AsyncUpdate,
 {
     async fn logn(self, actor: &mut Actor<CI, NO, N>, size: usize) -> Self
     where
@@ -201,21 +392,18 @@ where
 }
 #[async_trait]
 impl<T, U, CI, CO, const N: usize, const NO: usize, const NI: usize> IntoLogsN<CI, N, NO>
-    for (
-        &mut Actor<CO, NI, NO>,
-        Vec<flume::Receiver<Arc<io::Data<U>>>>,
-    )
+    for (&mut Actor<CO, NI, NO>, Vec<Rx<U>>)
 where
     T: 'static + Send + Sync,
     U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
-    CI: 'static + Update + Send + io::Read<U> + Entry<U>,
-    CO: 'static + Update + Send + io::Write<U>,
+    CI: 'static + AsyncUpdate + io::AsyncRead<U> + Entry<U>,
+    CO: 'static + AsyncUpdate + io::AsyncWrite<U>,
 {
     /// Creates a new logging entry for the output
     async fn logn(mut self, actor: &mut Actor<CI, NO, N>, size: usize) -> Self {
         if let Some(recv) = self.1.pop() {
             (*actor.client.lock().await).entry(size);
-            actor.add_input(recv, hashio(self.0))
+            connect(self.0, recv, actor)
         }
         self
     }
@@ -228,7 +416,7 @@ pub trait Size<U: UniqueIdentifier> {
 #[async_trait]
 pub trait IntoLogs<CI, const N: usize, const NO: usize>
 where
-    CI: Update + Send,
+    CI: AsyncUpdate,
 {
     async fn log(self, actor: &mut Actor<CI, NO, N>) -> Self
     where
@@ -236,22 +424,19 @@ where
 }
 #[async_trait]
 impl<T, U, CI, CO, const N: usize, const NO: usize, const NI: usize> IntoLogs<CI, N, NO>
-    for (
-        &mut Actor<CO, NI, NO>,
-        Vec<flume::Receiver<Arc<io::Data<U>>>>,
-    )
+    for (&mut Actor<CO, NI, NO>, Vec<Rx<U>>)
 where
     T: 'static + Send + Sync,
     U: 'static + Send + Sync + UniqueIdentifier<Data = T>,
-    CI: 'static + Update + Send + io::Read<U> + Entry<U>,
-    CO: 'static + Update + Send + io::Write<U> + Size<U>,
+    CI: 'static + AsyncUpdate + io::AsyncRead<U> + Entry<U>,
+    CO: 'static + AsyncUpdate + io::AsyncWrite<U> + Size<U>,
 {
     /// Creates a new logging entry for the output
     async fn log(mut self, actor: &mut Actor<CI, NO, N>) -> Self {
         if let Some(recv) = self.1.pop() {
             (*actor.client.lock().await)
                 .entry(<CO as Size<U>>::len(&mut *self.0.client.lock().await));
-            actor.add_input(recv, hashio(self.0))
+            connect(self.0, recv, actor)
         }
         self
     }
@@ -260,12 +445,16 @@ where
 pub struct ActorOutputBuilder {
     capacity: Vec<usize>,
     bootstrap: bool,
+    rate: Option<usize>,
+    batch: usize,
 }
 impl Default for ActorOutputBuilder {
     fn default() -> Self {
         Self {
             capacity: Vec::new(),
             bootstrap: false,
+            rate: None,
+            batch: 1,
         }
     }
 }
@@ -279,12 +468,12 @@ impl ActorOutputBuilder {
     }
 }
 
-type Rx<U> = flume::Receiver<Arc<io::Data<U>>>;
+type Rx<U> = flume::Receiver<io::Batch<U>>;
 
 /// Actor add output interface
 pub trait AddOuput<'a, C, const NI: usize, const NO: usize>
 where
-    C: 'static + Update + Send,
+    C: 'static + AsyncUpdate,
 {
     /// Sets the channel to unbounded
     fn unbounded(self) -> Self;
@@ -292,17 +481,34 @@ where
     fn bootstrap(self) -> Self;
     /// Multiplexes the output `n` times
     fn multiplex(self, n: usize) -> Self;
+    /// Sets the output sampling rate (default: the actor outputs rate `NO`)
+    fn rate(self, rate: usize) -> Self;
+    /// Sends the output data `k` samples at a time
+    ///
+    /// The channels carry [Batch](io::Batch)es of `k` consecutive samples,
+    /// the samples left when the actor loop ends are sent before the client [finishes](crate::Update::on_finish)
+    fn batch(self, k: usize) -> Self;
     /// Builds the new output
     fn build<U>(self) -> (&'a mut Actor<C, NI, NO>, Vec<Rx<U>>)
     where
-        C: io::Write<U>,
+        C: io::AsyncWrite<U>,
         U: 'static + UniqueIdentifier + Send + Sync,
         Assoc<U>: Send + Sync;
+    /// Builds the new output, its data is written to the model [recording](crate::model::Model::recording)
+    /// and the samples in flight to its inputs are saved in the model [checkpoint](crate::checkpoint)s
+    ///
+    /// The data of the outputs built with [build](AddOuput::build) is neither recorded nor checkpointed
+    #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+    fn build_recorded<U>(self) -> (&'a mut Actor<C, NI, NO>, Vec<Rx<U>>)
+    where
+        C: io::AsyncWrite<U>,
+        U: 'static + UniqueIdentifier + Send + Sync,
+        Assoc<U>: Send + Sync + io::Record;
 }
 impl<'a, C, const NI: usize, const NO: usize> AddOuput<'a, C, NI, NO>
     for (&'a mut Actor<C, NI, NO>, ActorOutputBuilder)
 where
-    C: 'static + Update + Send,
+    C: 'static + AsyncUpdate,
 {
     fn unbounded(self) -> Self {
         let n = self.1.capacity.len();
@@ -332,39 +538,80 @@ where
             },
         )
     }
+    fn rate(self, rate: usize) -> Self {
+        (
+            self.0,
+            ActorOutputBuilder {
+                rate: Some(rate),
+                ..self.1
+            },
+        )
+    }
+    fn batch(self, k: usize) -> Self {
+        (
+            self.0,
+            ActorOutputBuilder {
+                batch: k.max(1),
+                ..self.1
+            },
+        )
+    }
     fn build<U>(self) -> (&'a mut Actor<C, NI, NO>, Vec<Rx<U>>)
     where
-        C: 'static + Update + Send + io::Write<U>,
+        C: 'static + AsyncUpdate + io::AsyncWrite<U>,
         U: 'static + Send + Sync + UniqueIdentifier,
         Assoc<U>: Send + Sync,
     {
-        use io::{Output, S};
-        let (actor, builder) = self;
-        let mut txs = vec![];
-        let mut rxs = vec![];
-        for &cap in &builder.capacity {
-            let (tx, rx) = if cap == usize::MAX {
-                flume::unbounded::<S<U>>()
-            } else {
-                flume::bounded::<S<U>>(cap)
-            };
-            txs.push(tx);
-            rxs.push(rx);
-        }
-
-        let output: Output<C, Assoc<U>, U, NO> = Output::builder(actor.client.clone())
-            .bootstrap(builder.bootstrap)
-            .senders(txs)
-            .build();
+        build_output(self, |output| output)
+    }
+    #[cfg(any(feature = "recorder", feature = "checkpoint"))]
+    fn build_recorded<U>(self) -> (&'a mut Actor<C, NI, NO>, Vec<Rx<U>>)
+    where
+        C: 'static + AsyncUpdate + io::AsyncWrite<U>,
+        U: 'static + Send + Sync + UniqueIdentifier,
+        Assoc<U>: Send + Sync + io::Record,
+    {
+        build_output(self, |output| output.codec(io::Codec::new()))
+    }
+}
 
-        if let Some(ref mut outputs) = actor.outputs {
-            outputs.push(Box::new(output));
+/// Adds an output to the actor, the `configure` closure sets the output options that are not in the [ActorOutputBuilder]
+fn build_output<C, U, const NI: usize, const NO: usize>(
+    (actor, builder): (&mut Actor<C, NI, NO>, ActorOutputBuilder),
+    configure: impl FnOnce(io::OutputBuilder<Assoc<U>, U>) -> io::OutputBuilder<Assoc<U>, U>,
+) -> (&mut Actor<C, NI, NO>, Vec<Rx<U>>)
+where
+    C: 'static + AsyncUpdate + io::AsyncWrite<U>,
+    U: 'static + Send + Sync + UniqueIdentifier,
+    Assoc<U>: Send + Sync,
+{
+    use io::{Batch, Output};
+    let mut txs = vec![];
+    let mut rxs = vec![];
+    for &cap in &builder.capacity {
+        let (tx, rx) = if cap == usize::MAX {
+            flume::unbounded::<Batch<U>>()
         } else {
-            actor.outputs = Some(vec![Box::new(output)]);
-        }
+            flume::bounded::<Batch<U>>(cap)
+        };
+        txs.push(tx);
+        rxs.push(rx);
+    }
 
-        (actor, rxs)
+    let output = Output::<Assoc<U>, U>::builder()
+        .bootstrap(builder.bootstrap)
+        .rate(builder.rate.unwrap_or(actor.outputs_rate))
+        .batch(builder.batch)
+        .senders(txs);
+    let output = configure(output).build();
+
+    if let Some(ref mut outputs) = actor.outputs {
+        outputs.push(Box::new(output));
+    } else {
+        actor.outputs = Some(vec![Box::new(output)]);
     }
+
+    (actor, rxs)
 }
 
 /// Creates a reference counted pointer
@@ -378,7 +625,7 @@ pub trait ArcMutex {
         Arc::new(Mutex::new(self))
     }
 }
-impl<C: Update> ArcMutex for C {}
+impl<C: AsyncUpdate> ArcMutex for C {}
 
 pub trait Who<T> {
     /// Returns type name
@@ -409,7 +656,7 @@ pub mod prelude {
         Logging, OneSignal, Sampler, Signal, Signals, Source, Tick, Timer, Void,
     };
     pub use super::{
-        model::Model, Actor, AddOuput, ArcMutex, Initiator, IntoInputs, IntoLogs, IntoLogsN, Task,
-        Terminator, UniqueIdentifier, UID,
+        model::Model, Actor, AddOuput, ArcMutex, Initiator, IntoInputs, IntoLogs, IntoLogsN,
+        RateTransition, Task, Terminator, UniqueIdentifier, UID,
     };
 }
@@ -0,0 +1,228 @@
+//! Feedback loops analysis of the model network
+
+use super::{ModelError, Result};
+use crate::actor::plain::{self, PlainActor, PlainIO, PlainOutput};
+use std::collections::HashMap;
+
+/// Channel between 2 actors
+struct Link {
+    from: usize,
+    to: usize,
+    output: String,
+    rate: usize,
+    bootstrap: bool,
+    capacity: Option<usize>,
+}
+
+/// Model network with the actors as nodes and the channels as links
+struct Network<'a> {
+    actors: &'a [PlainActor],
+    links: Vec<Link>,
+}
+impl<'a> Network<'a> {
+    fn new(actors: &'a [PlainActor]) -> Self {
+        let producers: HashMap<u64, (usize, bool, &PlainIO)> = actors
+            .iter()
+            .enumerate()
+            .flat_map(|(k, actor)| {
+                actor
+                    .outputs
+                    .iter()
+                    .flatten()
+                    .map(move |output| match output {
+                        PlainOutput::Bootstrap(io) => (io.hash, (k, true, io)),
+                        PlainOutput::Regular(io) => (io.hash, (k, false, io)),
+                    })
+            })
+            .collect();
+        let mut links = vec![];
+        for (to, actor) in actors.iter().enumerate() {
+            for input in actor.inputs.iter().flatten() {
+                if let Some(&(from, bootstrap, io)) = producers.get(&input.hash) {
+                    links.push(Link {
+                        from,
+                        to,
+                        output: io.name.clone(),
+                        rate: io.rate,
+                        bootstrap,
+                        capacity: io.capacity,
+                    });
+                }
+            }
+        }
+        Self { actors, links }
+    }
+    /// Returns, for each actor, the actors that can be reached following the links
+    ///
+    /// Bootstrapped links are skipped if `regular_only` is true
+    fn reach(&self, regular_only: bool) -> Vec<Vec<bool>> {
+        let n = self.actors.len();
+        let mut next: Vec<Vec<usize>> = vec![vec![]; n];
+        for link in self
+            .links
+            .iter()
+            .filter(|link| !(regular_only && link.bootstrap))
+        {
+            next[link.from].push(link.to);
+        }
+        (0..n)
+            .map(|k| {
+                let mut reached = vec![false; n];
+                let mut stack = next[k].clone();
+                while let Some(i) = stack.pop() {
+                    if !reached[i] {
+                        reached[i] = true;
+                        stack.extend(&next[i]);
+                    }
+                }
+                reached
+            })
+            .collect()
+    }
+    /// Returns the actors within each loop
+    fn loops(&self, regular_only: bool) -> Vec<Vec<usize>> {
+        let reach = self.reach(regular_only);
+        let mut loops: Vec<Vec<usize>> = vec![];
+        for k in (0..self.actors.len()).filter(|&k| reach[k][k]) {
+            match loops
+                .iter_mut()
+                .find(|actors| reach[actors[0]][k] && reach[k][actors[0]])
+            {
+                Some(actors) => actors.push(k),
+                None => loops.push(vec![k]),
+            }
+        }
+        loops
+    }
+}
+
+/// Checks the feedback loops of the model
+///
+/// Each loop must be broken by at least one bootstrapped output,
+/// and a bootstrapped output within a loop must not send more samples than the channel capacity
+pub(super) fn check(actors: &[PlainActor]) -> Result<()> {
+    let network = Network::new(actors);
+    let unbroken = network.loops(true);
+    if !unbroken.is_empty() {
+        return Err(ModelError::AlgebraicLoop(
+            unbroken
+                .into_iter()
+                .map(|actors| {
+                    actors
+                        .into_iter()
+                        .map(|k| network.actors[k].client.clone())
+                        .collect()
+                })
+                .collect(),
+        ));
+    }
+    for actors in network.loops(false) {
+        for link in network.links.iter().filter(|link| {
+            link.bootstrap && actors.contains(&link.from) && actors.contains(&link.to)
+        }) {
+            let samples =
+                plain::bootstrap_samples(network.actors[link.from].inputs_rate, link.rate);
+            if let Some(capacity) = link.capacity.filter(|&capacity| samples > capacity) {
+                return Err(ModelError::BootstrapOverflow {
+                    actor: network.actors[link.from].client.clone(),
+                    output: link.output.clone(),
+                    samples,
+                    capacity,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{clients::Integrator, prelude::*};
+
+    #[derive(UID)]
+    enum Error {}
+    #[derive(UID)]
+    enum Command {}
+
+    /// Returns the layout of a controller and a plant in a feedback loop, with a monitor of the plant
+    fn feedback<const N: usize>(
+        configure: impl FnOnce(
+            &mut Actor<Integrator<Error>, N, 1>,
+            &mut Actor<Integrator<Command>, 1, N>,
+        ),
+    ) -> Vec<PlainActor> {
+        let mut controller: Actor<_, N, 1> = Integrator::<Error>::new(1).into();
+        let mut plant: Actor<_, 1, N> = Integrator::<Command>::new(1).into();
+        let mut monitor = Terminator::<_, N>::new(Logging::<f64>::default().into_arcx());
+        configure(&mut controller, &mut plant);
+        plant
+            .add_output()
+            .multiplex(2)
+            .build::<Error>()
+            .into_input(&mut controller)
+            .into_input(&mut monitor);
+        vec![controller.as_plain(), plant.as_plain(), monitor.as_plain()]
+    }
+
+    #[test]
+    fn bootstrapped_loop() {
+        let actors = feedback::<1>(|controller, plant| {
+            controller
+                .add_output()
+                .bootstrap()
+                .build::<Command>()
+                .into_input(plant);
+        });
+        assert!(check(&actors).is_ok());
+        let actors = feedback::<10>(|controller, plant| {
+            controller
+                .add_output()
+                .bootstrap()
+                .unbounded()
+                .build::<Command>()
+                .into_input(plant);
+        });
+        assert!(check(&actors).is_ok());
+    }
+
+    #[test]
+    fn missing_bootstrap() {
+        let actors = feedback::<1>(|controller, plant| {
+            controller.add_output().build::<Command>().into_input(plant);
+        });
+        match check(&actors) {
+            Err(ModelError::AlgebraicLoop(loops)) => {
+                assert_eq!(loops.len(), 1);
+                assert_eq!(
+                    loops[0],
+                    vec![actors[0].client.clone(), actors[1].client.clone()]
+                );
+            }
+            _ => panic!("expected an algebraic loop"),
+        }
+    }
+
+    #[test]
+    fn bootstrap_overflow() {
+        let actors = feedback::<10>(|controller, plant| {
+            controller
+                .add_output()
+                .bootstrap()
+                .build::<Command>()
+                .into_input(plant);
+        });
+        match check(&actors) {
+            Err(ModelError::BootstrapOverflow {
+                actor,
+                samples,
+                capacity,
+                ..
+            }) => {
+                assert_eq!(actor, actors[0].client);
+                assert_eq!((samples, capacity), (10, 1));
+            }
+            _ => panic!("expected a bootstrap overflow"),
+        }
+    }
+}
@@ -0,0 +1,1140 @@
+/*!
+# Integrated model
+
+The module implements the high-level integrated model interface.
+The model is build from a collection of [actor]s.
+
+The model has 6 states:
+ 1. [Unknown]: model state at its creation
+ 2. [Ready]: model state after succesfully performing runtime checks on inputs and outputs on all the actors, the model can move to the [Ready] state only from the [Unknown] state
+ 3. [Running]: model state while all the actors are performing their respective tasks, the model can move to the [Running] state either from the [Ready] or the [Paused] state
+ 4. [Paused]: model state while all the actors are [halted at a step boundary](pause), the model can move to the [Paused] state only from the [Running] state
+ 5. [Stepping]: model state while all the actors are [executed step-by-step](stepping) in a single thread, the model can move to the [Stepping] state only from the [Ready] state
+ 6. [Completed]: model state after the succesful completion of the tasks of all the actors, the model can move to the [Completed] state either from the [Running] or the [Stepping] state
+
+# Example
+
+A 3 actors model with [Signals], [Sampler] and [Logging] clients is build with:
+```
+use gmt_dos_actors::prelude::*;
+let mut source: Initiator<_> = Signals::new(1, 100).into();
+#[derive(UID)]
+enum Source {};
+let mut sampler: Actor<_, 1, 10> = Sampler::<Vec<f64>, Source>::default().into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_, 10>::new(logging);
+```
+`sampler` decimates `source` with a 1:10 ratio.
+The `source` connects to the `sampler` using the empty enum type `Source` as the data identifier.
+The source data is then logged into the client of the `sink` actor.
+```
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging);
+source.add_output().build::<Source>().into_input(&mut sampler);
+sampler.add_output().build::<Source>().into_input(&mut sink);
+```
+A [model](crate::model) is build from the set of actors:
+```
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)]);
+```
+Actors are checked for inputs/outputs consistencies:
+```
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+       .check()?;
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+```
+An actor that is wired but missing from the model is reported with the output left dangling:
+```
+# use gmt_dos_actors::{prelude::*, model::ModelError};
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+let model = Model::new(vec![Box::new(source), Box::new(sampler)]).check();
+assert!(matches!(model, Err(ModelError::DanglingOutput { index: 0, .. })));
+```
+The model run the actor tasks:
+```
+# tokio_test::block_on(async {
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+       .check()?
+       .run();
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+and wait for the tasks to finish:
+```
+# tokio_test::block_on(async {
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+       .check()?
+       .run()
+       .wait()
+       .await?;
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+Once the model run to completion, the data from `logging` is read with:
+```
+# tokio_test::block_on(async {
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+# Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+#       .check()?
+#       .run()
+#       .wait()
+#       .await?;
+let data: &[f64]  = &logging.lock().await;
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+A running model can be stopped before the actors run out of data,
+the actors leave their loop and the data logged so far is kept:
+```
+# tokio_test::block_on(async {
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, usize::MAX).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+let model = Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+       .check()?
+       .run();
+let stop = model.cancellation_token();
+tokio::spawn(async move {
+    tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    stop.cancel();
+});
+model.wait().await?;
+assert!(!logging.lock().await.is_empty());
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+The completed model holds a report for each actor with the reason the actor task ended,
+the number of steps and the task wall time:
+```
+# tokio_test::block_on(async {
+# use gmt_dos_actors::{prelude::*, model::ExitPolicy};
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+let model = Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+       .exit_policy(ExitPolicy::Strict)
+       .check()?
+       .run()
+       .wait()
+       .await?;
+for report in model.reports() {
+    println!("{report}");
+}
+assert_eq!(model.abnormal_exits().count(), 0);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+With [profiling](Model::profiling) enabled, the time each actor spends updating its client
+and waiting on its inputs and outputs is reported in a [ModelProfile] table, that is logged when the model completes:
+```
+# tokio_test::block_on(async {
+# use gmt_dos_actors::prelude::*;
+# let mut source: Initiator<_> = Signals::new(1, 100).into();
+# #[derive(UID)]
+# enum Source {};
+# let mut sampler: Actor<_> = Sampler::<Vec<f64>, Source>::default().into();
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut sink = Terminator::<_>::new(logging.clone());
+# source.add_output().build::<Source>().into_input(&mut sampler);
+# sampler.add_output().build::<Source>().into_input(&mut sink);
+let model = Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+       .profiling()
+       .check()?
+       .run()
+       .wait()
+       .await?;
+let profile = model.profile().unwrap();
+assert_eq!(profile.actors.len(), 3);
+let csv = profile.to_csv();
+let json = profile.to_json();
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+A [paced](Model::paced) model advances in step with the wall clock at the simulation sampling frequency.
+The actors client updates completing more than one sample late are counted as overruns
+and reported in a [ModelPacing] table, with the jitter of the updates, that is logged when the model completes:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::prelude::*;
+use std::time::{Duration, Instant};
+# #[derive(UID)]
+# enum Source {};
+struct Slow;
+impl gmt_dos_actors::Update for Slow {
+    fn update(&mut self) {
+        std::thread::sleep(Duration::from_millis(3));
+    }
+}
+impl gmt_dos_actors::io::Read<Source> for Slow {
+    fn read(&mut self, _: std::sync::Arc<gmt_dos_actors::io::Data<Source>>) {}
+}
+let mut source: Initiator<_> = Signals::new(1, 20).into();
+let mut sink: Terminator<_> = Slow.into();
+source.add_output().build::<Source>().into_input(&mut sink);
+let now = Instant::now();
+let model = Model::new(vec![Box::new(source), Box::new(sink)])
+       .check()?
+       .paced(1000.)
+       .run()
+       .await?;
+assert!(now.elapsed() >= Duration::from_millis(19));
+let pacing = model.pacing().unwrap();
+assert_eq!(pacing.actors.len(), 2);
+assert!(pacing.overruns() > 0);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+A feedback loop must be broken by a [bootstrap](crate::AddOuput::bootstrap)ped output,
+otherwise the model [check](Model::check) fails:
+```
+use gmt_dos_actors::{prelude::*, clients::Integrator, model::ModelError};
+#[derive(UID)]
+enum Error {};
+#[derive(UID)]
+enum Command {};
+let mut controller: Actor<_> = Integrator::<Error>::new(1).into();
+let mut plant: Actor<_> = Integrator::<Command>::new(1).into();
+controller.add_output().build::<Command>().into_input(&mut plant);
+plant.add_output().build::<Error>().into_input(&mut controller);
+let model = Model::new(vec![Box::new(controller), Box::new(plant)]).check();
+assert!(matches!(model, Err(ModelError::AlgebraicLoop(_))));
+```
+The bootstrapped samples must also fit in the channel,
+here the controller would send 10 samples in a channel of capacity 1:
+```
+# use gmt_dos_actors::{prelude::*, clients::Integrator, model::ModelError};
+# #[derive(UID)]
+# enum Error {};
+# #[derive(UID)]
+# enum Command {};
+let mut controller: Actor<_, 10, 1> = Integrator::<Error>::new(1).into();
+let mut plant: Actor<_, 1, 10> = Integrator::<Command>::new(1).into();
+controller.add_output().bootstrap().build::<Command>().into_input(&mut plant);
+plant.add_output().build::<Error>().into_input(&mut controller);
+let model = Model::new(vec![Box::new(controller), Box::new(plant)]).check();
+assert!(matches!(model, Err(ModelError::BootstrapOverflow { .. })));
+```
+
+[actor]: crate::actor
+[client]: crate::clients
+[Mutex]: tokio::sync::Mutex
+[Arc]: std::sync::Arc
+[Arcmutex]: crate::ArcMutex
+[into_arcx]: crate::ArcMutex::into_arcx
+[Signals]: crate::clients::Signals
+[Sampler]: crate::clients::Sampler
+[Logging]: crate::clients::Logging
+*/
+
+use crate::{
+    actor::{
+        flatten,
+        plain::{PlainActor, PlainOutput},
+        ActorReport, Context, Halt,
+    },
+    Task,
+};
+use chrono::{DateTime, Local, SecondsFormat};
+use std::{
+    collections::{hash_map::DefaultHasher, HashMap},
+    env,
+    fmt::Display,
+    fs::File,
+    hash::{Hash, Hasher},
+    io::Write,
+    marker::PhantomData,
+    path::Path,
+    process::Command,
+    sync::{atomic::AtomicUsize, Arc},
+    time::{Duration, Instant},
+};
+use tokio_util::sync::CancellationToken;
+
+mod loops;
+mod pacing;
+mod profile;
+mod wiring;
+pub use pacing::ModelPacing;
+pub use profile::ModelProfile;
+pub mod pause;
+pub mod reconfigure;
+pub use reconfigure::Reconfiguration;
+use reconfigure::TaskHandle;
+pub mod stepping;
+pub mod submodel;
+pub use submodel::{Port, SubModel};
+pub mod topology;
+pub use topology::Topology;
+
+#[derive(thiserror::Error, Debug)]
+pub enum ModelError {
+    #[error("no actors found in the model")]
+    NoActors,
+    #[error("failed to join the task")]
+    TaskError(#[from] tokio::task::JoinError),
+    #[error("Actor IO inconsistency")]
+    ActorIO(#[from] crate::ActorError),
+    #[error("{} actor(s) ended abnormally", .0.iter().filter(|r| !r.is_normal()).count())]
+    AbnormalExit(Vec<ActorReport>),
+    #[error("deadlock at step {0}, no progress from actors: {}", .1.join(", "))]
+    Deadlock(usize, Vec<String>),
+    #[error("{actor} output {output} #{index} is not connected to any actor of the model")]
+    DanglingOutput {
+        actor: String,
+        output: String,
+        index: usize,
+    },
+    #[error("{actor} input {input} has no producer in the model")]
+    OrphanInput { actor: String, input: String },
+    #[error(
+        "{actor} input {input} rate {rate} does not match the rate {output_rate} of its producer"
+    )]
+    RateMismatch {
+        actor: String,
+        input: String,
+        rate: usize,
+        output_rate: usize,
+    },
+    #[error("{actor} input {input} rate {rate} is not a positive multiple of the actor inputs rate {inputs_rate}")]
+    InputRate {
+        actor: String,
+        input: String,
+        rate: usize,
+        inputs_rate: usize,
+    },
+    #[error("feedback loop(s) without bootstrapped output: {}", .0.iter().map(|actors| format!("[{}]", actors.join(", "))).collect::<Vec<_>>().join(", "))]
+    AlgebraicLoop(Vec<Vec<String>>),
+    #[error("{actor} bootstraps {samples} samples of {output} in a feedback loop but the channel capacity is {capacity}")]
+    BootstrapOverflow {
+        actor: String,
+        output: String,
+        samples: usize,
+        capacity: usize,
+    },
+    #[error("all the actors ended before step {0}")]
+    Ended(usize),
+    #[error("no halted actor {0} of the requested type in the model")]
+    UnknownActor(String),
+    #[cfg(feature = "recorder")]
+    #[error("failed to create the model recording")]
+    Recording(#[from] bincode::Error),
+    #[cfg(feature = "checkpoint")]
+    #[error("model checkpoint failed")]
+    Checkpoint(#[from] crate::checkpoint::CheckpointError),
+}
+
+type Result<T> = std::result::Result<T, ModelError>;
+
+/// [Model] initial state
+pub enum Unknown {}
+/// Valid [Model] state
+pub enum Ready {}
+/// [Model]ing in-progress state
+pub enum Running {}
+/// [Model] paused state
+pub enum Paused {}
+/// [Model]ing step-by-step state
+pub enum Stepping {}
+/// [Model] final state
+pub enum Completed {}
+
+type Actors = Vec<Box<dyn Task>>;
+
+/// [Model] policy for actors ending abnormally
+///
+/// An actor ends normally if it runs out of data, if one of its channel is closed
+/// while in its loop, if the model is [stop](Model::stop)ped or if the actor is [retire](Reconfiguration::retire)d
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ExitPolicy {
+    /// Abnormal endings are only reported in the [Completed] model
+    #[default]
+    Lenient,
+    /// [Model::wait] returns an error if any actor ends abnormally
+    Strict,
+}
+
+/// Actor model
+pub struct Model<State> {
+    name: Option<String>,
+    actors: Option<Actors>,
+    task_handles: Option<Vec<TaskHandle>>,
+    state: PhantomData<State>,
+    start: Instant,
+    cancellation: CancellationToken,
+    exit_policy: ExitPolicy,
+    reports: Vec<ActorReport>,
+    step: usize,
+    profiling: bool,
+    pacing: Option<Duration>,
+    sampling_frequency: Option<f64>,
+    boundary: Arc<AtomicUsize>,
+    finished: Vec<PlainActor>,
+    #[cfg(feature = "recorder")]
+    recorder: Option<crate::recorder::Recorder>,
+}
+
+impl<S> Display for Model<S> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{} [{},{:?}]",
+            self.name.as_ref().unwrap_or(&"ACTOR MODEL".to_string()),
+            self.n_actors(),
+            self.n_io()
+        )?;
+        if let Some(actors) = &self.actors {
+            for actor in actors {
+                write!(f, " {}", actor)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<S> Model<S> {
+    /// Moves the model to another state
+    fn into_state<T>(self) -> Model<T> {
+        Model::<T> {
+            name: self.name,
+            actors: self.actors,
+            task_handles: self.task_handles,
+            state: PhantomData,
+            start: self.start,
+            cancellation: self.cancellation,
+            exit_policy: self.exit_policy,
+            reports: self.reports,
+            step: self.step,
+            profiling: self.profiling,
+            pacing: self.pacing,
+            sampling_frequency: self.sampling_frequency,
+            boundary: self.boundary,
+            finished: self.finished,
+            #[cfg(feature = "recorder")]
+            recorder: self.recorder,
+        }
+    }
+    /// Returns the simulation context passed to the clients hooks
+    fn context(&self) -> Context {
+        Context::new(self.name.clone(), self.sampling_frequency)
+    }
+    /// Prints some informations about the model and the actors within
+    pub fn inspect(self) -> Self {
+        println!("{self}");
+        self
+    }
+    /// Returns the total number of inputs and the total number of outputs
+    ///
+    /// Both numbers should be the same
+    pub fn n_io(&self) -> (usize, usize) {
+        if let Some(ref actors) = self.actors {
+            actors
+                .iter()
+                .fold((0usize, 0usize), |(mut i, mut o), actor| {
+                    i += actor.n_inputs();
+                    o += actor.n_outputs();
+                    (i, o)
+                })
+        } else {
+            (0, 0)
+        }
+    }
+    /// Returns the number of actors
+    pub fn n_actors(&self) -> usize {
+        self.actors.as_ref().map_or(0, |actors| actors.len())
+    }
+}
+
+#[doc(hidden)]
+pub trait UnknownOrReady {}
+impl UnknownOrReady for Unknown {}
+impl UnknownOrReady for Ready {}
+impl<State> Model<State>
+where
+    State: UnknownOrReady,
+{
+    /// Returns a [Graph] of the model
+    pub fn graph(&self) -> Option<Graph> {
+        self.actors
+            .as_ref()
+            .map(|actors| Graph::new(actors.iter().map(|a| a.as_plain()).collect()))
+    }
+    /// Returns the [Topology] of the model
+    pub fn topology(&self) -> Option<Topology> {
+        self.actors.as_ref().map(|actors| {
+            Topology::new(
+                self.name.clone(),
+                &actors.iter().map(|a| a.as_plain()).collect::<Vec<_>>(),
+            )
+        })
+    }
+    /// Produces the model flowchart from [Graph]
+    ///
+    /// The flowchart is written to the SVG file "integrated_model.dot.svg".
+    /// If a different model `name` is set, the file gets written to "`name`.dot.svg".
+    /// If Graphviz `neato` fails, the SVG file is drawn from the model [Topology] instead
+    pub fn flowchart(self) -> Self {
+        let name = self
+            .name
+            .clone()
+            .unwrap_or_else(|| "integrated_model".to_string());
+        let root_env = env::var("DATA_REPO").unwrap_or_else(|_| ".".to_string());
+        let path = Path::new(&root_env).join(&name);
+        if let Some(graph) = self.graph() {
+            match graph.to_dot(path.with_extension("dot")) {
+                Ok(_) => {
+                    let neato = Command::new("neato")
+                        .arg("-Gstart=rand")
+                        .arg("-Tsvg")
+                        .arg("-O")
+                        .arg(path.with_extension("dot").to_str().unwrap())
+                        .output();
+                    if !neato.is_ok_and(|output| output.status.success()) {
+                        if let Some(Err(e)) = self
+                            .topology()
+                            .map(|topology| topology.to_svg_file(path.with_extension("dot.svg")))
+                        {
+                            println!("Failed to write SVG image {path:?} with {e}")
+                        }
+                    }
+                }
+                Err(e) => println!("Failed to write Graphviz dot file {path:?} with {e}"),
+            }
+        }
+        self
+    }
+}
+
+impl Model<Unknown> {
+    /// Returns a new model
+    ///
+    /// A [SubModel] is replaced by the actors it is made of
+    pub fn new(actors: Actors) -> Self {
+        Self {
+            name: None,
+            actors: Some(actors.into_iter().flat_map(flatten).collect()),
+            task_handles: None,
+            state: PhantomData,
+            start: Instant::now(),
+            cancellation: CancellationToken::new(),
+            exit_policy: ExitPolicy::default(),
+            reports: Vec::new(),
+            step: 0,
+            profiling: false,
+            pacing: None,
+            sampling_frequency: None,
+            boundary: Arc::new(AtomicUsize::new(usize::MAX)),
+            finished: Vec::new(),
+            #[cfg(feature = "recorder")]
+            recorder: None,
+        }
+    }
+    /// Sets the model name
+    pub fn name<S: Into<String>>(self, name: S) -> Self {
+        Self {
+            name: Some(name.into()),
+            ..self
+        }
+    }
+    /// Sets the simulation sampling frequency in Hz
+    ///
+    /// The sampling frequency is given to the clients [on_start](crate::Update::on_start) and [on_finish](crate::Update::on_finish) hooks
+    pub fn sampling_frequency(self, sampling_frequency: f64) -> Self {
+        Self {
+            sampling_frequency: Some(sampling_frequency),
+            ..self
+        }
+    }
+    /// Enables the runtime profiling of the actors
+    ///
+    /// The [profile](Model::profile) of the actors is logged at the info level when the model completes
+    pub fn profiling(self) -> Self {
+        Self {
+            profiling: true,
+            ..self
+        }
+    }
+    /// Records the data sent by the actors outputs to the file at `path`
+    ///
+    /// Only the outputs built with [build_recorded](crate::AddOuput::build_recorded) are recorded,
+    /// the recording is read back with [Recording](crate::recorder::Recording)
+    /// and the file is complete once the model has completed
+    #[cfg(feature = "recorder")]
+    pub fn recording<P: AsRef<Path>>(mut self, path: P) -> Result<Self> {
+        if let Some(actors) = self.actors.as_mut() {
+            let plain: Vec<_> = actors.iter().map(|actor| actor.as_plain()).collect();
+            let recorder = crate::recorder::Recorder::create(path, &plain)?;
+            actors
+                .iter_mut()
+                .for_each(|actor| actor.set_recorder(&recorder));
+            self.recorder = Some(recorder);
+        }
+        Ok(self)
+    }
+    /// Sets the policy for actors ending abnormally (default: [ExitPolicy::Lenient])
+    pub fn exit_policy(self, exit_policy: ExitPolicy) -> Self {
+        Self {
+            exit_policy,
+            ..self
+        }
+    }
+    /// Validates actors inputs and outputs
+    ///
+    /// Each output must be connected to the input of an actor of the model and vice versa,
+    /// feedback loops must be broken by a bootstrapped output
+    /// and the bootstrapped samples must fit in the loop channels
+    pub fn check(self) -> Result<Model<Ready>> {
+        match self.actors {
+            Some(ref actors) => {
+                check(actors.iter().map(|actor| actor.as_ref()), vec![])?;
+                Ok(self.into_state())
+            }
+            None => Err(ModelError::NoActors),
+        }
+    }
+}
+
+/// Validates the inputs and outputs of the actors, alongside the layout of actors that are no longer running
+fn check<'a>(actors: impl Iterator<Item = &'a dyn Task>, mut plain: Vec<PlainActor>) -> Result<()> {
+    for actor in actors {
+        actor.check_inputs()?;
+        actor.check_outputs()?;
+        plain.push(actor.as_plain());
+    }
+    wiring::check(&plain)?;
+    loops::check(&plain)?;
+    Ok(())
+}
+
+impl Model<Ready> {
+    /// Paces the model with the wall clock at the simulation `sampling_frequency` in Hz
+    ///
+    /// The actors without inputs wait for each sample to be due
+    /// and the [pacing](Model::pacing) statistics of the actors are logged at the info level when the model completes
+    pub fn paced(self, sampling_frequency: f64) -> Self {
+        Self {
+            pacing: Some(Duration::from_secs_f64(sampling_frequency.recip())),
+            sampling_frequency: Some(sampling_frequency),
+            ..self
+        }
+    }
+    /// Spawns each actor task
+    pub fn run(mut self) -> Model<Running> {
+        let now: DateTime<Local> = Local::now();
+        println!(
+            "[{}<{}>] LAUNCHED",
+            self.name
+                .as_ref()
+                .unwrap_or(&String::from("Model"))
+                .to_uppercase(),
+            now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        );
+        let mut actors = self.actors.take().unwrap();
+        let mut task_handles = vec![];
+        let start = tokio::time::Instant::now();
+        let context = self.context();
+        while let Some(mut actor) = actors.pop() {
+            actor.set_cancellation_token(self.cancellation.clone());
+            actor.set_context(context.clone());
+            if self.profiling {
+                actor.enable_profiling();
+            }
+            if let Some(period) = self.pacing {
+                let origin = start.checked_sub(period.mul_f64(self.step as f64));
+                actor.enable_pacing(origin.unwrap_or(start), period);
+            }
+            let halt = Halt::new(self.boundary.clone(), self.step);
+            actor.set_halt(halt.clone());
+            task_handles.push(TaskHandle::spawn(actor, halt));
+        }
+        Model::<Running> {
+            task_handles: Some(task_handles),
+            start: Instant::now(),
+            ..self.into_state()
+        }
+    }
+}
+
+impl Model<Running> {
+    /// Stops the model
+    ///
+    /// All the actors leave their loop at the next opportunity,
+    /// the model must still be [wait](Model::wait)ed for
+    pub fn stop(&self) {
+        self.cancellation.cancel();
+    }
+    /// Returns a token that stops the model when cancelled
+    ///
+    /// The token can be moved to another task to [stop](Model::stop) the model
+    /// while the model is [wait](Model::wait)ed for
+    pub fn cancellation_token(&self) -> CancellationToken {
+        self.cancellation.clone()
+    }
+    /// Waits for the task of each actor to finish
+    ///
+    /// With the [ExitPolicy::Strict] policy, an error is returned if any actor ended abnormally
+    pub async fn wait(mut self) -> Result<Model<Completed>> {
+        let task_handles = self.task_handles.take().unwrap();
+        let mut reports = vec![];
+        for task_handle in task_handles.into_iter() {
+            reports.push(task_handle.wait().await?);
+        }
+        let elapsed_time = Instant::now().duration_since(self.start);
+        let now: DateTime<Local> = Local::now();
+        println!(
+            "[{}<{}>] {} in {}",
+            self.name
+                .as_ref()
+                .unwrap_or(&String::from("Model"))
+                .to_uppercase(),
+            now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            if self.cancellation.is_cancelled() {
+                "STOPPED"
+            } else {
+                "COMPLETED"
+            },
+            humantime::format_duration(elapsed_time)
+        );
+        self.reports.extend(reports);
+        Model::<Completed>::from_reports(self)
+    }
+}
+
+impl Model<Completed> {
+    /// Completes the model with the reports of the actor tasks
+    fn from_reports<S>(model: Model<S>) -> Result<Self> {
+        #[cfg(feature = "recorder")]
+        if let Some(recorder) = &model.recorder {
+            recorder.flush()?;
+        }
+        for report in model.reports.iter().filter(|report| !report.is_normal()) {
+            log::warn!("{report}");
+        }
+        if model.exit_policy == ExitPolicy::Strict && model.reports.iter().any(|r| !r.is_normal()) {
+            return Err(ModelError::AbnormalExit(model.reports));
+        }
+        let model = Model::<Completed> {
+            actors: None,
+            task_handles: None,
+            ..model.into_state()
+        };
+        if let Some(profile) = model.profile() {
+            log::info!("actors profile:\n{profile}");
+        }
+        if let Some(pacing) = model.pacing() {
+            log::info!("actors pacing:\n{pacing}");
+        }
+        Ok(model)
+    }
+    /// Returns the reports of the actor tasks
+    pub fn reports(&self) -> &[ActorReport] {
+        &self.reports
+    }
+    /// Returns the reports of the actors that ended abnormally
+    pub fn abnormal_exits(&self) -> impl Iterator<Item = &ActorReport> {
+        self.reports.iter().filter(|report| !report.is_normal())
+    }
+    /// Returns the runtime profile of the actors, if [profiling](Model::profiling) is enabled
+    pub fn profile(&self) -> Option<ModelProfile> {
+        self.profiling.then(|| ModelProfile::new(&self.reports))
+    }
+    /// Returns the pacing statistics of the actors, if the model is [paced](Model::paced)
+    pub fn pacing(&self) -> Option<ModelPacing> {
+        self.pacing.map(|_| ModelPacing::new(&self.reports))
+    }
+}
+
+use std::future::{Future, IntoFuture};
+use std::pin::Pin;
+pub type ModelCompleted = Pin<
+    Box<dyn Future<Output = std::result::Result<Model<Completed>, ModelError>> + Send + 'static>,
+>;
+impl IntoFuture for Model<Running> {
+    type IntoFuture = ModelCompleted;
+    type Output = <ModelCompleted as Future>::Output;
+    fn into_future(self) -> Self::IntoFuture {
+        Box::pin(self.wait())
+    }
+}
+
+/// Returns the client name without the module path and the generic parameters
+fn client_name(client: &str) -> String {
+    client
+        .replace("::Controller", "")
+        .split('<')
+        .next()
+        .unwrap()
+        .split("::")
+        .last()
+        .unwrap()
+        .to_string()
+}
+
+/// [Model] network mapping
+///
+/// The structure is used to build a [Graphviz](https://www.graphviz.org/) diagram of a [Model].
+/// A new [Graph] is created with [Model::graph()].
+///
+/// The model flow chart is written to a SVG image with `neato -Gstart=rand -Tsvg filename.dot > filename.svg`
+#[derive(Debug)]
+pub struct Graph {
+    actors: Vec<PlainActor>,
+}
+impl Graph {
+    fn new(actors: Vec<PlainActor>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        let mut actors = actors;
+        actors.iter_mut().for_each(|actor| {
+            actor.client = client_name(&actor.client);
+            actor.hash(&mut hasher);
+            actor.hash = hasher.finish();
+        });
+        Self { actors }
+    }
+    /// Returns the diagram in the [Graphviz](https://www.graphviz.org/) dot language
+    pub fn to_string(&self) -> String {
+        use PlainOutput::*;
+        let mut lookup: HashMap<usize, usize> = HashMap::new();
+        let mut colors = (1usize..=8).cycle();
+        let outputs: Vec<_> = self
+            .actors
+            .iter()
+            .filter_map(|actor| {
+                actor.outputs.as_ref().map(|outputs| {
+                    outputs
+                        .iter()
+                        .map(|output| {
+                            let color = lookup
+                                .entry(actor.outputs_rate)
+                                .or_insert_with(|| colors.next().unwrap());
+                            match output {
+                                Bootstrap(output) => format!(
+                                    "{0} -> {1} [color={2}, style=bold];",
+                                    actor.hash, output.hash, color
+                                ),
+                                Regular(output) => format!(
+                                    "{0} -> {1} [color={2}];",
+                                    actor.hash, output.hash, color
+                                ),
+                            }
+                        })
+                        .collect::<Vec<String>>()
+                })
+            })
+            .flatten()
+            .collect();
+        let inputs: Vec<_> = self
+            .actors
+            .iter()
+            .filter_map(|actor| {
+                actor.inputs.as_ref().map(|inputs| {
+                    inputs
+                        .iter()
+                        .map(|input| {
+                            let color = lookup
+                                .entry(actor.inputs_rate)
+                                .or_insert_with(|| colors.next().unwrap());
+                            format!(
+                                r#"{0} -> {1} [label="{2}", color={3}];"#,
+                                input.hash,
+                                actor.hash,
+                                input.name.split("::").last().unwrap(),
+                                color
+                            )
+                        })
+                        .collect::<Vec<String>>()
+                })
+            })
+            .flatten()
+            .collect();
+        format!(
+            r#"
+digraph  G {{
+  overlap = scale;
+  splines = true;
+  bgcolor = gray24;
+  {{node [shape=box, width=1.5, style="rounded,filled", fillcolor=lightgray]; {};}}
+  node [shape=point, fillcolor=gray24, color=lightgray];
+
+  /* Sub-models */
+{}
+
+  /* Outputs */
+{{
+  edge [arrowhead=none,colorscheme=dark28];
+  {}
+}}
+  /* Inputs */
+{{
+  edge [arrowhead=vee,fontsize=9, fontcolor=lightgray, labelfloat=true,colorscheme=dark28]
+  {}
+}}
+}}
+"#,
+            self.actors
+                .iter()
+                .map(|actor| format!(r#"{} [label="{}"]"#, actor.hash, actor.client))
+                .collect::<Vec<String>>()
+                .join("; "),
+            self.clusters(&[], &mut 0),
+            outputs.join("\n"),
+            inputs.join("\n"),
+        )
+    }
+    /// Returns the [SubModel] clusters nested within the cluster at `path`
+    fn clusters(&self, path: &[String], id: &mut usize) -> String {
+        let mut names: Vec<&String> = vec![];
+        for actor in &self.actors {
+            if actor.clusters.len() > path.len() && actor.clusters.starts_with(path) {
+                let name = &actor.clusters[path.len()];
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+        names
+            .into_iter()
+            .map(|name| {
+                let path = [path, std::slice::from_ref(name)].concat();
+                let nodes: Vec<_> = self
+                    .actors
+                    .iter()
+                    .filter(|actor| actor.clusters == path)
+                    .map(|actor| actor.hash.to_string())
+                    .collect();
+                *id += 1;
+                let cluster = *id;
+                format!(
+                    r#"subgraph cluster_{} {{ label="{}"; color=lightgray; fontcolor=lightgray; {}; {} }}"#,
+                    cluster,
+                    name,
+                    nodes.join("; "),
+                    self.clusters(&path, id)
+                )
+            })
+            .collect::<Vec<String>>()
+            .join("\n")
+    }
+    /// Writes the output of [Graph::to_string()] to a file
+    pub fn to_dot<P: AsRef<Path>>(
+        &self,
+        path: P,
+    ) -> std::result::Result<(), Box<dyn std::error::Error>> {
+        let mut file = File::create(path)?;
+        write!(&mut file, "{}", self.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExitPolicy, ModelError, Ready};
+    use crate::{
+        actor::{ActorReport, Context, Exit},
+        io::{Data, Read},
+        prelude::*,
+        ClientResult, TryUpdate,
+    };
+    use std::sync::Arc;
+
+    #[derive(UID)]
+    enum Source {}
+
+    /// Hook of the [Failing] client that fails
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    enum Failure {
+        Start,
+        Loop,
+        Panic,
+        Finish,
+    }
+
+    /// Sink failing at its 3rd update or in one of its hooks
+    struct Failing(Failure, usize);
+    impl TryUpdate for Failing {
+        fn on_start(&mut self, _context: &Context) -> ClientResult<()> {
+            match self.0 {
+                Failure::Start => Err("start failed".into()),
+                _ => Ok(()),
+            }
+        }
+        fn try_update(&mut self) -> ClientResult<()> {
+            self.1 += 1;
+            match self.0 {
+                Failure::Loop if self.1 == 3 => Err("update failed".into()),
+                Failure::Panic if self.1 == 3 => panic!("update panicked"),
+                _ => Ok(()),
+            }
+        }
+        fn on_finish(&mut self, _context: &Context, _exit: &Exit) -> ClientResult<()> {
+            match self.0 {
+                Failure::Finish => Err("finish failed".into()),
+                _ => Ok(()),
+            }
+        }
+    }
+    impl Read<Source> for Failing {
+        fn read(&mut self, _: Arc<Data<Source>>) {}
+    }
+
+    /// Returns a model with a sink failing as given
+    fn model(failure: Failure, exit_policy: ExitPolicy) -> Model<Ready> {
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        let mut sink: Terminator<_> = (Failing(failure, 0), "failing").into();
+        source.add_output().build::<Source>().into_input(&mut sink);
+        Model::new(vec![Box::new(source), Box::new(sink)])
+            .exit_policy(exit_policy)
+            .check()
+            .unwrap()
+    }
+
+    /// Checks that the only abnormal exit is the failure of the sink
+    fn check_abnormal<'a>(mut reports: impl Iterator<Item = &'a ActorReport>, failure: Failure) {
+        let report = reports.next().expect("no abnormal exit");
+        assert_eq!(report.name, "failing");
+        let matching = match failure {
+            Failure::Start => matches!(report.exit, Exit::Start(_)),
+            Failure::Loop => matches!(report.exit, Exit::Loop(_)),
+            Failure::Panic => matches!(&report.exit, Exit::Panic(msg) if msg == "update panicked"),
+            Failure::Finish => matches!(report.exit, Exit::Finish(_)),
+        };
+        assert!(matching, "unexpected exit for {failure:?}: {}", report.exit);
+        assert!(reports.next().is_none());
+    }
+
+    const FAILURES: [Failure; 4] = [
+        Failure::Start,
+        Failure::Loop,
+        Failure::Panic,
+        Failure::Finish,
+    ];
+
+    #[tokio::test]
+    async fn lenient_exit_policy() {
+        for failure in FAILURES {
+            let model = model(failure, ExitPolicy::Lenient)
+                .run()
+                .wait()
+                .await
+                .unwrap();
+            assert_eq!(model.reports().len(), 2);
+            check_abnormal(model.abnormal_exits(), failure);
+        }
+    }
+
+    #[tokio::test]
+    async fn strict_exit_policy() {
+        for failure in FAILURES {
+            match model(failure, ExitPolicy::Strict).run().wait().await {
+                Err(ModelError::AbnormalExit(reports)) => {
+                    assert_eq!(reports.len(), 2);
+                    check_abnormal(reports.iter().filter(|r| !r.is_normal()), failure);
+                }
+                Err(e) => panic!("unexpected error for {failure:?}: {e}"),
+                Ok(_) => panic!("no error for {failure:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn strict_exit_policy_stepping() {
+        // a panic is not caught when the model is stepped
+        for failure in FAILURES.into_iter().filter(|&f| f != Failure::Panic) {
+            let model = model(failure, ExitPolicy::Strict).stepping().await;
+            match model.complete().await {
+                Err(ModelError::AbnormalExit(reports)) => {
+                    check_abnormal(reports.iter().filter(|r| !r.is_normal()), failure)
+                }
+                Err(e) => panic!("unexpected error for {failure:?}: {e}"),
+                Ok(_) => panic!("no error for {failure:?}"),
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn strict_exit_policy_normal_exits() {
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        let mut sink = Terminator::<_>::new(Logging::<f64>::default().into_arcx());
+        source.add_output().build::<Source>().into_input(&mut sink);
+        let model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .exit_policy(ExitPolicy::Strict)
+            .check()
+            .unwrap()
+            .run()
+            .wait()
+            .await
+            .unwrap();
+        assert_eq!(model.abnormal_exits().count(), 0);
+    }
+}
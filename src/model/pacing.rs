@@ -0,0 +1,131 @@
+use crate::actor::{ActorPacing, ActorReport};
+use std::fmt;
+
+/// [Model](super::Model) pacing statistics
+///
+/// The table lists, for each actor, the number of paced client updates, the number of overruns,
+/// the mean and largest lateness and the jitter of the client updates
+#[derive(Debug, Default, Clone)]
+pub struct ModelPacing {
+    /// Actors name and pacing statistics
+    pub actors: Vec<(String, ActorPacing)>,
+}
+impl ModelPacing {
+    /// Creates the pacing table from the actors reports
+    pub fn new(reports: &[ActorReport]) -> Self {
+        Self {
+            actors: reports
+                .iter()
+                .filter_map(|report| {
+                    report
+                        .pacing
+                        .as_ref()
+                        .map(|pacing| (report.name.clone(), pacing.clone()))
+                })
+                .collect(),
+        }
+    }
+    /// Returns the total number of overruns
+    pub fn overruns(&self) -> usize {
+        self.actors.iter().map(|(_, pacing)| pacing.overruns).sum()
+    }
+}
+
+impl fmt::Display for ModelPacing {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .actors
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default()
+            .max(5);
+        writeln!(
+            f,
+            "{:<width$} {:>10} {:>10} {:>12} {:>12} {:>12}",
+            "ACTOR", "SAMPLES", "OVERRUNS", "LATE MEAN", "LATE MAX", "JITTER"
+        )?;
+        for (name, pacing) in &self.actors {
+            writeln!(
+                f,
+                "{:<width$} {:>10} {:>10} {:>10.3}ms {:>12} {:>10.3}ms",
+                name,
+                pacing.samples,
+                pacing.overruns,
+                pacing.lateness_mean() * 1e3,
+                format!("{:.3?}", pacing.lateness_max),
+                pacing.jitter() * 1e3
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        actor::Pacer,
+        io::{AsyncRead, Data},
+        prelude::*,
+        AsyncUpdate, ClientResult,
+    };
+    use async_trait::async_trait;
+    use std::{sync::Arc, time::Duration};
+    use tokio::time::{advance, Instant};
+
+    #[derive(UID)]
+    enum Source {}
+
+    /// Client whose updates last 3 sampling periods
+    struct Overrunning;
+    #[async_trait]
+    impl AsyncRead<Source> for Overrunning {
+        async fn read(&mut self, _: Arc<Data<Source>>) -> ClientResult<()> {
+            Ok(())
+        }
+    }
+    #[async_trait]
+    impl AsyncUpdate for Overrunning {
+        async fn update(&mut self) -> ClientResult<()> {
+            tokio::time::sleep(Duration::from_millis(3)).await;
+            Ok(())
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn overruns() {
+        let period = Duration::from_millis(1);
+        let mut pacer = Pacer::new(Instant::now(), period);
+        for t in 0..10 {
+            advance(3 * period).await;
+            pacer.record(t);
+        }
+        // the update #k completes at 3(k+1)ms instead of (3+k)ms: 2k ms late
+        assert_eq!(pacer.stats.samples, 10);
+        assert_eq!(pacer.stats.overruns, 9);
+        assert_eq!(pacer.stats.lateness_max, Duration::from_millis(18));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn overrunning_client() {
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        let mut sink = Terminator::<_>::new(Overrunning.into_arcx()).name("sink");
+        source.add_output().build::<Source>().into_input(&mut sink);
+        let model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .paced(1e3)
+            .run()
+            .await
+            .unwrap();
+        let pacing = model.pacing().unwrap();
+        let (_, sink) = pacing
+            .actors
+            .iter()
+            .find(|(name, _)| name == "sink")
+            .unwrap();
+        assert_eq!(sink.samples, 10);
+        assert_eq!(sink.overruns, 9);
+        assert_eq!(sink.lateness_max, Duration::from_millis(18));
+    }
+}
@@ -0,0 +1,191 @@
+/*!
+# Pause and resume
+
+A [Running] model is [pause](Model::pause)d at a step boundary, the same way as it is [reconfigure](Model::reconfigure)d:
+all the actor loops halt at the same sample, after sending the data of the previous samples.
+The [Paused] model is then [resume](Model::resume)d and the actors carry on their loop where they left off:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{clients::Integrator, prelude::*};
+#[derive(UID)]
+enum Source {};
+let mut source: Initiator<_> = Signals::new(1, 100).signals(Signal::Constant(1.)).into();
+let mut integrator: Actor<_> = Integrator::<Source>::new(1).gain(1.).into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+source.add_output().build::<Source>().into_input(&mut integrator);
+integrator.add_output().batch(8).build::<Source>().into_input(&mut sink);
+let paused = Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+    .check()?
+    .run()
+    .pause(50)
+    .await?;
+assert_eq!(logging.lock().await.len(), paused.step());
+paused.resume().await?;
+let data = logging.lock().await;
+assert_eq!(data.len(), 100);
+assert!(data.iter().zip(1..).all(|(x, k)| *x == -(k as f64)));
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+With the `checkpoint` feature, the state of a paused model is saved with [checkpoint](Model::checkpoint)
+and a new model is [restore](Model::restore)d from it, see the [checkpoint](crate::checkpoint) module.
+*/
+
+use super::{reconfigure::TaskHandle, Model, Paused, Ready, Reconfiguration, Result, Running};
+use crate::actor::Halt;
+use std::sync::atomic::Ordering::SeqCst;
+
+impl Model<Running> {
+    /// Pauses the model at the step boundary `step`
+    ///
+    /// The boundary is moved past `step` if some actors have already gone past it.
+    /// Returns an error if all the actors ended before reaching the boundary.
+    pub async fn pause(mut self, step: usize) -> Result<Model<Paused>> {
+        let halted = self.halt(step).await;
+        self.boundary.store(usize::MAX, SeqCst);
+        let Reconfiguration { step, actors, .. } = halted?;
+        Ok(Model::<Paused> {
+            actors: Some(actors.into_iter().map(|(actor, _)| actor).collect()),
+            step,
+            ..self.into_state()
+        })
+    }
+}
+
+impl Model<Paused> {
+    /// Returns the step at which the model is paused
+    pub fn step(&self) -> usize {
+        self.step
+    }
+    /// Resumes the actor loops at the step the model is paused
+    pub fn resume(mut self) -> Model<Running> {
+        let actors = self.actors.take().unwrap_or_default();
+        let task_handles = actors
+            .into_iter()
+            .map(|mut actor| {
+                let halt = Halt::new(self.boundary.clone(), self.step);
+                actor.set_halt(halt.clone());
+                TaskHandle::spawn(actor, halt)
+            })
+            .collect();
+        Model::<Running> {
+            task_handles: Some(task_handles),
+            ..self.into_state()
+        }
+    }
+    /// Writes the state of the paused model to the file at `path`
+    ///
+    /// The model can still be [resume](Model::resume)d afterwards
+    #[cfg(feature = "checkpoint")]
+    pub async fn checkpoint<P: AsRef<std::path::Path>>(&mut self, path: P) -> Result<()> {
+        let mut states = vec![];
+        for actor in self.actors.iter_mut().flatten() {
+            states.extend(actor.save_state().await?);
+        }
+        crate::checkpoint::ModelCheckpoint {
+            step: self.step,
+            actors: states,
+            ended: self.finished.iter().map(|actor| actor.into()).collect(),
+        }
+        .save(path)?;
+        Ok(())
+    }
+}
+
+impl Model<Ready> {
+    /// Restores the model from the [checkpoint](Model::checkpoint) at `path`
+    ///
+    /// The model must be built the same way as the checkpointed model,
+    /// each actor is restored from the checkpointed actor with the same name, inputs and outputs
+    /// and the actors that had ended are removed.
+    /// The model [run](Model::run)s from the step at which the checkpointed model was paused.
+    #[cfg(feature = "checkpoint")]
+    pub async fn restore<P: AsRef<std::path::Path>>(mut self, path: P) -> Result<Self> {
+        use crate::checkpoint::{ActorKey, CheckpointError, ModelCheckpoint};
+        let ModelCheckpoint {
+            step,
+            mut actors,
+            ended,
+        } = ModelCheckpoint::load(path)?;
+        let tasks = self.actors.get_or_insert_with(Vec::new);
+        for key in ended {
+            let k = tasks
+                .iter()
+                .position(|task| ActorKey::from(&task.as_plain()) == key)
+                .ok_or(CheckpointError::UnknownActor(key.name))?;
+            let task = tasks.remove(k);
+            self.finished.push(task.as_plain());
+        }
+        for actor in self.actors.iter_mut().flatten() {
+            actor.restore_state(&mut actors).await?;
+        }
+        if let Some(state) = actors.first() {
+            return Err(CheckpointError::UnknownActor(state.actor.name.clone()).into());
+        }
+        self.step = step;
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{clients::Integrator, prelude::*};
+
+    #[derive(UID)]
+    enum Source {}
+
+    /// Returns the integrated ramp, pausing and resuming the model at the given steps
+    async fn integrate(pauses: &[usize]) -> Vec<f64> {
+        let mut source: Initiator<_> = Signals::new(1, 200)
+            .signals(Signal::Ramp { a: 1., b: 0. })
+            .into();
+        let mut integrator: Actor<_> = Integrator::<Source>::new(1).gain(0.5).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        source
+            .add_output()
+            .build::<Source>()
+            .into_input(&mut integrator);
+        integrator
+            .add_output()
+            .bootstrap()
+            .batch(4)
+            .build::<Source>()
+            .into_input(&mut sink);
+        let mut model = Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run();
+        for &step in pauses {
+            model = model.pause(step).await.unwrap().resume();
+        }
+        model.await.unwrap();
+        let data = logging.lock().await.to_vec();
+        data
+    }
+
+    #[tokio::test]
+    async fn resumed_model_is_identical() {
+        let data = integrate(&[]).await;
+        assert_eq!(data.len(), 201);
+        assert_eq!(data, integrate(&[1, 10, 11, 150]).await);
+    }
+
+    #[tokio::test]
+    async fn pause_after_the_end_fails() {
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        source.add_output().build::<Source>().into_input(&mut sink);
+        let model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run();
+        assert!(matches!(
+            model.pause(100).await,
+            Err(crate::model::ModelError::Ended(_))
+        ));
+    }
+}
@@ -0,0 +1,148 @@
+use crate::actor::{ActorProfile, ActorReport};
+use std::{fmt, time::Duration};
+
+/// [Model](super::Model) runtime profile
+///
+/// The table lists, for each actor, the number of client updates,
+/// the mean and maximum update time and the time blocked on the inputs and on the outputs
+#[derive(Debug, Default, Clone)]
+pub struct ModelProfile {
+    /// Actors name and profile
+    pub actors: Vec<(String, ActorProfile)>,
+}
+impl ModelProfile {
+    /// Creates the profile table from the actors reports
+    pub fn new(reports: &[ActorReport]) -> Self {
+        Self {
+            actors: reports
+                .iter()
+                .filter_map(|report| {
+                    report
+                        .profile
+                        .as_ref()
+                        .map(|profile| (report.name.clone(), profile.clone()))
+                })
+                .collect(),
+        }
+    }
+    /// Returns the table in the CSV format, with durations in seconds
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("actor,iterations,update_mean,update_max,inputs,outputs\n");
+        for (name, profile) in &self.actors {
+            csv.push_str(&format!(
+                "\"{}\",{},{},{},{},{}\n",
+                name.replace('"', "\"\""),
+                profile.iterations,
+                profile.update_mean().as_secs_f64(),
+                profile.update_max.as_secs_f64(),
+                profile.inputs.as_secs_f64(),
+                profile.outputs.as_secs_f64()
+            ));
+        }
+        csv
+    }
+    /// Returns the table in the JSON format, with durations in seconds
+    pub fn to_json(&self) -> String {
+        let rows: Vec<_> = self
+            .actors
+            .iter()
+            .map(|(name, profile)| {
+                format!(
+                    r#"{{"actor":"{}","iterations":{},"update_mean":{},"update_max":{},"inputs":{},"outputs":{}}}"#,
+                    escape(name),
+                    profile.iterations,
+                    profile.update_mean().as_secs_f64(),
+                    profile.update_max.as_secs_f64(),
+                    profile.inputs.as_secs_f64(),
+                    profile.outputs.as_secs_f64()
+                )
+            })
+            .collect();
+        format!("[{}]", rows.join(","))
+    }
+}
+
+/// Escapes a string for JSON
+pub(super) fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '"' => "\\\"".to_string(),
+            '\\' => "\\\\".to_string(),
+            c if c.is_control() => format!("\\u{:04x}", c as u32),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+impl fmt::Display for ModelProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let width = self
+            .actors
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or_default()
+            .max(5);
+        writeln!(
+            f,
+            "{:<width$} {:>10} {:>12} {:>12} {:>12} {:>12}",
+            "ACTOR", "ITERATIONS", "UPDATE MEAN", "UPDATE MAX", "INPUTS", "OUTPUTS"
+        )?;
+        let fmt = |d: Duration| format!("{:.3?}", d);
+        for (name, profile) in &self.actors {
+            writeln!(
+                f,
+                "{:<width$} {:>10} {:>12} {:>12} {:>12} {:>12}",
+                name,
+                profile.iterations,
+                fmt(profile.update_mean()),
+                fmt(profile.update_max),
+                fmt(profile.inputs),
+                fmt(profile.outputs)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> ModelProfile {
+        let ms = Duration::from_millis;
+        ModelProfile {
+            actors: vec![
+                (
+                    r#"sink "a\b""#.to_string(),
+                    ActorProfile {
+                        iterations: 4,
+                        update: ms(8),
+                        update_max: ms(5),
+                        inputs: ms(250),
+                        outputs: Duration::ZERO,
+                    },
+                ),
+                ("source".to_string(), ActorProfile::default()),
+            ],
+        }
+    }
+
+    #[test]
+    fn to_csv() {
+        assert_eq!(
+            profile().to_csv(),
+            "actor,iterations,update_mean,update_max,inputs,outputs\n\
+             \"sink \"\"a\\b\"\"\",4,0.002,0.005,0.25,0\n\
+             \"source\",0,0,0,0,0\n"
+        );
+    }
+
+    #[test]
+    fn to_json() {
+        assert_eq!(
+            profile().to_json(),
+            r#"[{"actor":"sink \"a\\b\"","iterations":4,"update_mean":0.002,"update_max":0.005,"inputs":0.25,"outputs":0},{"actor":"source","iterations":0,"update_mean":0,"update_max":0,"inputs":0,"outputs":0}]"#
+        );
+    }
+}
@@ -0,0 +1,355 @@
+/*!
+# Runtime reconfiguration
+
+A [Running] model is reconfigured with [Model::reconfigure] at a step boundary.
+All the actor loops halt at the same sample, after sending the data of the previous samples,
+and the [Reconfiguration] of the halted actors is used to
+ - [retire](Reconfiguration::retire) actors,
+ - [insert](Reconfiguration::insert) new actors, wired to the halted actors with the usual API
+   or onto the channels of other actors with [take_output](crate::Actor::take_output) and [take_input](crate::Actor::take_input),
+ - [swap](crate::Actor::swap_client) the client of a halted [actor](Reconfiguration::actor).
+
+The channels and the data in flight are left as they are, so the actors resume their loop in step with each other.
+The new layout is [check](Model::check)ed the same way as the initial layout before the actors resume.
+
+Here the source is replaced at step 50 by another source that takes over the channel to the sink:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::prelude::*;
+#[derive(UID)]
+enum Source {};
+let mut source: Initiator<_> = (Signals::new(1, 1000).signals(Signal::Constant(1.)), "source").into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+source.add_output().build::<Source>().into_input(&mut sink);
+let mut model = Model::new(vec![Box::new(source), Box::new(sink)])
+    .check()?
+    .run();
+let step = model
+    .reconfigure(50, |halted| {
+        let mut source: Initiator<_> = Signals::new(1, 100).signals(Signal::Constant(-1.)).into();
+        source.take_output::<Source>(halted.retire("source")?)?;
+        halted.insert(Box::new(source));
+        Ok(())
+    })
+    .await?;
+model.await?;
+let data = logging.lock().await;
+assert_eq!(data.len(), step + 100);
+assert!(data[..step].iter().all(|x| *x == 1.));
+assert!(data[step..].iter().all(|x| *x == -1.));
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+and here the client of an actor is swapped for a new client with the opposite gain:
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{clients::Integrator, prelude::*};
+#[derive(UID)]
+enum Source {};
+let mut source: Initiator<_> = Signals::new(1, 100).signals(Signal::Constant(1.)).into();
+let mut integrator: Actor<_> = (Integrator::<Source>::new(1).gain(1.), "integrator").into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+source.add_output().build::<Source>().into_input(&mut integrator);
+integrator.add_output().build::<Source>().into_input(&mut sink);
+let mut model = Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+    .check()?
+    .run();
+let step = model
+    .reconfigure(50, |halted| {
+        halted
+            .actor::<Integrator<Source>, 1, 1>("integrator")?
+            .swap_client(Integrator::<Source>::new(1).gain(-1.).into_arcx());
+        Ok(())
+    })
+    .await?;
+model.await?;
+let data = logging.lock().await;
+assert_eq!(data[step - 1], -(step as f64));
+assert_eq!(data[step], 1.);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+
+use super::{check, Model, ModelError, Result, Running};
+use crate::{
+    actor::{flatten, plain::PlainActor, ActorReport, AsyncUpdate, Exit, Halt},
+    Actor, Task,
+};
+use futures::{stream::FuturesUnordered, StreamExt};
+use std::sync::atomic::Ordering::SeqCst;
+use tokio::task::{JoinError, JoinHandle};
+
+/// Outcome of an actor task
+pub(super) enum Outcome {
+    /// The actor loop halted at the step boundary
+    Halted(Box<dyn Task>),
+    /// The actor task ended
+    Done(Box<ActorReport>),
+}
+
+/// Handle to the task of an actor of a [Running] model
+pub(super) struct TaskHandle {
+    plain: PlainActor,
+    halt: Halt,
+    handle: JoinHandle<Outcome>,
+}
+impl TaskHandle {
+    /// Spawns the actor task, keeping the layout of the actor
+    pub(super) fn spawn(mut actor: Box<dyn Task>, halt: Halt) -> Self {
+        let plain = actor.as_plain();
+        let handle = tokio::spawn(async move {
+            let report = actor.task().await;
+            if actor.halted().is_some() {
+                Outcome::Halted(actor)
+            } else {
+                Outcome::Done(Box::new(report))
+            }
+        });
+        Self {
+            plain,
+            halt,
+            handle,
+        }
+    }
+    /// Waits for the actor task to end, resuming the actor loop if it halted
+    pub(super) async fn wait(self) -> Result<ActorReport> {
+        Ok(match self.handle.await? {
+            Outcome::Done(report) => *report,
+            Outcome::Halted(mut actor) => actor.task().await,
+        })
+    }
+    /// Waits for the actor task to end or to halt
+    async fn join(self) -> (std::result::Result<Outcome, JoinError>, PlainActor, Halt) {
+        (self.handle.await, self.plain, self.halt)
+    }
+}
+
+/// Actors of a [Running] model halted at a step boundary
+///
+/// The reconfiguration is applied by [Model::reconfigure]
+pub struct Reconfiguration {
+    pub(super) step: usize,
+    pub(super) actors: Vec<(Box<dyn Task>, Halt)>,
+    inserted: Vec<Box<dyn Task>>,
+    retired: Vec<Box<dyn Task>>,
+}
+impl Reconfiguration {
+    /// Returns the step at which the actors halted
+    pub fn step(&self) -> usize {
+        self.step
+    }
+    /// Returns the position of the halted actor `name`
+    fn position(&self, name: &str) -> Result<usize> {
+        self.actors
+            .iter()
+            .position(|(actor, _)| actor.as_plain().client == name)
+            .ok_or_else(|| ModelError::UnknownActor(name.to_string()))
+    }
+    /// Returns the halted actor `name`
+    pub fn task(&mut self, name: &str) -> Result<&mut (dyn Task + 'static)> {
+        let k = self.position(name)?;
+        Ok(self.actors[k].0.as_mut())
+    }
+    /// Returns the halted actor `name` with client `C` and inputs and outputs rates `NI` and `NO`
+    pub fn actor<C, const NI: usize, const NO: usize>(
+        &mut self,
+        name: &str,
+    ) -> Result<&mut Actor<C, NI, NO>>
+    where
+        C: 'static + AsyncUpdate,
+    {
+        self.actors
+            .iter_mut()
+            .filter(|(actor, _)| actor.as_plain().client == name)
+            .find_map(|(actor, _)| actor.as_any_mut()?.downcast_mut::<Actor<C, NI, NO>>())
+            .ok_or_else(|| ModelError::UnknownActor(name.to_string()))
+    }
+    /// Retires the halted actor `name`
+    ///
+    /// The actor is returned for other actors to take over its inputs and outputs.
+    /// Its client [finishes](crate::Update::on_finish) with [Exit::Retired],
+    /// then the channels left are dropped and removed from the outputs feeding the actor
+    pub fn retire(&mut self, name: &str) -> Result<&mut (dyn Task + 'static)> {
+        let k = self.position(name)?;
+        let (actor, _) = self.actors.remove(k);
+        self.retired.push(actor);
+        Ok(self.retired.last_mut().unwrap().as_mut())
+    }
+    /// Inserts a new actor starting at the step boundary
+    ///
+    /// A [SubModel](super::SubModel) is inserted as the actors it is made of
+    pub fn insert(&mut self, actor: Box<dyn Task>) {
+        self.inserted.extend(flatten(actor));
+    }
+}
+
+impl Model<Running> {
+    /// Halts the actor loops at the first step boundary from `step` that none of the actors has gone past
+    ///
+    /// The actors that halted at an earlier boundary are resumed until they reach the last one
+    pub(super) async fn halt(&mut self, step: usize) -> Result<Reconfiguration> {
+        let task_handles = self.task_handles.replace(vec![]).unwrap_or_default();
+        let mut boundary = step;
+        loop {
+            self.boundary.store(boundary, SeqCst);
+            match task_handles.iter().map(|handle| handle.halt.tick()).max() {
+                Some(tick) if tick >= boundary => boundary = tick + 1,
+                _ => break,
+            }
+        }
+        let mut joins: FuturesUnordered<_> =
+            task_handles.into_iter().map(TaskHandle::join).collect();
+        let mut actors = vec![];
+        while let Some((outcome, plain, halt)) = joins.next().await {
+            match outcome? {
+                Outcome::Done(report) => {
+                    self.reports.push(*report);
+                    self.finished.push(plain);
+                }
+                Outcome::Halted(actor) if actor.halted() < Some(boundary) => {
+                    joins.push(TaskHandle::spawn(actor, halt).join())
+                }
+                Outcome::Halted(actor) => actors.push((actor, halt)),
+            }
+        }
+        if actors.is_empty() {
+            return Err(ModelError::Ended(boundary));
+        }
+        Ok(Reconfiguration {
+            step: boundary,
+            actors,
+            inserted: vec![],
+            retired: vec![],
+        })
+    }
+    /// Reconfigures the model with the actors halted at the step boundary `step`
+    ///
+    /// The boundary is moved past `step` if some actors have already gone past it.
+    /// The halted actors are given to `reconfigure` and the new layout is [check](Model::check)ed
+    /// before the actors resume their loop, the inserted actors being set up like the others.
+    /// Returns the step at which the model has been reconfigured.
+    /// If either `reconfigure` or the check fails, the model is [stop](Model::stop)ped.
+    pub async fn reconfigure<F>(&mut self, step: usize, reconfigure: F) -> Result<usize>
+    where
+        F: FnOnce(&mut Reconfiguration) -> Result<()>,
+    {
+        let halted = self.halt(step).await;
+        self.boundary.store(usize::MAX, SeqCst);
+        let mut reconfiguration = halted?;
+        let result = reconfigure(&mut reconfiguration);
+        let Reconfiguration {
+            step,
+            mut actors,
+            inserted,
+            retired,
+        } = reconfiguration;
+        let hashes: Vec<_> = retired
+            .iter()
+            .flat_map(|actor| actor.inputs_hashes())
+            .collect();
+        for mut actor in retired {
+            let exit = actor.finish(Exit::Retired).await;
+            self.reports.push(actor.report(exit, self.start.elapsed()));
+        }
+        actors
+            .iter_mut()
+            .for_each(|(actor, _)| actor.prune_outputs(&hashes));
+        let now = tokio::time::Instant::now();
+        let context = self.context();
+        for mut actor in inserted {
+            actor.set_cancellation_token(self.cancellation.clone());
+            actor.set_context(context.clone());
+            if self.profiling {
+                actor.enable_profiling();
+            }
+            if let Some(period) = self.pacing {
+                let start = now.checked_sub(period.mul_f64(step as f64));
+                actor.enable_pacing(start.unwrap_or(now), period);
+            }
+            let halt = Halt::new(self.boundary.clone(), step);
+            actor.set_halt(halt.clone());
+            actors.push((actor, halt));
+        }
+        let result = result.and_then(|_| {
+            check(
+                actors.iter().map(|(actor, _)| actor.as_ref()),
+                self.finished.clone(),
+            )
+        });
+        if result.is_err() {
+            self.stop();
+        }
+        self.task_handles = Some(
+            actors
+                .into_iter()
+                .map(|(actor, halt)| TaskHandle::spawn(actor, halt))
+                .collect(),
+        );
+        result.map(|_| step)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[derive(UID)]
+    enum Source {}
+
+    #[tokio::test]
+    async fn retired_sink_leaves_producer_running() {
+        let mut source: Initiator<_> = Signals::new(1, 100).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        let retired = Logging::<f64>::default().into_arcx();
+        let mut other = Terminator::<_>::new(retired.clone()).name("other");
+        source
+            .add_output()
+            .multiplex(2)
+            .build::<Source>()
+            .into_input(&mut sink)
+            .into_input(&mut other);
+        let mut model = Model::new(vec![Box::new(source), Box::new(sink), Box::new(other)])
+            .check()
+            .unwrap()
+            .run();
+        let step = model
+            .reconfigure(10, |halted| halted.retire("other").map(|_| ()))
+            .await
+            .unwrap();
+        let model = model.await.unwrap();
+        assert_eq!(retired.lock().await.len(), step);
+        assert_eq!(logging.lock().await.len(), 100);
+        assert_eq!(model.reports().len(), 3);
+        assert_eq!(model.abnormal_exits().count(), 0);
+    }
+
+    #[tokio::test]
+    async fn dangling_output_stops_the_model() {
+        let mut source: Initiator<_> = Signals::new(1, usize::MAX).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        source.add_output().build::<Source>().into_input(&mut sink);
+        let mut model = Model::new(vec![Box::new(source), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run();
+        let result = model
+            .reconfigure(10, |halted| {
+                let mut source: Initiator<_> = Signals::new(1, usize::MAX).into();
+                let mut sink = Terminator::<_>::new(logging.clone());
+                source.add_output().build::<Source>().into_input(&mut sink);
+                halted.insert(Box::new(source));
+                Ok(())
+            })
+            .await;
+        assert!(matches!(
+            result,
+            Err(crate::model::ModelError::DanglingOutput { .. })
+        ));
+        model.await.unwrap();
+    }
+}
@@ -0,0 +1,316 @@
+/*!
+# Step-by-step model execution
+
+A [Ready] model can be executed deterministically, one sample of the simulation sampling rate at a time,
+with all the actors running in the same thread.
+
+The actors are sorted in the topological order of the model network,
+where the bootstrapped outputs break the feedback loops.
+At each step, the actors are visited in that order and each one of them is [step](crate::Task::step)ped once,
+collecting, updating and distributing according to its inputs and outputs rates.
+An actor waiting for its inputs, or for its outputs to be read, does nothing until the next step.
+
+The actor loops are driven by the model instead of the [tokio] scheduler,
+so the results are bit-reproducible and the model can be stepped through in a debugger.
+
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::prelude::*;
+let mut source: Initiator<_> = Signals::new(1, 100).into();
+#[derive(UID)]
+enum Source {};
+let mut sampler: Actor<_, 1, 10> = Sampler::<Vec<f64>, Source>::default().into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_, 10>::new(logging.clone());
+source.add_output().build::<Source>().into_input(&mut sampler);
+sampler.add_output().build::<Source>().into_input(&mut sink);
+
+let mut model = Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)])
+    .check()?
+    .stepping()
+    .await;
+model.step(20).await?;
+assert_eq!(logging.lock().await.len(), 2);
+model.run_until(50).await?;
+assert_eq!(model.current_step(), 50);
+let model = model.complete().await?;
+assert_eq!(logging.lock().await.len(), 10);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+*/
+
+use super::{Completed, Model, ModelError, Ready, Result, Stepping};
+use crate::{actor::Exit, Task};
+use chrono::{DateTime, Local, SecondsFormat};
+use std::{collections::HashMap, time::Instant};
+
+/// Sorts the actors in the topological order of the model network
+///
+/// Bootstrapped outputs are ignored and actors within unbroken loops are appended last
+fn topological_sort(actors: Vec<Box<dyn Task>>) -> Vec<Box<dyn Task>> {
+    use crate::actor::plain::PlainOutput::Regular;
+    let plain: Vec<_> = actors.iter().map(|actor| actor.as_plain()).collect();
+    let producers: HashMap<u64, usize> = plain
+        .iter()
+        .enumerate()
+        .flat_map(|(k, actor)| {
+            actor
+                .outputs
+                .iter()
+                .flatten()
+                .filter_map(move |output| match output {
+                    Regular(output) => Some((output.hash, k)),
+                    _ => None,
+                })
+        })
+        .collect();
+    let mut n_dependencies: Vec<usize> = vec![0; actors.len()];
+    let mut consumers: Vec<Vec<usize>> = vec![vec![]; actors.len()];
+    for (k, actor) in plain.iter().enumerate() {
+        for input in actor.inputs.iter().flatten() {
+            if let Some(&producer) = producers.get(&input.hash) {
+                n_dependencies[k] += 1;
+                consumers[producer].push(k);
+            }
+        }
+    }
+    let mut order = vec![];
+    let mut visited = vec![false; actors.len()];
+    while let Some(k) = (0..actors.len()).find(|&k| !visited[k] && n_dependencies[k] == 0) {
+        visited[k] = true;
+        order.push(k);
+        for &consumer in &consumers[k] {
+            n_dependencies[consumer] -= 1;
+        }
+    }
+    order.extend((0..actors.len()).filter(|&k| !visited[k]));
+    let mut actors: Vec<_> = actors.into_iter().map(Some).collect();
+    order.into_iter().filter_map(|k| actors[k].take()).collect()
+}
+
+impl Model<Ready> {
+    /// Returns a model that is executed step-by-step
+    ///
+    /// The actors are sorted and their bootstrapped outputs are sent
+    pub async fn stepping(mut self) -> Model<Stepping> {
+        let mut actors = topological_sort(self.actors.take().unwrap());
+        if self.profiling {
+            actors.iter_mut().for_each(|actor| actor.enable_profiling());
+        }
+        let context = self.context();
+        let now = Instant::now();
+        let mut reports = vec![];
+        let mut k = 0;
+        while k < actors.len() {
+            actors[k].set_context(context.clone());
+            let exit = match actors[k].start().await {
+                Err(e) => Some(Exit::Start(e)),
+                Ok(_) => actors[k].step_bootstrap().await.err().map(Exit::Bootstrap),
+            };
+            if let Some(exit) = exit {
+                let mut actor = actors.remove(k);
+                let exit = actor.finish(exit).await;
+                reports.push(actor.report(exit, now.elapsed()));
+            } else {
+                k += 1;
+            }
+        }
+        Model::<Stepping> {
+            actors: Some(actors),
+            start: now,
+            reports,
+            step: 0,
+            ..self.into_state()
+        }
+    }
+}
+
+impl Model<Stepping> {
+    /// Advances the model by `n` steps
+    ///
+    /// Returns the number of steps that have been performed,
+    /// it is less than `n` if all the actors have completed their tasks
+    pub async fn step(&mut self, n: usize) -> Result<usize> {
+        let mut k = 0;
+        while k < n && !self.is_completed() {
+            let actors = self.actors.as_mut().unwrap();
+            let mut progress = false;
+            let mut i = 0;
+            while i < actors.len() {
+                match actors[i].step().await {
+                    Ok(advanced) => {
+                        progress |= advanced;
+                        i += 1;
+                    }
+                    Err(e) => {
+                        let mut actor = actors.remove(i);
+                        log::info!("{} loop ended: {}", actor.as_plain().client, e);
+                        let exit = actor.finish(Exit::Loop(e)).await;
+                        self.reports.push(actor.report(exit, self.start.elapsed()));
+                        progress = true;
+                    }
+                }
+            }
+            if !progress {
+                return Err(ModelError::Deadlock(
+                    self.step,
+                    actors.iter().map(|actor| actor.as_plain().client).collect(),
+                ));
+            }
+            k += 1;
+            self.step += 1;
+        }
+        Ok(k)
+    }
+    /// Advances the model until the model step is equal to `step`
+    pub async fn run_until(&mut self, step: usize) -> Result<usize> {
+        self.step(step.saturating_sub(self.step)).await
+    }
+    /// Returns the number of steps performed so far
+    pub fn current_step(&self) -> usize {
+        self.step
+    }
+    /// Checks if all the actors have completed their tasks
+    pub fn is_completed(&self) -> bool {
+        self.actors.as_ref().is_none_or(|actors| actors.is_empty())
+    }
+    /// Advances the model until all the actors have completed their tasks
+    ///
+    /// With the [ExitPolicy::Strict](super::ExitPolicy::Strict) policy,
+    /// an error is returned if any actor ended abnormally
+    pub async fn complete(mut self) -> Result<Model<Completed>> {
+        self.step(usize::MAX).await?;
+        let now: DateTime<Local> = Local::now();
+        println!(
+            "[{}<{}>] COMPLETED in {} steps ({})",
+            self.name
+                .as_ref()
+                .unwrap_or(&String::from("Model"))
+                .to_uppercase(),
+            now.to_rfc3339_opts(SecondsFormat::Secs, true),
+            self.step,
+            humantime::format_duration(self.start.elapsed())
+        );
+        Model::<Completed>::from_reports(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        clients::Integrator,
+        model::{ModelError, Ready},
+        prelude::*,
+    };
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    #[derive(UID)]
+    enum Source {}
+    #[derive(UID)]
+    enum Decimated {}
+    #[derive(UID)]
+    enum Integrated {}
+
+    type Log = Arc<Mutex<Logging<f64>>>;
+
+    /// Returns a model with decimated, bootstrapped and batched outputs and the logs of its sinks
+    fn multi_rate() -> (Model<Ready>, [Log; 2]) {
+        let mut source: Initiator<_> = Signals::new(1, 50)
+            .signals(Signal::Ramp { a: 1., b: 0. })
+            .into();
+        let mut sampler: Actor<_, 1, 5> = Sampler::<Vec<f64>, Source, Decimated>::default().into();
+        let mut integrator: Actor<_> = Integrator::<Source>::new(1).gain(0.5).into();
+        let logs = [
+            Logging::<f64>::default().into_arcx(),
+            Logging::<f64>::default().into_arcx(),
+        ];
+        let mut decimated = Terminator::<_, 5>::new(logs[0].clone()).name("decimated");
+        let mut integrated = Terminator::<_>::new(logs[1].clone()).name("integrated");
+        source
+            .add_output()
+            .multiplex(2)
+            .build::<Source>()
+            .into_input(&mut sampler)
+            .into_input(&mut integrator);
+        sampler
+            .add_output()
+            .build::<Decimated>()
+            .into_input(&mut decimated);
+        integrator
+            .add_output()
+            .bootstrap()
+            .batch(3)
+            .build::<Integrated>()
+            .into_input(&mut integrated);
+        let model = Model::new(vec![
+            Box::new(source),
+            Box::new(sampler),
+            Box::new(integrator),
+            Box::new(decimated),
+            Box::new(integrated),
+        ])
+        .check()
+        .unwrap();
+        (model, logs)
+    }
+
+    async fn logged(logs: &[Log; 2]) -> [Vec<f64>; 2] {
+        [logs[0].lock().await.to_vec(), logs[1].lock().await.to_vec()]
+    }
+
+    #[tokio::test]
+    async fn stepping_matches_run() {
+        let (model, reference) = multi_rate();
+        model.run().await.unwrap();
+        let reference = logged(&reference).await;
+        assert_eq!(reference[0].len(), 10);
+        assert_eq!(reference[1].len(), 51);
+
+        let (model, logs) = multi_rate();
+        let mut model = model.stepping().await;
+        assert_eq!(model.step(7).await.unwrap(), 7);
+        assert_eq!(model.run_until(33).await.unwrap(), 26);
+        assert_eq!(model.current_step(), 33);
+        model.complete().await.unwrap();
+        assert_eq!(logged(&logs).await, reference);
+    }
+
+    #[tokio::test]
+    async fn stepping_is_reproducible() {
+        let mut runs = vec![];
+        for _ in 0..2 {
+            let (model, logs) = multi_rate();
+            let model = model.stepping().await.complete().await.unwrap();
+            let steps: Vec<_> = model.reports().iter().map(|report| report.step).collect();
+            runs.push((logged(&logs).await, steps));
+        }
+        assert_eq!(runs[0], runs[1]);
+    }
+
+    #[tokio::test]
+    async fn feedback_without_bootstrap_deadlocks() {
+        let mut forward: Actor<_> = Sampler::<Vec<f64>, Source, Decimated>::default().into();
+        let mut backward: Actor<_> = Sampler::<Vec<f64>, Decimated, Source>::default().into();
+        forward
+            .add_output()
+            .build::<Decimated>()
+            .into_input(&mut backward);
+        backward
+            .add_output()
+            .build::<Source>()
+            .into_input(&mut forward);
+        // the I/O check is bypassed, it cannot handle feedback loops
+        let model: Model<Ready> =
+            Model::new(vec![Box::new(forward), Box::new(backward)]).into_state();
+        let mut model = model.stepping().await;
+        match model.step(1).await {
+            Err(ModelError::Deadlock(step, actors)) => {
+                assert_eq!(step, 0);
+                assert_eq!(actors.len(), 2);
+            }
+            _ => panic!("expected a deadlock"),
+        }
+    }
+}
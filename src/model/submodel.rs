@@ -0,0 +1,506 @@
+/*!
+# Sub-models
+
+A [SubModel] bundles a set of actors already wired together and
+exposes some of their inputs and outputs through typed [Port]s.
+A sub-model is a [Task] and it is added to a [Model](super::Model) alongside the other actors.
+
+A [Port] is an actor which client forwards the data it receives.
+Ports are added to the sub-model with [add_input_port](SubModel::add_input_port) or [add_output_port](SubModel::add_output_port)
+after being connected to the actors of the sub-model and they are retrieved with [port](SubModel::port) to be connected to the actors outside the sub-model.
+When the sub-model is converted into its actors, the ports are removed
+and the actors outside the sub-model are connected directly to the actors inside.
+
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{prelude::*, model::{Port, SubModel}};
+#[derive(UID)]
+enum Samples {};
+#[derive(UID)]
+enum Decimated {};
+
+// the sub-model decimates the `Samples` input into the `Decimated` output
+let mut input: Actor<_> = Port::<Samples>::default().into();
+let mut sampler: Actor<_, 1, 10> = Sampler::<Vec<f64>, Samples, Decimated>::default().into();
+let mut output: Actor<_, 10, 10> = Port::<Decimated>::default().into();
+input.add_output().build::<Samples>().into_input(&mut sampler);
+sampler.add_output().build::<Decimated>().into_input(&mut output);
+let mut decimator = SubModel::new("decimator", vec![Box::new(sampler)])
+    .add_input_port(input)
+    .add_output_port(output);
+
+let mut source: Initiator<_> = Signals::new(1, 100).into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_, 10>::new(logging.clone());
+source
+    .add_output()
+    .build::<Samples>()
+    .into_input(decimator.port::<Samples, 1, 1>().unwrap());
+decimator
+    .port::<Decimated, 10, 10>()
+    .unwrap()
+    .add_output()
+    .build::<Decimated>()
+    .into_input(&mut sink);
+
+Model::new(vec![Box::new(source), Box::new(decimator), Box::new(sink)])
+    .check()?
+    .run()
+    .await?;
+assert_eq!(logging.lock().await.len(), 10);
+# Ok::<(), gmt_dos_actors::model::ModelError>(())
+# });
+```
+
+The [Model](super::Model) converts a sub-model into the actors it is made of, see [into_tasks](SubModel::into_tasks).
+They are run like the other actors of the model,
+each with its own task, and they are grouped together in the model [Graph](super::Graph).
+A port is kept as a relay actor only if it cannot be removed, i.e. if an input port feeds more than one input of the sub-model
+or if the output of the sub-model feeding an output port is also connected to other inputs.
+*/
+
+use crate::{
+    actor::{flatten, plain::PlainActor, ActorReport, AnyTask, Exit},
+    io::{Assoc, Data, Input, Output, Read, UniqueIdentifier, Write},
+    Actor, ActorError, Result, Task, Update,
+};
+use async_trait::async_trait;
+use futures::future::join_all;
+use std::{
+    any::type_name,
+    fmt::{self, Display},
+    sync::Arc,
+    time::Instant,
+};
+
+/// [SubModel] port
+///
+/// The port client writes the last data it has read
+pub struct Port<U: UniqueIdentifier> {
+    data: Option<Arc<Data<U>>>,
+}
+impl<U: UniqueIdentifier> Default for Port<U> {
+    fn default() -> Self {
+        Self { data: None }
+    }
+}
+impl<U: UniqueIdentifier> Update for Port<U> {}
+impl<U: UniqueIdentifier> Read<U> for Port<U> {
+    fn read(&mut self, data: Arc<Data<U>>) {
+        self.data = Some(data);
+    }
+}
+impl<U: UniqueIdentifier> Write<U> for Port<U> {
+    fn write(&mut self) -> Option<Arc<Data<U>>> {
+        self.data.clone()
+    }
+}
+
+/// Direction of a [Port] as seen from outside the [SubModel]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PortKind {
+    /// The port feeds an input of the sub-model
+    Input,
+    /// The port is fed by an output of the sub-model
+    Output,
+}
+
+/// [Port] actor that can be removed from a [SubModel]
+trait PortTask: AnyTask {
+    /// Connects the actor the port reads from or writes to directly to the actors on the other side of the port
+    ///
+    /// The port is given back if it cannot be removed
+    fn splice(
+        self: Box<Self>,
+        kind: PortKind,
+        tasks: &mut [Box<dyn Task>],
+    ) -> Option<Box<dyn Task>>;
+}
+impl<U, const NI: usize, const NO: usize> PortTask for Actor<Port<U>, NI, NO>
+where
+    U: 'static + UniqueIdentifier + Send + Sync,
+    U::Data: Send + Sync,
+{
+    fn splice(
+        mut self: Box<Self>,
+        kind: PortKind,
+        tasks: &mut [Box<dyn Task>],
+    ) -> Option<Box<dyn Task>> {
+        let (&[input_hash], &[output_hash]) = (
+            self.inputs_hashes().as_slice(),
+            self.outputs_hashes().as_slice(),
+        ) else {
+            return Some(self);
+        };
+        let uid = type_name::<U>();
+        match kind {
+            PortKind::Input => {
+                // the input of the sub-model actor is replaced by the port input
+                let readers: usize = tasks
+                    .iter()
+                    .map(|task| {
+                        task.inputs_hashes()
+                            .iter()
+                            .filter(|&&hash| hash == output_hash)
+                            .count()
+                    })
+                    .sum();
+                if readers != 1 {
+                    return Some(self);
+                }
+                let mut input = Task::take_input(self.as_mut(), uid)?;
+                for task in tasks.iter_mut() {
+                    input = task.splice_input(output_hash, input)?;
+                }
+                if let Ok(input) = input.downcast::<Input<Assoc<U>, U>>() {
+                    self.inputs.get_or_insert_with(Vec::new).push(input);
+                }
+            }
+            PortKind::Output => {
+                // the output of the sub-model actor sends to the inputs the port output is connected to
+                let mut output = Task::take_output(self.as_mut(), uid)?;
+                for task in tasks.iter_mut() {
+                    output = task.splice_output(input_hash, output)?;
+                }
+                if let Ok(output) = output.downcast::<Output<Assoc<U>, U>>() {
+                    self.outputs.get_or_insert_with(Vec::new).push(output);
+                }
+            }
+        }
+        Some(self)
+    }
+}
+
+/// Set of actors with typed [Port]s
+#[derive(Default)]
+pub struct SubModel {
+    name: String,
+    actors: Vec<Box<dyn Task>>,
+    ports: Vec<(PortKind, Box<dyn PortTask>)>,
+}
+impl SubModel {
+    /// Creates a new sub-model from a set of actors
+    ///
+    /// The actors may include other sub-models, nested within this one
+    pub fn new<S: Into<String>>(name: S, actors: Vec<Box<dyn Task>>) -> Self {
+        Self {
+            name: name.into(),
+            actors,
+            ports: Vec::new(),
+        }
+    }
+    /// Adds a port feeding an input of the sub-model
+    pub fn add_input_port<U, const NI: usize, const NO: usize>(
+        mut self,
+        port: Actor<Port<U>, NI, NO>,
+    ) -> Self
+    where
+        U: 'static + UniqueIdentifier + Send + Sync,
+        U::Data: Send + Sync,
+    {
+        self.ports.push((PortKind::Input, Box::new(port)));
+        self
+    }
+    /// Adds a port fed by an output of the sub-model
+    pub fn add_output_port<U, const NI: usize, const NO: usize>(
+        mut self,
+        port: Actor<Port<U>, NI, NO>,
+    ) -> Self
+    where
+        U: 'static + UniqueIdentifier + Send + Sync,
+        U::Data: Send + Sync,
+    {
+        self.ports.push((PortKind::Output, Box::new(port)));
+        self
+    }
+    /// Returns the port for the data `U` with inputs and outputs rates `NI` and `NO`
+    pub fn port<U, const NI: usize, const NO: usize>(
+        &mut self,
+    ) -> Option<&mut Actor<Port<U>, NI, NO>>
+    where
+        U: 'static + UniqueIdentifier + Send + Sync,
+        U::Data: Send + Sync,
+    {
+        self.ports.iter_mut().find_map(|(_, port)| {
+            port.as_any_mut()
+                .and_then(|port| port.downcast_mut::<Actor<Port<U>, NI, NO>>())
+        })
+    }
+    /// Returns the name of the sub-model
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// Returns the actors the sub-model is made of, enclosed within the sub-model cluster
+    ///
+    /// The ports are removed, the actors outside the sub-model being connected directly to the actors inside
+    pub fn into_tasks(self) -> Vec<Box<dyn Task>> {
+        let Self {
+            name,
+            actors,
+            ports,
+        } = self;
+        let mut tasks: Vec<_> = actors.into_iter().flat_map(flatten).collect();
+        let relays: Vec<_> = ports
+            .into_iter()
+            .filter_map(|(kind, port)| port.splice(kind, &mut tasks))
+            .collect();
+        tasks
+            .into_iter()
+            .chain(relays)
+            .map(|mut task| {
+                task.enclose(&name);
+                task
+            })
+            .collect()
+    }
+    fn tasks(&self) -> impl Iterator<Item = &dyn Task> {
+        self.actors
+            .iter()
+            .map(|actor| actor.as_ref())
+            .chain(self.ports.iter().map(|(_, port)| port.as_task()))
+    }
+    fn tasks_mut(&mut self) -> impl Iterator<Item = &mut dyn Task> {
+        self.actors
+            .iter_mut()
+            .map(|actor| actor.as_mut() as &mut dyn Task)
+            .chain(self.ports.iter_mut().map(|(_, port)| port.as_task_mut()))
+    }
+}
+impl From<SubModel> for Vec<Box<dyn Task>> {
+    fn from(submodel: SubModel) -> Self {
+        submodel.into_tasks()
+    }
+}
+
+impl Display for SubModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}:", self.name.to_uppercase())?;
+        for task in self.tasks() {
+            write!(f, " {}", task)?;
+        }
+        Ok(())
+    }
+}
+
+/// A [SubModel] run as a [Task] runs its actors and its ports concurrently
+///
+/// Within a [Model](super::Model), the sub-model is replaced by the actors it is made of, see [into_tasks](SubModel::into_tasks)
+#[async_trait]
+impl Task for SubModel {
+    /// Runs the loops of all the actors of the sub-model
+    async fn async_run(&mut self) -> Result<()> {
+        join_all(self.tasks_mut().map(|task| task.async_run()))
+            .await
+            .into_iter()
+            .collect()
+    }
+    fn spawn(mut self) -> tokio::task::JoinHandle<ActorReport> {
+        tokio::spawn(async move { self.task().await })
+    }
+    fn check_inputs(&self) -> Result<()> {
+        self.tasks().try_for_each(|task| task.check_inputs())
+    }
+    fn check_outputs(&self) -> Result<()> {
+        self.tasks().try_for_each(|task| task.check_outputs())
+    }
+    /// Runs the tasks of all the actors of the sub-model
+    ///
+    /// The report exit is the exit of the first actor that ended abnormally
+    async fn task(&mut self) -> ActorReport {
+        let now = Instant::now();
+        let reports = join_all(self.tasks_mut().map(|task| task.task())).await;
+        let step = reports
+            .iter()
+            .map(|report| report.step)
+            .max()
+            .unwrap_or_default();
+        let exit = reports
+            .into_iter()
+            .map(|report| report.exit)
+            .find(|exit| !exit.is_normal())
+            .unwrap_or(Exit::Loop(ActorError::NoData));
+        ActorReport {
+            step,
+            ..self.report(exit, now.elapsed())
+        }
+    }
+    fn n_inputs(&self) -> usize {
+        self.tasks().map(|task| task.n_inputs()).sum()
+    }
+    fn n_outputs(&self) -> usize {
+        self.tasks().map(|task| task.n_outputs()).sum()
+    }
+    fn inputs_hashes(&self) -> Vec<u64> {
+        self.tasks().flat_map(|task| task.inputs_hashes()).collect()
+    }
+    fn outputs_hashes(&self) -> Vec<u64> {
+        self.tasks()
+            .flat_map(|task| task.outputs_hashes())
+            .collect()
+    }
+    /// Returns the sub-model as a single actor with the inputs and outputs of all the actors
+    fn as_plain(&self) -> PlainActor {
+        let plain: Vec<_> = self.tasks().map(|task| task.as_plain()).collect();
+        PlainActor {
+            client: self.name.clone(),
+            inputs_rate: plain
+                .iter()
+                .map(|p| p.inputs_rate)
+                .min()
+                .unwrap_or_default(),
+            outputs_rate: plain
+                .iter()
+                .map(|p| p.outputs_rate)
+                .min()
+                .unwrap_or_default(),
+            inputs: Some(
+                plain
+                    .iter()
+                    .flat_map(|p| p.inputs.clone())
+                    .flatten()
+                    .collect(),
+            ),
+            outputs: Some(
+                plain
+                    .iter()
+                    .flat_map(|p| p.outputs.clone())
+                    .flatten()
+                    .collect(),
+            ),
+            hash: 0,
+            clusters: Vec::new(),
+        }
+    }
+    fn take_tasks(&mut self) -> Option<Vec<Box<dyn Task>>> {
+        Some(std::mem::take(self).into_tasks())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clients::{Integrator, Logging, Sampler, Signal, Signals},
+        model::Model,
+        AddOuput, ArcMutex, Initiator, IntoInputs, Terminator, UID,
+    };
+
+    #[derive(UID)]
+    enum Samples {}
+    #[derive(UID)]
+    enum Decimated {}
+    #[derive(UID)]
+    enum Integrated {}
+
+    #[tokio::test]
+    async fn ports_are_removed() {
+        let mut input: Actor<_> = Port::<Samples>::default().into();
+        let mut sampler: Actor<_, 1, 10> =
+            Sampler::<Vec<f64>, Samples, Decimated>::default().into();
+        let mut output: Actor<_, 10, 10> = Port::<Decimated>::default().into();
+        input
+            .add_output()
+            .build::<Samples>()
+            .into_input(&mut sampler);
+        sampler
+            .add_output()
+            .build::<Decimated>()
+            .into_input(&mut output);
+        let mut decimator = SubModel::new("decimator", vec![Box::new(sampler)])
+            .add_input_port(input)
+            .add_output_port(output);
+
+        let mut source: Initiator<_> = Signals::new(1, 100).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_, 10>::new(logging.clone());
+        source
+            .add_output()
+            .build::<Samples>()
+            .into_input(decimator.port::<Samples, 1, 1>().unwrap());
+        decimator
+            .port::<Decimated, 10, 10>()
+            .unwrap()
+            .add_output()
+            .build::<Decimated>()
+            .into_input(&mut sink);
+
+        let tasks = decimator.into_tasks();
+        assert_eq!(tasks.len(), 1);
+        assert_eq!(tasks[0].as_plain().clusters, ["decimator"]);
+        let mut actors: Vec<Box<dyn Task>> = vec![Box::new(source), Box::new(sink)];
+        actors.extend(tasks);
+        Model::new(actors).check().unwrap().run().await.unwrap();
+        assert_eq!(logging.lock().await.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn multiplexed_port_is_kept() {
+        let mut input: Actor<_> = Port::<Samples>::default().into();
+        let first = Logging::<f64>::default().into_arcx();
+        let second = Logging::<f64>::default().into_arcx();
+        let mut first_sink = Terminator::<_>::new(first.clone());
+        let mut second_sink = Terminator::<_>::new(second.clone());
+        input
+            .add_output()
+            .multiplex(2)
+            .build::<Samples>()
+            .into_input(&mut first_sink)
+            .into_input(&mut second_sink);
+        let mut sinks = SubModel::new("sinks", vec![Box::new(first_sink), Box::new(second_sink)])
+            .add_input_port(input);
+
+        let mut source: Initiator<_> = Signals::new(1, 10).into();
+        source
+            .add_output()
+            .build::<Samples>()
+            .into_input(sinks.port::<Samples, 1, 1>().unwrap());
+
+        let mut actors: Vec<Box<dyn Task>> = vec![Box::new(source)];
+        actors.extend(Vec::from(sinks));
+        assert_eq!(actors.len(), 4);
+        Model::new(actors).check().unwrap().run().await.unwrap();
+        assert_eq!(first.lock().await.len(), 10);
+        assert_eq!(second.lock().await.len(), 10);
+    }
+
+    #[tokio::test]
+    async fn bootstrapped_port() {
+        let mut input: Actor<_> = Port::<Samples>::default().into();
+        let mut integrator: Actor<_> = Integrator::<Samples>::new(1).gain(1.).into();
+        let mut output: Actor<_> = Port::<Integrated>::default().into();
+        input
+            .add_output()
+            .build::<Samples>()
+            .into_input(&mut integrator);
+        integrator
+            .add_output()
+            .build::<Integrated>()
+            .into_input(&mut output);
+        let mut integral = SubModel::new("integral", vec![Box::new(integrator)])
+            .add_input_port(input)
+            .add_output_port(output);
+
+        let mut source: Initiator<_> = Signals::new(1, 4).signals(Signal::Constant(1.)).into();
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        source
+            .add_output()
+            .build::<Samples>()
+            .into_input(integral.port::<Samples, 1, 1>().unwrap());
+        integral
+            .port::<Integrated, 1, 1>()
+            .unwrap()
+            .add_output()
+            .bootstrap()
+            .build::<Integrated>()
+            .into_input(&mut sink);
+
+        Model::new(vec![Box::new(source), Box::new(integral), Box::new(sink)])
+            .check()
+            .unwrap()
+            .run()
+            .await
+            .unwrap();
+        assert_eq!(logging.lock().await.to_vec(), [0., -1., -2., -3., -4.]);
+    }
+}
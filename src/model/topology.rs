@@ -0,0 +1,713 @@
+/*!
+# Model topology
+
+The [Topology] of a [Model](super::Model) lists the actors with their inputs and outputs rates
+and the links between the actors outputs and inputs,
+with the name of the data [UID](crate::UniqueIdentifier), the sampling rate, the bootstrap flag and the number of channels.
+
+The topology is written to JSON, [Mermaid](https://mermaid.js.org), [GraphML](http://graphml.graphdrawing.org) or SVG,
+the SVG image being laid out without any external tool:
+```
+use gmt_dos_actors::prelude::*;
+#[derive(UID)]
+enum Source {};
+let mut source: Initiator<_> = Signals::new(1, 100).into();
+let mut sampler: Actor<_, 1, 10> = Sampler::<Vec<f64>, Source>::default().into();
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_, 10>::new(logging);
+source.add_output().build::<Source>().into_input(&mut sampler);
+sampler.add_output().build::<Source>().into_input(&mut sink);
+let model = Model::new(vec![Box::new(source), Box::new(sampler), Box::new(sink)]);
+let topology = model.topology().unwrap();
+assert_eq!(topology.links.len(), 2);
+assert!(topology.to_mermaid().contains(r#"a1 -- "Source @10" --> a2"#));
+assert!(topology
+    .to_json()
+    .contains(r#"{"uid":"Source","from":"Signals","to":"Sampler","rate":1,"bootstrap":false}"#));
+assert_eq!(topology.to_graphml().matches("<edge ").count(), 2);
+let svg = topology.to_svg();
+assert!(svg.starts_with("<svg"));
+```
+
+Two versions of a model are compared with [Topology::diff]:
+```
+use gmt_dos_actors::prelude::*;
+#[derive(UID)]
+enum Source {};
+let model = |rate: usize| {
+    let mut source: Initiator<_> = Signals::new(1, 100).into();
+    let logging = Logging::<f64>::default().into_arcx();
+    let mut sink = Terminator::<_>::new(logging);
+    source.add_output().rate(rate).build::<Source>().into_input_at(&mut sink);
+    Model::new(vec![Box::new(source), Box::new(sink)])
+};
+let diff = model(1).topology().unwrap().diff(&model(2).topology().unwrap());
+assert_eq!(diff.added_links.len(), 1);
+assert_eq!(diff.removed_links.len(), 1);
+assert_eq!(diff.changed_actors, ["Signals", "Logging"]);
+println!("{diff}");
+```
+*/
+
+use super::profile::escape;
+use crate::actor::plain::{PlainActor, PlainIO, PlainOutput};
+use std::{collections::HashMap, fmt, fs::File, io::Write, path::Path};
+
+/// Input or output of an actor
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct NodeIO {
+    /// Data UID name
+    pub uid: String,
+    /// Sampling rate
+    pub rate: usize,
+    /// Number of channels
+    pub multiplex: usize,
+    /// Bootstrap flag of an output
+    pub bootstrap: bool,
+}
+/// Actor of a [Topology]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Node {
+    /// Actor name, unique within the topology
+    pub name: String,
+    /// Inputs rate
+    pub inputs_rate: usize,
+    /// Outputs rate
+    pub outputs_rate: usize,
+    /// Sub-models the actor belongs to, from the outermost
+    pub clusters: Vec<String>,
+    pub inputs: Vec<NodeIO>,
+    pub outputs: Vec<NodeIO>,
+}
+/// Link from an actor output to an actor input
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Link {
+    /// Data UID name
+    pub uid: String,
+    /// Name of the actor the output belongs to
+    pub from: String,
+    /// Name of the actor the input belongs to
+    pub to: String,
+    /// Sampling rate
+    pub rate: usize,
+    /// Bootstrap flag of the output
+    pub bootstrap: bool,
+}
+
+/// [Model](super::Model) topology
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Topology {
+    /// Model name
+    pub name: Option<String>,
+    pub actors: Vec<Node>,
+    pub links: Vec<Link>,
+}
+
+/// Returns the name of a data UID without the module path
+fn uid_name(io: &PlainIO) -> String {
+    io.name.split("::").last().unwrap().to_string()
+}
+
+impl Topology {
+    /// Creates the topology of the actors
+    ///
+    /// The actors sharing the same name are numbered from the second one on, i.e. `name #2`, `name #3`, ...
+    pub(crate) fn new(name: Option<String>, actors: &[PlainActor]) -> Self {
+        let mut count: HashMap<String, usize> = HashMap::new();
+        let mut producers: HashMap<u64, (usize, &PlainIO, bool)> = HashMap::new();
+        let mut nodes = vec![];
+        for (k, actor) in actors.iter().enumerate() {
+            let client = super::client_name(&actor.client);
+            let n = count.entry(client.clone()).or_default();
+            *n += 1;
+            let name = if *n > 1 {
+                format!("{client} #{n}")
+            } else {
+                client
+            };
+            let outputs = actor
+                .outputs
+                .iter()
+                .flatten()
+                .map(|output| {
+                    let (io, bootstrap) = match output {
+                        PlainOutput::Bootstrap(io) => (io, true),
+                        PlainOutput::Regular(io) => (io, false),
+                    };
+                    producers.insert(io.hash, (k, io, bootstrap));
+                    NodeIO {
+                        uid: uid_name(io),
+                        rate: io.rate,
+                        multiplex: io.multiplex,
+                        bootstrap,
+                    }
+                })
+                .collect();
+            let inputs = actor
+                .inputs
+                .iter()
+                .flatten()
+                .map(|io| NodeIO {
+                    uid: uid_name(io),
+                    rate: io.rate,
+                    multiplex: io.multiplex,
+                    bootstrap: false,
+                })
+                .collect();
+            nodes.push(Node {
+                name,
+                inputs_rate: actor.inputs_rate,
+                outputs_rate: actor.outputs_rate,
+                clusters: actor.clusters.clone(),
+                inputs,
+                outputs,
+            });
+        }
+        let links = actors
+            .iter()
+            .enumerate()
+            .flat_map(|(k, actor)| actor.inputs.iter().flatten().map(move |io| (k, io)))
+            .filter_map(|(k, input)| {
+                producers
+                    .get(&input.hash)
+                    .map(|&(j, output, bootstrap)| Link {
+                        uid: uid_name(output),
+                        from: nodes[j].name.clone(),
+                        to: nodes[k].name.clone(),
+                        rate: input.rate,
+                        bootstrap,
+                    })
+            })
+            .collect();
+        Self {
+            name,
+            actors: nodes,
+            links,
+        }
+    }
+    /// Returns the index of the actor `name`
+    fn index(&self, name: &str) -> Option<usize> {
+        self.actors.iter().position(|actor| actor.name == name)
+    }
+    /// Returns the topology in the JSON format
+    ///
+    /// Each actor and each link is written on a separate line so the JSON files of 2 models can be compared line by line
+    pub fn to_json(&self) -> String {
+        let ios = |ios: &[NodeIO]| {
+            ios.iter()
+                .map(|io| {
+                    format!(
+                        r#"{{"uid":"{}","rate":{},"multiplex":{},"bootstrap":{}}}"#,
+                        escape(&io.uid),
+                        io.rate,
+                        io.multiplex,
+                        io.bootstrap
+                    )
+                })
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let actors: Vec<_> = self
+            .actors
+            .iter()
+            .map(|actor| {
+                format!(
+                    r#"    {{"name":"{}","inputs_rate":{},"outputs_rate":{},"clusters":[{}],"inputs":[{}],"outputs":[{}]}}"#,
+                    escape(&actor.name),
+                    actor.inputs_rate,
+                    actor.outputs_rate,
+                    actor
+                        .clusters
+                        .iter()
+                        .map(|cluster| format!(r#""{}""#, escape(cluster)))
+                        .collect::<Vec<_>>()
+                        .join(","),
+                    ios(&actor.inputs),
+                    ios(&actor.outputs)
+                )
+            })
+            .collect();
+        let links: Vec<_> = self
+            .links
+            .iter()
+            .map(|link| {
+                format!(
+                    r#"    {{"uid":"{}","from":"{}","to":"{}","rate":{},"bootstrap":{}}}"#,
+                    escape(&link.uid),
+                    escape(&link.from),
+                    escape(&link.to),
+                    link.rate,
+                    link.bootstrap
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"name\": {},\n  \"actors\": [\n{}\n  ],\n  \"links\": [\n{}\n  ]\n}}\n",
+            self.name
+                .as_ref()
+                .map_or("null".to_string(), |name| format!(r#""{}""#, escape(name))),
+            actors.join(",\n"),
+            links.join(",\n")
+        )
+    }
+    /// Returns the topology as a [Mermaid](https://mermaid.js.org) flowchart
+    ///
+    /// The links are labeled with the data UID and the sampling rate, the bootstrapped links are drawn with thick lines
+    pub fn to_mermaid(&self) -> String {
+        let mut lines = vec!["flowchart LR".to_string()];
+        lines.extend(
+            self.actors
+                .iter()
+                .enumerate()
+                .filter(|(_, actor)| actor.clusters.is_empty())
+                .map(|(k, actor)| format!(r#"  a{k}["{}"]"#, mermaid_escape(&actor.name))),
+        );
+        self.mermaid_clusters(&[], &mut 0, &mut lines);
+        lines.extend(self.links.iter().filter_map(|link| {
+            let (from, to) = (self.index(&link.from)?, self.index(&link.to)?);
+            let arrow = if link.bootstrap { "==" } else { "--" };
+            Some(format!(
+                r#"  a{from} {arrow} "{} @{}" {arrow}> a{to}"#,
+                mermaid_escape(&link.uid),
+                link.rate
+            ))
+        }));
+        lines.join("\n") + "\n"
+    }
+    /// Writes the Mermaid subgraphs of the sub-models nested within the sub-model at `path`
+    fn mermaid_clusters(&self, path: &[String], id: &mut usize, lines: &mut Vec<String>) {
+        for name in self.cluster_names(path) {
+            let path = [path, std::slice::from_ref(&name)].concat();
+            *id += 1;
+            lines.push(format!(r#"  subgraph c{id}["{}"]"#, mermaid_escape(&name)));
+            lines.extend(
+                self.actors
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, actor)| actor.clusters == path)
+                    .map(|(k, actor)| format!(r#"  a{k}["{}"]"#, mermaid_escape(&actor.name))),
+            );
+            self.mermaid_clusters(&path, id, lines);
+            lines.push("  end".to_string());
+        }
+    }
+    /// Returns the names of the sub-models nested within the sub-model at `path`
+    fn cluster_names(&self, path: &[String]) -> Vec<String> {
+        let mut names: Vec<String> = vec![];
+        for actor in &self.actors {
+            if actor.clusters.len() > path.len() && actor.clusters.starts_with(path) {
+                let name = &actor.clusters[path.len()];
+                if !names.contains(name) {
+                    names.push(name.clone());
+                }
+            }
+        }
+        names
+    }
+    /// Returns the topology in the [GraphML](http://graphml.graphdrawing.org) format
+    pub fn to_graphml(&self) -> String {
+        let mut xml = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="inputs_rate" for="node" attr.name="inputs_rate" attr.type="int"/>
+  <key id="outputs_rate" for="node" attr.name="outputs_rate" attr.type="int"/>
+  <key id="clusters" for="node" attr.name="clusters" attr.type="string"/>
+  <key id="uid" for="edge" attr.name="uid" attr.type="string"/>
+  <key id="rate" for="edge" attr.name="rate" attr.type="int"/>
+  <key id="bootstrap" for="edge" attr.name="bootstrap" attr.type="boolean"/>
+"#,
+        );
+        xml.push_str(&format!(
+            "  <graph id=\"{}\" edgedefault=\"directed\">\n",
+            xml_escape(self.name.as_deref().unwrap_or("model"))
+        ));
+        for (k, actor) in self.actors.iter().enumerate() {
+            xml.push_str(&format!(
+                "    <node id=\"a{k}\"><data key=\"name\">{}</data><data key=\"inputs_rate\">{}</data><data key=\"outputs_rate\">{}</data><data key=\"clusters\">{}</data></node>\n",
+                xml_escape(&actor.name),
+                actor.inputs_rate,
+                actor.outputs_rate,
+                xml_escape(&actor.clusters.join("/"))
+            ));
+        }
+        for (k, link) in self.links.iter().enumerate() {
+            if let (Some(from), Some(to)) = (self.index(&link.from), self.index(&link.to)) {
+                xml.push_str(&format!(
+                    "    <edge id=\"l{k}\" source=\"a{from}\" target=\"a{to}\"><data key=\"uid\">{}</data><data key=\"rate\">{}</data><data key=\"bootstrap\">{}</data></edge>\n",
+                    xml_escape(&link.uid),
+                    link.rate,
+                    link.bootstrap
+                ));
+            }
+        }
+        xml.push_str("  </graph>\n</graphml>\n");
+        xml
+    }
+    /// Returns the column of each actor in the SVG image
+    ///
+    /// An actor is laid out one column right of the actors it receives data from,
+    /// the links closing the feedback loops are ignored
+    fn columns(&self) -> Vec<usize> {
+        let n = self.actors.len();
+        let mut next: Vec<Vec<usize>> = vec![vec![]; n];
+        for link in &self.links {
+            if let (Some(from), Some(to)) = (self.index(&link.from), self.index(&link.to)) {
+                if from != to && !next[from].contains(&to) {
+                    next[from].push(to);
+                }
+            }
+        }
+        // depth-first search of the links closing the loops, the links to an actor still being visited
+        fn visit(k: usize, next: &[Vec<usize>], state: &mut [u8], loops: &mut Vec<(usize, usize)>) {
+            state[k] = 1;
+            for &j in &next[k] {
+                match state[j] {
+                    0 => visit(j, next, state, loops),
+                    1 => loops.push((k, j)),
+                    _ => (),
+                }
+            }
+            state[k] = 2;
+        }
+        let mut state = vec![0u8; n];
+        let mut loops = vec![];
+        for k in 0..n {
+            if state[k] == 0 {
+                visit(k, &next, &mut state, &mut loops);
+            }
+        }
+        let mut columns = vec![0; n];
+        for _ in 0..n {
+            let mut moved = false;
+            for (from, to) in next
+                .iter()
+                .enumerate()
+                .flat_map(|(from, next)| next.iter().map(move |&to| (from, to)))
+                .filter(|link| !loops.contains(link))
+            {
+                if columns[to] <= columns[from] {
+                    columns[to] = columns[from] + 1;
+                    moved = true;
+                }
+            }
+            if !moved {
+                break;
+            }
+        }
+        columns
+    }
+    /// Returns the topology as a SVG image
+    ///
+    /// The actors are laid out in columns from the inputs to the outputs of the model,
+    /// the bootstrapped links are drawn with dashed lines
+    pub fn to_svg(&self) -> String {
+        const WIDTH: usize = 180;
+        const HEIGHT: usize = 40;
+        const DX: usize = 260;
+        const DY: usize = 80;
+        const MARGIN: usize = 40;
+        let columns = self.columns();
+        let mut rows: HashMap<usize, usize> = HashMap::new();
+        let positions: Vec<(usize, usize)> = columns
+            .iter()
+            .map(|&column| {
+                let row = rows.entry(column).or_default();
+                *row += 1;
+                (MARGIN + column * DX, MARGIN + (*row - 1) * DY)
+            })
+            .collect();
+        let width = 2 * MARGIN + WIDTH + DX * columns.iter().max().copied().unwrap_or_default();
+        let height =
+            2 * MARGIN + HEIGHT + DY / 2 + DY * (rows.values().max().copied().unwrap_or(1) - 1);
+        let mut svg = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="sans-serif" font-size="12">
+  <defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#555"/></marker></defs>
+  <rect width="100%" height="100%" fill="white"/>
+"##
+        );
+        if let Some(name) = &self.name {
+            svg.push_str(&format!(
+                "  <text x=\"{MARGIN}\" y=\"{}\" font-size=\"14\">{}</text>\n",
+                MARGIN / 2,
+                xml_escape(name)
+            ));
+        }
+        for link in &self.links {
+            let (Some(from), Some(to)) = (self.index(&link.from), self.index(&link.to)) else {
+                continue;
+            };
+            let ((x0, y0), (x1, y1)) = (positions[from], positions[to]);
+            let (x0, y0, x1, y1) = (x0 + WIDTH, y0 + HEIGHT / 2, x1, y1 + HEIGHT / 2);
+            let (path, (xl, yl)) = if x1 > x0 {
+                let xm = (x0 + x1) / 2;
+                (
+                    format!("M {x0} {y0} C {xm} {y0}, {xm} {y1}, {x1} {y1}"),
+                    (xm, (y0 + y1) / 2 - 4),
+                )
+            } else {
+                // the link goes back below the actors, the label is written under the middle of the curve
+                let yb = y0.max(y1) + DY / 2;
+                (
+                    format!(
+                        "M {x0} {y0} C {} {yb}, {} {yb}, {x1} {y1}",
+                        x0 + DX / 4,
+                        x1.saturating_sub(DX / 4)
+                    ),
+                    ((x0 + x1) / 2, (y0 + y1) / 8 + 3 * yb / 4 + 14),
+                )
+            };
+            let dash = if link.bootstrap {
+                r#" stroke-dasharray="6 4""#
+            } else {
+                ""
+            };
+            svg.push_str(&format!(
+                "  <path d=\"{path}\" fill=\"none\" stroke=\"#555\"{dash} marker-end=\"url(#arrow)\"/>\n"
+            ));
+            svg.push_str(&format!(
+                "  <text x=\"{xl}\" y=\"{yl}\" text-anchor=\"middle\" fill=\"#333\">{} @{}</text>\n",
+                xml_escape(&link.uid),
+                link.rate
+            ));
+        }
+        for (actor, (x, y)) in self.actors.iter().zip(&positions) {
+            svg.push_str(&format!(
+                "  <g><rect x=\"{x}\" y=\"{y}\" width=\"{WIDTH}\" height=\"{HEIGHT}\" rx=\"8\" fill=\"lightgray\" stroke=\"#333\"/><text x=\"{}\" y=\"{}\" text-anchor=\"middle\" dominant-baseline=\"middle\">{}</text></g>\n",
+                x + WIDTH / 2,
+                y + HEIGHT / 2,
+                xml_escape(&actor.name)
+            ));
+        }
+        svg.push_str("</svg>\n");
+        svg
+    }
+    /// Writes the output of [Topology::to_svg()] to a file
+    pub fn to_svg_file<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut file = File::create(path)?;
+        write!(&mut file, "{}", self.to_svg())
+    }
+    /// Returns the differences from this topology to the `other` topology
+    ///
+    /// The actors are matched by name and the links by UID and actors names
+    pub fn diff(&self, other: &Topology) -> TopologyDiff {
+        let find = |topology: &Topology, name: &str| {
+            topology
+                .actors
+                .iter()
+                .find(|actor| actor.name == name)
+                .cloned()
+        };
+        TopologyDiff {
+            added_actors: other
+                .actors
+                .iter()
+                .filter(|actor| self.index(&actor.name).is_none())
+                .map(|actor| actor.name.clone())
+                .collect(),
+            removed_actors: self
+                .actors
+                .iter()
+                .filter(|actor| other.index(&actor.name).is_none())
+                .map(|actor| actor.name.clone())
+                .collect(),
+            changed_actors: self
+                .actors
+                .iter()
+                .filter(|&actor| find(other, &actor.name).is_some_and(|other| other != *actor))
+                .map(|actor| actor.name.clone())
+                .collect(),
+            added_links: other
+                .links
+                .iter()
+                .filter(|link| !self.links.contains(link))
+                .cloned()
+                .collect(),
+            removed_links: self
+                .links
+                .iter()
+                .filter(|link| !other.links.contains(link))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+/// Differences between two [Topology]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TopologyDiff {
+    /// Names of the actors only in the other topology
+    pub added_actors: Vec<String>,
+    /// Names of the actors only in this topology
+    pub removed_actors: Vec<String>,
+    /// Names of the actors in both topologies with different rates, inputs or outputs
+    pub changed_actors: Vec<String>,
+    /// Links only in the other topology
+    pub added_links: Vec<Link>,
+    /// Links only in this topology
+    pub removed_links: Vec<Link>,
+}
+impl TopologyDiff {
+    /// Checks if both topologies are the same
+    pub fn is_empty(&self) -> bool {
+        self.added_actors.is_empty()
+            && self.removed_actors.is_empty()
+            && self.changed_actors.is_empty()
+            && self.added_links.is_empty()
+            && self.removed_links.is_empty()
+    }
+}
+impl fmt::Display for Link {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} -> {} [{} @{}{}]",
+            self.from,
+            self.to,
+            self.uid,
+            self.rate,
+            if self.bootstrap { ", bootstrap" } else { "" }
+        )
+    }
+}
+impl fmt::Display for TopologyDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in &self.removed_actors {
+            writeln!(f, "- actor {name}")?;
+        }
+        for name in &self.added_actors {
+            writeln!(f, "+ actor {name}")?;
+        }
+        for name in &self.changed_actors {
+            writeln!(f, "~ actor {name}")?;
+        }
+        for link in &self.removed_links {
+            writeln!(f, "- link {link}")?;
+        }
+        for link in &self.added_links {
+            writeln!(f, "+ link {link}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Escapes a string for XML
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+/// Escapes a string for a Mermaid label
+fn mermaid_escape(s: &str) -> String {
+    s.replace('"', "#quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Returns the topology of a source bootstrapping `Data` to a sink every 2 samples
+    fn two_actors() -> Topology {
+        let io = |bootstrap| NodeIO {
+            uid: "Data".to_string(),
+            rate: 2,
+            multiplex: 1,
+            bootstrap,
+        };
+        Topology {
+            name: Some("golden".to_string()),
+            actors: vec![
+                Node {
+                    name: "Source".to_string(),
+                    inputs_rate: 0,
+                    outputs_rate: 2,
+                    clusters: vec![],
+                    inputs: vec![],
+                    outputs: vec![io(true)],
+                },
+                Node {
+                    name: "Sink".to_string(),
+                    inputs_rate: 2,
+                    outputs_rate: 0,
+                    clusters: vec![],
+                    inputs: vec![io(false)],
+                    outputs: vec![],
+                },
+            ],
+            links: vec![Link {
+                uid: "Data".to_string(),
+                from: "Source".to_string(),
+                to: "Sink".to_string(),
+                rate: 2,
+                bootstrap: true,
+            }],
+        }
+    }
+
+    #[test]
+    fn json() {
+        let expected = r#"{
+  "name": "golden",
+  "actors": [
+    {"name":"Source","inputs_rate":0,"outputs_rate":2,"clusters":[],"inputs":[],"outputs":[{"uid":"Data","rate":2,"multiplex":1,"bootstrap":true}]},
+    {"name":"Sink","inputs_rate":2,"outputs_rate":0,"clusters":[],"inputs":[{"uid":"Data","rate":2,"multiplex":1,"bootstrap":false}],"outputs":[]}
+  ],
+  "links": [
+    {"uid":"Data","from":"Source","to":"Sink","rate":2,"bootstrap":true}
+  ]
+}
+"#;
+        assert_eq!(two_actors().to_json(), expected);
+    }
+
+    #[test]
+    fn mermaid() {
+        let expected = r#"flowchart LR
+  a0["Source"]
+  a1["Sink"]
+  a0 == "Data @2" ==> a1
+"#;
+        assert_eq!(two_actors().to_mermaid(), expected);
+    }
+
+    #[test]
+    fn graphml() {
+        let expected = r#"<?xml version="1.0" encoding="UTF-8"?>
+<graphml xmlns="http://graphml.graphdrawing.org/xmlns">
+  <key id="name" for="node" attr.name="name" attr.type="string"/>
+  <key id="inputs_rate" for="node" attr.name="inputs_rate" attr.type="int"/>
+  <key id="outputs_rate" for="node" attr.name="outputs_rate" attr.type="int"/>
+  <key id="clusters" for="node" attr.name="clusters" attr.type="string"/>
+  <key id="uid" for="edge" attr.name="uid" attr.type="string"/>
+  <key id="rate" for="edge" attr.name="rate" attr.type="int"/>
+  <key id="bootstrap" for="edge" attr.name="bootstrap" attr.type="boolean"/>
+  <graph id="golden" edgedefault="directed">
+    <node id="a0"><data key="name">Source</data><data key="inputs_rate">0</data><data key="outputs_rate">2</data><data key="clusters"></data></node>
+    <node id="a1"><data key="name">Sink</data><data key="inputs_rate">2</data><data key="outputs_rate">0</data><data key="clusters"></data></node>
+    <edge id="l0" source="a0" target="a1"><data key="uid">Data</data><data key="rate">2</data><data key="bootstrap">true</data></edge>
+  </graph>
+</graphml>
+"#;
+        assert_eq!(two_actors().to_graphml(), expected);
+    }
+
+    #[test]
+    fn svg() {
+        let expected = r##"<svg xmlns="http://www.w3.org/2000/svg" width="520" height="160" viewBox="0 0 520 160" font-family="sans-serif" font-size="12">
+  <defs><marker id="arrow" viewBox="0 0 10 10" refX="10" refY="5" markerWidth="8" markerHeight="8" orient="auto-start-reverse"><path d="M 0 0 L 10 5 L 0 10 z" fill="#555"/></marker></defs>
+  <rect width="100%" height="100%" fill="white"/>
+  <text x="40" y="20" font-size="14">golden</text>
+  <path d="M 220 60 C 260 60, 260 60, 300 60" fill="none" stroke="#555" stroke-dasharray="6 4" marker-end="url(#arrow)"/>
+  <text x="260" y="56" text-anchor="middle" fill="#333">Data @2</text>
+  <g><rect x="40" y="40" width="180" height="40" rx="8" fill="lightgray" stroke="#333"/><text x="130" y="60" text-anchor="middle" dominant-baseline="middle">Source</text></g>
+  <g><rect x="300" y="40" width="180" height="40" rx="8" fill="lightgray" stroke="#333"/><text x="390" y="60" text-anchor="middle" dominant-baseline="middle">Sink</text></g>
+</svg>
+"##;
+        assert_eq!(two_actors().to_svg(), expected);
+    }
+}
@@ -0,0 +1,158 @@
+//! Inputs and outputs wiring analysis of the model network
+
+use super::{ModelError, Result};
+use crate::actor::plain::{PlainActor, PlainOutput};
+use std::collections::HashMap;
+
+/// Checks that each output channel is connected to an input and that each input has a producer
+///
+/// Both must belong to actors of the model and the input must be sampled at the rate of the output,
+/// that rate being a multiple of the inputs rate of the actor
+pub(super) fn check(actors: &[PlainActor]) -> Result<()> {
+    let mut n_inputs: HashMap<u64, usize> = HashMap::new();
+    for input in actors
+        .iter()
+        .flat_map(|actor| actor.inputs.iter().flatten())
+    {
+        *n_inputs.entry(input.hash).or_default() += 1;
+    }
+    let mut n_outputs: HashMap<u64, usize> = HashMap::new();
+    let mut rates: HashMap<u64, usize> = HashMap::new();
+    for actor in actors {
+        for output in actor.outputs.iter().flatten() {
+            let (PlainOutput::Bootstrap(io) | PlainOutput::Regular(io)) = output;
+            let available = n_inputs.entry(io.hash).or_default();
+            if *available < io.multiplex {
+                return Err(ModelError::DanglingOutput {
+                    actor: actor.client.clone(),
+                    output: io.name.clone(),
+                    index: *available,
+                });
+            }
+            *available -= io.multiplex;
+            *n_outputs.entry(io.hash).or_default() += io.multiplex;
+            rates.insert(io.hash, io.rate);
+        }
+    }
+    for actor in actors {
+        for input in actor.inputs.iter().flatten() {
+            match n_outputs.get_mut(&input.hash) {
+                Some(available) if *available > 0 => *available -= 1,
+                _ => {
+                    return Err(ModelError::OrphanInput {
+                        actor: actor.client.clone(),
+                        input: input.name.clone(),
+                    })
+                }
+            }
+            let output_rate = rates[&input.hash];
+            if input.rate != output_rate {
+                return Err(ModelError::RateMismatch {
+                    actor: actor.client.clone(),
+                    input: input.name.clone(),
+                    rate: input.rate,
+                    output_rate,
+                });
+            }
+            if input.rate == 0 || !input.rate.is_multiple_of(actor.inputs_rate) {
+                return Err(ModelError::InputRate {
+                    actor: actor.client.clone(),
+                    input: input.name.clone(),
+                    rate: input.rate,
+                    inputs_rate: actor.inputs_rate,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actor::plain::PlainIO;
+
+    fn io(hash: u64, rate: usize) -> PlainIO {
+        PlainIO::new(format!("IO#{hash}"), hash).rate(rate)
+    }
+
+    fn actor(
+        client: &str,
+        inputs_rate: usize,
+        inputs: Vec<PlainIO>,
+        outputs: Vec<PlainIO>,
+    ) -> PlainActor {
+        PlainActor {
+            client: client.to_string(),
+            inputs_rate,
+            outputs_rate: outputs.first().map_or(0, |io| io.rate),
+            inputs: (!inputs.is_empty()).then_some(inputs),
+            outputs: (!outputs.is_empty())
+                .then(|| outputs.into_iter().map(PlainOutput::Regular).collect()),
+            hash: 0,
+            clusters: vec![],
+        }
+    }
+
+    /// Returns a source with an output sampled at rate 2 multiplexed to `n` sinks
+    fn source(n: usize) -> PlainActor {
+        actor("source", 0, vec![], vec![io(1, 2).multiplex(n)])
+    }
+
+    #[test]
+    fn valid_wiring() {
+        let actors = [
+            source(2),
+            actor("sink#1", 2, vec![io(1, 2)], vec![]),
+            actor("sink#2", 1, vec![io(1, 2)], vec![]),
+        ];
+        assert!(check(&actors).is_ok());
+    }
+
+    #[test]
+    fn dangling_output() {
+        let actors = [source(2), actor("sink", 2, vec![io(1, 2)], vec![])];
+        assert!(matches!(
+            check(&actors),
+            Err(ModelError::DanglingOutput { actor, index: 1, .. }) if actor == "source"
+        ));
+    }
+
+    #[test]
+    fn orphan_input() {
+        let actors = [
+            source(1),
+            actor("sink", 2, vec![io(1, 2), io(2, 2)], vec![]),
+        ];
+        assert!(matches!(
+            check(&actors),
+            Err(ModelError::OrphanInput { actor, input }) if actor == "sink" && input == "IO#2"
+        ));
+    }
+
+    #[test]
+    fn rate_mismatch() {
+        let actors = [source(1), actor("sink", 1, vec![io(1, 1)], vec![])];
+        assert!(matches!(
+            check(&actors),
+            Err(ModelError::RateMismatch {
+                rate: 1,
+                output_rate: 2,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn input_rate() {
+        let actors = [source(1), actor("sink", 4, vec![io(1, 2)], vec![])];
+        assert!(matches!(
+            check(&actors),
+            Err(ModelError::InputRate {
+                rate: 2,
+                inputs_rate: 4,
+                ..
+            })
+        ));
+    }
+}
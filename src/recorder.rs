@@ -0,0 +1,645 @@
+/*!
+# Channel traffic recording and replay
+
+A [Model] [recording](Model::recording) writes to a file every data sent by the actors outputs,
+with the sample index of the sender at the time the data was sent.
+The recording is loaded with [Recording::load].
+
+A [Replay] feeds the inputs of a single actor with the recorded data
+and compares the data sent by the actor outputs with the recorded ones.
+
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{clients::Integrator, prelude::*, recorder::{Recording, Replay}};
+#[derive(UID)]
+enum Error {}
+#[derive(UID)]
+enum Command {}
+
+let path = std::env::temp_dir().join("integrator.rec");
+let mut source: Initiator<_> = Signals::new(1, 20)
+    .signals(Signal::Ramp { a: 1e-2, b: 0. })
+    .into();
+let mut integrator: Actor<_> = Actor::new(Integrator::<Error>::new(1).gain(0.5).into_arcx())
+    .name("integrator");
+let logging = Logging::<f64>::default().into_arcx();
+let mut sink = Terminator::<_>::new(logging.clone());
+source.add_output().build_recorded::<Error>().into_input(&mut integrator);
+integrator.add_output().build_recorded::<Command>().into_input(&mut sink);
+Model::new(vec![Box::new(source), Box::new(integrator), Box::new(sink)])
+    .recording(&path)?
+    .check()?
+    .run()
+    .await?;
+
+let recording = Recording::load(&path)?;
+assert_eq!(recording.output::<Command>("integrator")?.len(), 20);
+
+// same gain: the outputs are identical to the recorded ones
+let mut replay = Replay::new(&recording, "integrator")?;
+let mut integrator: Actor<_> = Integrator::<Error>::new(1).gain(0.5).into();
+replay.input::<Error, _, 1, 1>(&mut integrator)?;
+replay.output::<Command, _, 1, 1>(&mut integrator)?;
+let report = replay.run(integrator).await?;
+assert!(report.is_identical());
+
+// different gain: the outputs differ from the 2nd sample onward
+let mut replay = Replay::new(&recording, "integrator")?;
+let mut integrator: Actor<_> = Integrator::<Error>::new(1).gain(0.4).into();
+replay.input::<Error, _, 1, 1>(&mut integrator)?;
+replay.output::<Command, _, 1, 1>(&mut integrator)?;
+let report = replay.run(integrator).await?;
+assert_eq!(report.outputs[0].mismatches.len(), 19);
+# Ok::<(), Box<dyn std::error::Error>>(())
+# });
+```
+
+Only the outputs built with [build_recorded](crate::AddOuput::build_recorded) are recorded, their data must be serializable.
+*/
+
+use crate::{
+    actor::{
+        plain::{PlainActor, PlainIO, PlainOutput},
+        AsyncUpdate,
+    },
+    io::{Assoc, AsyncRead, AsyncWrite, Data, Read, Record, UniqueIdentifier, Write},
+    model::{Model, ModelError},
+    Actor, AddOuput, ArcMutex, IntoInputs, Task, Update,
+};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    any::type_name,
+    collections::{HashMap, VecDeque},
+    fmt,
+    fs::File,
+    io::{BufReader, BufWriter, Write as _},
+    marker::PhantomData,
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+#[derive(thiserror::Error, Debug)]
+pub enum RecorderError {
+    #[error("failed to read the recording")]
+    Io(#[from] std::io::Error),
+    #[error("failed to decode the recording")]
+    Decode(#[from] bincode::Error),
+    #[error("no actor named {0} in the recording")]
+    UnknownActor(String),
+    #[error("actor {actor} has no recorded input {input}")]
+    UnknownInput { actor: String, input: String },
+    #[error("actor {actor} has no recorded output {output}")]
+    UnknownOutput { actor: String, output: String },
+    #[error("replay model failed")]
+    Model(#[from] ModelError),
+}
+pub type Result<R> = std::result::Result<R, RecorderError>;
+
+/// Returns the name of an input or an output i.e. the [UID](crate::UID) type name without the path
+fn io_name(name: &str) -> &str {
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Recorded input or output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedIO {
+    /// [UID](crate::UID) name
+    pub name: String,
+    /// Hash shared by the output and the inputs it is connected to
+    pub hash: u64,
+    /// Sampling rate
+    pub rate: usize,
+    /// Bootstrapped output flag
+    pub bootstrap: bool,
+}
+impl From<&PlainIO> for RecordedIO {
+    fn from(io: &PlainIO) -> Self {
+        Self {
+            name: io_name(&io.name).to_string(),
+            hash: io.hash,
+            rate: io.rate,
+            bootstrap: false,
+        }
+    }
+}
+
+/// Recorded actor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedActor {
+    /// Actor name
+    pub name: String,
+    pub inputs: Vec<RecordedIO>,
+    pub outputs: Vec<RecordedIO>,
+}
+impl From<&PlainActor> for RecordedActor {
+    fn from(actor: &PlainActor) -> Self {
+        Self {
+            name: actor.client.clone(),
+            inputs: actor
+                .inputs
+                .iter()
+                .flatten()
+                .map(RecordedIO::from)
+                .collect(),
+            outputs: actor
+                .outputs
+                .iter()
+                .flatten()
+                .map(|output| match output {
+                    PlainOutput::Bootstrap(io) => RecordedIO {
+                        bootstrap: true,
+                        ..io.into()
+                    },
+                    PlainOutput::Regular(io) => io.into(),
+                })
+                .collect(),
+        }
+    }
+}
+impl RecordedActor {
+    fn input<U>(&self) -> Result<&RecordedIO> {
+        let name = io_name(type_name::<U>());
+        self.inputs
+            .iter()
+            .find(|input| input.name == name)
+            .ok_or_else(|| RecorderError::UnknownInput {
+                actor: self.name.clone(),
+                input: name.to_string(),
+            })
+    }
+    fn output<U>(&self) -> Result<&RecordedIO> {
+        let name = io_name(type_name::<U>());
+        self.outputs
+            .iter()
+            .find(|output| output.name == name)
+            .ok_or_else(|| RecorderError::UnknownOutput {
+                actor: self.name.clone(),
+                output: name.to_string(),
+            })
+    }
+}
+
+/// Data sent by an output
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sample {
+    /// Sample index of the sender when the data was sent
+    pub step: usize,
+    /// Sent while bootstrapping the output
+    pub bootstrap: bool,
+    /// Serialized data
+    pub data: Vec<u8>,
+}
+
+/// Recording file entry
+#[derive(Serialize, Deserialize)]
+enum Entry {
+    Actor(RecordedActor),
+    Sample(u64, Sample),
+}
+
+/// Message to the recording writer
+enum Message {
+    Sample(u64, Sample),
+    /// Flushes the file and acknowledges once all the previous samples are written
+    Flush(flume::Sender<bincode::Result<()>>),
+}
+
+/// Recorder of the data sent by the actors outputs
+///
+/// The samples are sent to a dedicated thread that writes them to the recording file,
+/// so the actors never wait on the file
+#[derive(Clone)]
+pub struct Recorder {
+    tx: flume::Sender<Message>,
+}
+impl Recorder {
+    /// Creates the recording file, writes the description of the actors and starts the writer thread
+    pub(crate) fn create<P: AsRef<Path>>(path: P, actors: &[PlainActor]) -> bincode::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        for actor in actors {
+            bincode::serialize_into(&mut writer, &Entry::Actor(actor.into()))?;
+        }
+        let (tx, rx) = flume::unbounded();
+        std::thread::Builder::new()
+            .name("recorder".into())
+            .spawn(move || Self::write(writer, rx))?;
+        Ok(Self { tx })
+    }
+    /// Writes the samples received from the actors until all the recorders are dropped
+    fn write(mut writer: BufWriter<File>, rx: flume::Receiver<Message>) {
+        let mut failed = None;
+        while let Ok(message) = rx.recv() {
+            match message {
+                Message::Sample(hash, sample) => {
+                    if failed.is_some() {
+                        continue;
+                    }
+                    if let Err(e) =
+                        bincode::serialize_into(&mut writer, &Entry::Sample(hash, sample))
+                    {
+                        log::warn!("failed to record a sample: {e}");
+                        failed = Some(e.to_string());
+                    }
+                }
+                Message::Flush(ack) => {
+                    let result = match &failed {
+                        Some(e) => Err(bincode::ErrorKind::Custom(e.clone()).into()),
+                        None => writer.flush().map_err(bincode::Error::from),
+                    };
+                    let _ = ack.send(result);
+                }
+            }
+        }
+        if let Err(e) = writer.flush() {
+            log::warn!("failed to flush the recording: {e}");
+        }
+    }
+    /// Records the data sent by the output with the given `hash`
+    pub(crate) fn record(&self, hash: u64, step: usize, bootstrap: bool, data: Vec<u8>) {
+        let sample = Sample {
+            step,
+            bootstrap,
+            data,
+        };
+        if self.tx.send(Message::Sample(hash, sample)).is_err() {
+            log::warn!("failed to record a sample: the recording writer has stopped");
+        }
+    }
+    /// Waits for all the samples recorded so far to be written to the file
+    pub(crate) fn flush(&self) -> bincode::Result<()> {
+        let (tx, rx) = flume::bounded(1);
+        self.tx
+            .send(Message::Flush(tx))
+            .ok()
+            .and_then(|_| rx.recv().ok())
+            .unwrap_or_else(|| {
+                Err(bincode::ErrorKind::Custom("the recording writer has stopped".into()).into())
+            })
+    }
+}
+
+/// Model recording
+#[derive(Debug, Default)]
+pub struct Recording {
+    actors: Vec<RecordedActor>,
+    samples: HashMap<u64, Vec<Sample>>,
+}
+impl Recording {
+    /// Loads a recording from a file
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let mut reader = BufReader::new(File::open(path)?);
+        let mut recording = Self::default();
+        loop {
+            match bincode::deserialize_from(&mut reader) {
+                Ok(Entry::Actor(actor)) => recording.actors.push(actor),
+                Ok(Entry::Sample(hash, sample)) => {
+                    recording.samples.entry(hash).or_default().push(sample)
+                }
+                Err(e) => match *e {
+                    bincode::ErrorKind::Io(ref e)
+                        if e.kind() == std::io::ErrorKind::UnexpectedEof =>
+                    {
+                        break Ok(recording)
+                    }
+                    _ => break Err(e.into()),
+                },
+            }
+        }
+    }
+    /// Returns the recorded actors
+    pub fn actors(&self) -> &[RecordedActor] {
+        &self.actors
+    }
+    /// Returns the recorded actor with the given name
+    pub fn actor(&self, name: &str) -> Result<&RecordedActor> {
+        self.actors
+            .iter()
+            .find(|actor| actor.name == name)
+            .ok_or_else(|| RecorderError::UnknownActor(name.to_string()))
+    }
+    /// Returns the data sent by the output with the given hash
+    pub fn samples(&self, hash: u64) -> &[Sample] {
+        self.samples
+            .get(&hash)
+            .map_or(&[], |samples| samples.as_slice())
+    }
+    /// Returns the sample indices and the data sent by the output `U` of an actor
+    pub fn output<U>(&self, actor: &str) -> Result<Vec<(usize, Assoc<U>)>>
+    where
+        U: UniqueIdentifier,
+        Assoc<U>: DeserializeOwned,
+    {
+        let output = self.actor(actor)?.output::<U>()?;
+        Ok(self
+            .samples(output.hash)
+            .iter()
+            .map(|sample| Ok((sample.step, bincode::deserialize(&sample.data)?)))
+            .collect::<bincode::Result<Vec<_>>>()?)
+    }
+}
+
+/// Client writing recorded data
+struct Playback<U: UniqueIdentifier> {
+    data: VecDeque<Assoc<U>>,
+}
+impl<U: UniqueIdentifier> Update for Playback<U> {}
+impl<U: UniqueIdentifier> Write<U> for Playback<U> {
+    fn write(&mut self) -> Option<Arc<Data<U>>> {
+        self.data.pop_front().map(|data| Arc::new(Data::new(data)))
+    }
+}
+
+/// Serialized data read by a [Capture] client
+type Captured = Arc<Mutex<Vec<Vec<u8>>>>;
+
+/// Client serializing the data it reads
+struct Capture<U> {
+    data: Captured,
+    uid: PhantomData<U>,
+}
+impl<U> Update for Capture<U> {}
+impl<U> Read<U> for Capture<U>
+where
+    U: UniqueIdentifier,
+    Assoc<U>: Serialize,
+{
+    fn read(&mut self, data: Arc<Data<U>>) {
+        match bincode::serialize(&**data) {
+            Ok(bytes) => self.data.lock().unwrap().push(bytes),
+            Err(e) => log::warn!("failed to capture a sample: {e}"),
+        }
+    }
+}
+
+/// Comparison of the data sent by an output with the recorded data
+#[derive(Debug)]
+pub struct OutputDiff {
+    /// Output name
+    pub output: String,
+    /// # of recorded samples
+    pub recorded: usize,
+    /// # of replayed samples
+    pub replayed: usize,
+    /// Recorded sample indices of the replayed samples that differ from the recorded ones
+    pub mismatches: Vec<usize>,
+}
+impl OutputDiff {
+    /// Checks if the replayed samples are identical to the recorded ones
+    pub fn is_identical(&self) -> bool {
+        self.recorded == self.replayed && self.mismatches.is_empty()
+    }
+}
+
+/// [Replay] report
+#[derive(Debug)]
+pub struct ReplayReport {
+    /// Actor name
+    pub actor: String,
+    pub outputs: Vec<OutputDiff>,
+}
+impl ReplayReport {
+    /// Checks if all the replayed outputs are identical to the recorded ones
+    pub fn is_identical(&self) -> bool {
+        self.outputs.iter().all(|output| output.is_identical())
+    }
+}
+impl fmt::Display for ReplayReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{} replay:", self.actor)?;
+        for output in &self.outputs {
+            write!(
+                f,
+                " - {}: {} recorded, {} replayed",
+                output.output, output.recorded, output.replayed
+            )?;
+            match output.mismatches.first() {
+                Some(step) => writeln!(
+                    f,
+                    ", {} mismatches from step {}",
+                    output.mismatches.len(),
+                    step
+                )?,
+                None => writeln!(f)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replay of a recording into a single actor
+///
+/// All the inputs and outputs of the actor must be added to the replay
+pub struct Replay<'a> {
+    recording: &'a Recording,
+    actor: &'a RecordedActor,
+    tasks: Vec<Box<dyn Task>>,
+    captures: Vec<(&'a RecordedIO, Captured)>,
+}
+impl<'a> Replay<'a> {
+    /// Creates a replay of the recorded actor with the given name
+    pub fn new(recording: &'a Recording, actor: &str) -> Result<Self> {
+        Ok(Self {
+            recording,
+            actor: recording.actor(actor)?,
+            tasks: vec![],
+            captures: vec![],
+        })
+    }
+    /// Feeds the input `U` of the actor with the recorded data
+    pub fn input<U, C, const NI: usize, const NO: usize>(
+        &mut self,
+        actor: &mut Actor<C, NI, NO>,
+    ) -> Result<&mut Self>
+    where
+        C: 'static + AsyncUpdate + AsyncRead<U>,
+        U: 'static + UniqueIdentifier + Send + Sync,
+        Assoc<U>: Send + Sync + Record,
+    {
+        let input = self.actor.input::<U>()?;
+        let data = self
+            .recording
+            .samples(input.hash)
+            .iter()
+            .map(|sample| bincode::deserialize(&sample.data))
+            .collect::<bincode::Result<VecDeque<_>>>()?;
+        let mut playback: Actor<Playback<U>, 0, 1> = Actor::new(Playback { data }.into_arcx());
+        playback
+            .add_output()
+            .rate(input.rate)
+            .build::<U>()
+            .into_input_at(actor);
+        self.tasks.push(Box::new(playback));
+        Ok(self)
+    }
+    /// Compares the data sent by the output `U` of the actor with the recorded data
+    pub fn output<U, C, const NI: usize, const NO: usize>(
+        &mut self,
+        actor: &mut Actor<C, NI, NO>,
+    ) -> Result<&mut Self>
+    where
+        C: 'static + AsyncUpdate + AsyncWrite<U>,
+        U: 'static + UniqueIdentifier + Send + Sync,
+        Assoc<U>: Send + Sync + Record,
+    {
+        let output = self.actor.output::<U>()?;
+        let data = Arc::new(Mutex::new(vec![]));
+        let mut capture: Actor<Capture<U>, 1, 0> = Actor::new(
+            Capture {
+                data: data.clone(),
+                uid: PhantomData,
+            }
+            .into_arcx(),
+        );
+        let builder = actor.add_output().rate(output.rate);
+        let builder = if output.bootstrap {
+            builder.bootstrap()
+        } else {
+            builder
+        };
+        builder.build::<U>().into_input_at(&mut capture);
+        self.tasks.push(Box::new(capture));
+        self.captures.push((output, data));
+        Ok(self)
+    }
+    /// Runs the actor with the recorded inputs and compares its outputs with the recorded ones
+    pub async fn run<T: Task + 'static>(self, actor: T) -> Result<ReplayReport> {
+        let mut tasks = self.tasks;
+        tasks.push(Box::new(actor));
+        Model::new(tasks)
+            .name(format!("{} replay", self.actor.name))
+            .check()?
+            .run()
+            .await?;
+        let outputs = self
+            .captures
+            .into_iter()
+            .map(|(output, data)| {
+                let recorded = self.recording.samples(output.hash);
+                let replayed = data.lock().unwrap();
+                OutputDiff {
+                    output: output.name.clone(),
+                    recorded: recorded.len(),
+                    replayed: replayed.len(),
+                    mismatches: recorded
+                        .iter()
+                        .zip(replayed.iter())
+                        .filter(|(recorded, replayed)| recorded.data != **replayed)
+                        .map(|(recorded, _)| recorded.step)
+                        .collect(),
+                }
+            })
+            .collect();
+        Ok(ReplayReport {
+            actor: self.actor.name.clone(),
+            outputs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        clients::{Integrator, Logging, Signal, Signals},
+        Initiator, Terminator, UID,
+    };
+
+    #[derive(UID)]
+    enum Ramp {}
+    #[derive(UID)]
+    enum Unrecorded {}
+    #[derive(UID)]
+    enum Command {}
+
+    /// Records a ramp integrated with the given gain
+    async fn record(path: &Path, gain: f64) -> Vec<f64> {
+        let mut source: Initiator<_> = Actor::new(
+            Signals::new(1, 10)
+                .signals(Signal::Ramp { a: 1., b: 0. })
+                .into_arcx(),
+        )
+        .name("ramp");
+        let mut integrator: Actor<_> =
+            Actor::new(Integrator::<Ramp>::new(1).gain(gain).into_arcx()).name("integrator");
+        let logging = Logging::<f64>::default().into_arcx();
+        let mut sink = Terminator::<_>::new(logging.clone());
+        let mut void = Terminator::<_>::new(Logging::<f64>::default().into_arcx());
+        source
+            .add_output()
+            .build_recorded::<Ramp>()
+            .into_input(&mut integrator);
+        integrator
+            .add_output()
+            .build_recorded::<Command>()
+            .into_input(&mut sink);
+        integrator
+            .add_output()
+            .build::<Unrecorded>()
+            .into_input(&mut void);
+        Model::new(vec![
+            Box::new(source),
+            Box::new(integrator),
+            Box::new(sink),
+            Box::new(void),
+        ])
+        .recording(path)
+        .unwrap()
+        .check()
+        .unwrap()
+        .run()
+        .await
+        .unwrap();
+        let data = logging.lock().await.to_vec();
+        data
+    }
+
+    #[tokio::test]
+    async fn recording_round_trip() {
+        let path = std::env::temp_dir().join("recording_round_trip.rec");
+        let logged = record(&path, 0.5).await;
+        let recording = Recording::load(&path).unwrap();
+
+        let ramp = recording.output::<Ramp>("ramp").unwrap();
+        assert_eq!(ramp.len(), 10);
+        assert!(ramp
+            .iter()
+            .zip(0..)
+            .all(|((step, x), i)| *step == i && *x == [i as f64]));
+
+        let command = recording.output::<Command>("integrator").unwrap();
+        assert_eq!(
+            command
+                .into_iter()
+                .flat_map(|(_, x)| x)
+                .collect::<Vec<f64>>(),
+            logged
+        );
+
+        assert!(recording
+            .output::<Unrecorded>("integrator")
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_round_trip() {
+        let path = std::env::temp_dir().join("replay_round_trip.rec");
+        record(&path, 0.5).await;
+        let recording = Recording::load(&path).unwrap();
+
+        let mut replay = Replay::new(&recording, "integrator").unwrap();
+        let mut integrator: Actor<_> = Integrator::<Ramp>::new(1).gain(0.5).into();
+        replay.input::<Ramp, _, 1, 1>(&mut integrator).unwrap();
+        replay.output::<Command, _, 1, 1>(&mut integrator).unwrap();
+        let report = replay.run(integrator).await.unwrap();
+        assert!(report.is_identical());
+        assert_eq!(report.outputs[0].replayed, 10);
+
+        let mut replay = Replay::new(&recording, "integrator").unwrap();
+        let mut integrator: Actor<_> = Integrator::<Ramp>::new(1).gain(0.25).into();
+        replay.input::<Ramp, _, 1, 1>(&mut integrator).unwrap();
+        replay.output::<Command, _, 1, 1>(&mut integrator).unwrap();
+        let report = replay.run(integrator).await.unwrap();
+        assert_eq!(report.outputs[0].mismatches, (1..10).collect::<Vec<_>>());
+    }
+}
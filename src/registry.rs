@@ -0,0 +1,587 @@
+/*!
+# Model description
+
+A [Model] can be described in a [RON](https://github.com/ron-rs/ron) or a [TOML](https://toml.io) file listing the actors,
+with their rates and client parameters, and the connections between the actors by [UID](crate::UID) name.
+A [UID](crate::UID) is named either by its type name alone, if no other registered [UID](crate::UID) has the same name,
+or by its full path e.g. `my_crate::optics::Source`.
+
+The clients are created from the factories registered under a name in a [Registry],
+together with the [UID](crate::UID)s they read and write.
+A factory takes the client parameters of the description as argument and returns the client.
+
+```
+# tokio_test::block_on(async {
+use gmt_dos_actors::{prelude::*, registry::Registry};
+#[derive(UID)]
+enum Source {}
+#[derive(serde::Deserialize)]
+struct SignalsParams {
+    n_step: usize,
+}
+
+let logging = Logging::<f64>::default().into_arcx();
+let mut registry = Registry::new();
+registry
+    .client("Signals", |params: SignalsParams| {
+        Signals::new(1, params.n_step).into_arcx()
+    })
+    .writes::<Source>();
+registry
+    .client("Sampler", |_: ()| {
+        Sampler::<Vec<f64>, Source>::default().into_arcx()
+    })
+    .reads::<Source>()
+    .writes::<Source>();
+let log = logging.clone();
+registry
+    .client("Logging", move |_: ()| log.clone())
+    .reads::<Source>();
+
+let model = registry.from_ron(
+    r#"(
+    name: "decimation",
+    actors: [
+        (name: "source", client: "Signals", outputs_rate: 1, params: (n_step: 100)),
+        (name: "sampler", client: "Sampler", inputs_rate: 1, outputs_rate: 10),
+        (name: "sink", client: "Logging", inputs_rate: 10),
+    ],
+    connections: [
+        (uid: "Source", from: "source", to: ["sampler"], batch: 10),
+        (uid: "Source", from: "sampler", to: ["sink"]),
+    ],
+)"#,
+)?;
+model.check()?.run().await?;
+assert_eq!(logging.lock().await.len(), 10);
+# Ok::<(), Box<dyn std::error::Error>>(())
+# });
+```
+
+The loader returns a [RegistryError] if an actor, a client or a [UID](crate::UID) is unknown,
+or if the data types written and read through a connection do not match.
+
+```
+use gmt_dos_actors::{prelude::*, registry::{Registry, RegistryError}};
+#[derive(UID)]
+enum Source {}
+let mut registry = Registry::new();
+registry
+    .client("Signals", |n_step: usize| Signals::new(1, n_step).into_arcx())
+    .writes::<Source>();
+let model = registry.from_ron(
+    r#"(
+    actors: [(name: "source", client: "Signals", outputs_rate: 1, params: 100)],
+    connections: [(uid: "Sink", from: "source", to: ["source"])],
+)"#,
+);
+assert!(matches!(model, Err(RegistryError::UnknownOutput { .. })));
+```
+
+The same description in TOML:
+```
+# use gmt_dos_actors::{prelude::*, registry::Registry};
+# #[derive(UID)]
+# enum Source {}
+# let logging = Logging::<f64>::default().into_arcx();
+# let mut registry = Registry::new();
+# registry
+#     .client("Signals", |n_step: usize| Signals::new(1, n_step).into_arcx())
+#     .writes::<Source>();
+# let log = logging.clone();
+# registry
+#     .client("Logging", move |_: ()| log.clone())
+#     .reads::<Source>();
+let model = registry.from_toml(
+    r#"
+    [[actors]]
+    name = "source"
+    client = "Signals"
+    outputs_rate = 1
+    params = 100
+
+    [[actors]]
+    name = "sink"
+    client = "Logging"
+    inputs_rate = 1
+
+    [[connections]]
+    uid = "Source"
+    from = "source"
+    to = ["sink"]
+"#,
+)?;
+# Ok::<(), gmt_dos_actors::registry::RegistryError>(())
+```
+*/
+
+use crate::{
+    actor::{AnyTask, AsyncUpdate},
+    io::{Assoc, AsyncRead, AsyncWrite, Batch, UniqueIdentifier},
+    model::{Model, Unknown},
+    Actor, AddOuput,
+};
+use serde::{de::DeserializeOwned, Deserialize};
+use std::{
+    any::{type_name, Any},
+    collections::HashMap,
+    marker::PhantomData,
+    sync::Arc,
+};
+use tokio::sync::Mutex;
+
+#[derive(thiserror::Error, Debug)]
+pub enum RegistryError {
+    #[error("failed to parse the model description")]
+    Ron(#[from] ron::error::SpannedError),
+    #[error("failed to parse the model description")]
+    Toml(#[from] toml::de::Error),
+    #[error("no client registered under the name {0}")]
+    UnknownClient(String),
+    #[error("no actor named {0} in the model description")]
+    UnknownActor(String),
+    #[error("actor {0} is described more than once")]
+    DuplicateActor(String),
+    #[error("invalid parameters for actor {actor}")]
+    Params {
+        actor: String,
+        #[source]
+        source: ron::Error,
+    },
+    #[error("{client} client of actor {actor} does not write {uid}")]
+    UnknownOutput {
+        actor: String,
+        client: String,
+        uid: String,
+    },
+    #[error("{client} client of actor {actor} does not read {uid}")]
+    UnknownInput {
+        actor: String,
+        client: String,
+        uid: String,
+    },
+    #[error("{uid} written by {from} has a different type than {uid} read by {to}")]
+    TypeMismatch {
+        uid: String,
+        from: String,
+        to: String,
+    },
+    #[error("{uid} from {from} is not connected to any actor")]
+    NoDestination { uid: String, from: String },
+    #[error("{uid} may be any of {candidates:?}, use the full path")]
+    AmbiguousUid {
+        uid: String,
+        candidates: Vec<String>,
+    },
+    #[error("actor {actor} was not built by the {client} client factory")]
+    ClientType { actor: String, client: String },
+}
+pub type Result<R> = std::result::Result<R, RegistryError>;
+
+/// Model description
+#[derive(Debug, Deserialize)]
+pub struct ModelDescription {
+    /// Model name
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Model actors
+    pub actors: Vec<ActorDescription>,
+    /// Connections between the actors
+    #[serde(default)]
+    pub connections: Vec<ConnectionDescription>,
+}
+
+/// Actor description
+#[derive(Debug, Deserialize)]
+pub struct ActorDescription {
+    /// Actor name, unique within the model
+    pub name: String,
+    /// Name of the client in the [Registry]
+    pub client: String,
+    /// Inputs rate (default: 0)
+    #[serde(default)]
+    pub inputs_rate: usize,
+    /// Outputs rate (default: 0)
+    #[serde(default)]
+    pub outputs_rate: usize,
+    /// Client parameters given to the client factory
+    #[serde(default)]
+    pub params: Option<ron::Value>,
+}
+
+/// Connection description
+#[derive(Debug, Deserialize)]
+pub struct ConnectionDescription {
+    /// Name of the [UID](crate::UID)
+    pub uid: String,
+    /// Name of the actor the output belongs to
+    pub from: String,
+    /// Names of the actors the inputs belong to
+    pub to: Vec<String>,
+    /// Flags the output to be bootstrapped
+    #[serde(default)]
+    pub bootstrap: bool,
+    /// Sets the channel to unbounded
+    #[serde(default)]
+    pub unbounded: bool,
+    /// Output sampling rate (default: the actor outputs rate)
+    #[serde(default)]
+    pub rate: Option<usize>,
+    /// Number of samples sent at a time (default: 1)
+    #[serde(default)]
+    pub batch: Option<usize>,
+}
+
+/// Receivers of an output and the hash and rate of the inputs they are assigned to
+struct Link<U: UniqueIdentifier> {
+    rxs: Vec<flume::Receiver<Batch<U>>>,
+    hash: u64,
+    rate: usize,
+}
+
+type Factory = Box<dyn Fn(ron::Value, &ActorDescription) -> Result<Box<dyn AnyTask>>>;
+/// Adds an output to an actor, `None` if the actor is not of the client type
+type OutputBuilder = Box<dyn Fn(&mut dyn AnyTask, &ConnectionDescription) -> Option<Box<dyn Any>>>;
+/// Adds an input to an actor, `None` if the actor is not of the client type
+/// and `Some(false)` if the output data type does not match
+type InputBuilder = Box<dyn Fn(&mut dyn AnyTask, &mut Box<dyn Any>) -> Option<bool>>;
+
+/// Client factory with the [UID](crate::UID)s, by full path, the client reads and writes
+struct ClientEntry {
+    factory: Factory,
+    outputs: HashMap<String, OutputBuilder>,
+    inputs: HashMap<String, InputBuilder>,
+}
+
+/// Returns the name of a [UID](crate::UID) i.e. its full path without the modules
+fn uid_name(path: &str) -> &str {
+    let head = path.find('<').map_or(path, |i| &path[..i]);
+    head.rfind("::").map_or(path, |i| &path[i + 2..])
+}
+
+/// Returns the builder of the [UID](crate::UID) named `uid`
+///
+/// `uid` is either the [UID](crate::UID) full path or its name if the name is not shared
+fn lookup<'a, B>(builders: &'a HashMap<String, B>, uid: &str) -> Result<Option<&'a B>> {
+    if let Some(builder) = builders.get(uid) {
+        return Ok(Some(builder));
+    }
+    let mut candidates: Vec<_> = builders
+        .iter()
+        .filter(|(path, _)| uid_name(path) == uid)
+        .collect();
+    match candidates.len() {
+        0 => Ok(None),
+        1 => Ok(candidates.pop().map(|(_, builder)| builder)),
+        _ => {
+            let mut candidates: Vec<_> = candidates
+                .into_iter()
+                .map(|(path, _)| path.clone())
+                .collect();
+            candidates.sort();
+            Err(RegistryError::AmbiguousUid {
+                uid: uid.to_string(),
+                candidates,
+            })
+        }
+    }
+}
+
+/// Registry of client factories
+#[derive(Default)]
+pub struct Registry {
+    clients: HashMap<String, ClientEntry>,
+}
+
+/// Client registration in a [Registry]
+pub struct ClientRegistration<'a, C> {
+    entry: &'a mut ClientEntry,
+    client: PhantomData<C>,
+}
+impl<'a, C> ClientRegistration<'a, C>
+where
+    C: 'static + AsyncUpdate,
+{
+    /// Registers `U` as an output of the client
+    pub fn writes<U>(self) -> Self
+    where
+        C: AsyncWrite<U>,
+        U: 'static + UniqueIdentifier + Send + Sync,
+        Assoc<U>: Send + Sync,
+    {
+        let builder: OutputBuilder = Box::new(|task, connection| {
+            let actor = task
+                .as_any_mut()
+                .and_then(|actor| actor.downcast_mut::<Actor<C, 1, 1>>())?;
+            let mut output = actor.add_output().multiplex(connection.to.len());
+            if connection.unbounded {
+                output = output.unbounded();
+            }
+            if connection.bootstrap {
+                output = output.bootstrap();
+            }
+            if let Some(rate) = connection.rate {
+                output = output.rate(rate);
+            }
+            if let Some(k) = connection.batch {
+                output = output.batch(k);
+            }
+            let (actor, rxs) = output.build::<U>();
+            let link = Link::<U> {
+                rxs,
+                hash: crate::hashio(actor),
+                rate: crate::rateio(actor),
+            };
+            Some(Box::new(link))
+        });
+        self.entry
+            .outputs
+            .insert(type_name::<U>().to_string(), builder);
+        self
+    }
+    /// Registers `U` as an input of the client
+    pub fn reads<U>(self) -> Self
+    where
+        C: AsyncRead<U>,
+        U: 'static + UniqueIdentifier + Send + Sync,
+        Assoc<U>: Send + Sync,
+    {
+        let builder: InputBuilder = Box::new(|task, link| {
+            let actor = task
+                .as_any_mut()
+                .and_then(|actor| actor.downcast_mut::<Actor<C, 1, 1>>())?;
+            Some(match link.downcast_mut::<Link<U>>() {
+                Some(link) => {
+                    if let Some(rx) = link.rxs.pop() {
+                        #[cfg(feature = "checkpoint")]
+                        actor.add_input(rx, link.hash, link.rate, None);
+                        #[cfg(not(feature = "checkpoint"))]
+                        actor.add_input(rx, link.hash, link.rate);
+                    }
+                    true
+                }
+                None => false,
+            })
+        });
+        self.entry
+            .inputs
+            .insert(type_name::<U>().to_string(), builder);
+        self
+    }
+}
+
+impl Registry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Default::default()
+    }
+    /// Registers a client factory under the given name
+    ///
+    /// The factory parameters are deserialized from the actor `params` in the model description
+    pub fn client<C, P, F>(&mut self, name: &str, factory: F) -> ClientRegistration<'_, C>
+    where
+        C: 'static + AsyncUpdate,
+        P: DeserializeOwned,
+        F: Fn(P) -> Arc<Mutex<C>> + 'static,
+    {
+        let factory: Factory = Box::new(move |params, description| {
+            let params: P = params.into_rust().map_err(|source| RegistryError::Params {
+                actor: description.name.clone(),
+                source,
+            })?;
+            let mut actor = Actor::<C, 1, 1>::new(factory(params)).name(&description.name);
+            actor.set_rates(description.inputs_rate, description.outputs_rate);
+            Ok(Box::new(actor))
+        });
+        let entry = ClientEntry {
+            factory,
+            outputs: HashMap::new(),
+            inputs: HashMap::new(),
+        };
+        self.clients.insert(name.to_string(), entry);
+        ClientRegistration {
+            entry: self.clients.get_mut(name).unwrap(),
+            client: PhantomData,
+        }
+    }
+    /// Parses a [RON](https://github.com/ron-rs/ron) model description and builds the model
+    ///
+    /// Optional fields can be given without `Some`
+    pub fn from_ron(&self, description: &str) -> Result<Model<Unknown>> {
+        let description: ModelDescription = ron::Options::default()
+            .with_default_extension(ron::extensions::Extensions::IMPLICIT_SOME)
+            .from_str(description)?;
+        self.build(description)
+    }
+    /// Parses a [TOML](https://toml.io) model description and builds the model
+    ///
+    /// The actors and the connections are given as arrays of tables, `[[actors]]` and `[[connections]]`
+    pub fn from_toml(&self, description: &str) -> Result<Model<Unknown>> {
+        let description: ModelDescription = toml::from_str(description)?;
+        self.build(description)
+    }
+    /// Builds the model from its description
+    pub fn build(&self, description: ModelDescription) -> Result<Model<Unknown>> {
+        let mut index: HashMap<&str, usize> = HashMap::new();
+        let mut actors: Vec<Box<dyn AnyTask>> = vec![];
+        let mut entries: Vec<&ClientEntry> = vec![];
+        for (k, actor) in description.actors.iter().enumerate() {
+            if index.insert(&actor.name, k).is_some() {
+                return Err(RegistryError::DuplicateActor(actor.name.clone()));
+            }
+            let entry = self
+                .clients
+                .get(&actor.client)
+                .ok_or_else(|| RegistryError::UnknownClient(actor.client.clone()))?;
+            let params = actor.params.clone().unwrap_or(ron::Value::Unit);
+            actors.push((entry.factory)(params, actor)?);
+            entries.push(entry);
+        }
+        let actor_index = |name: &String| {
+            index
+                .get(name.as_str())
+                .copied()
+                .ok_or_else(|| RegistryError::UnknownActor(name.clone()))
+        };
+        for connection in &description.connections {
+            let from = actor_index(&connection.from)?;
+            if connection.to.is_empty() {
+                return Err(RegistryError::NoDestination {
+                    uid: connection.uid.clone(),
+                    from: connection.from.clone(),
+                });
+            }
+            let to = connection
+                .to
+                .iter()
+                .map(actor_index)
+                .collect::<Result<Vec<_>>>()?;
+            let client_type = |k: usize| RegistryError::ClientType {
+                actor: description.actors[k].name.clone(),
+                client: description.actors[k].client.clone(),
+            };
+            let output = lookup(&entries[from].outputs, &connection.uid)?.ok_or_else(|| {
+                RegistryError::UnknownOutput {
+                    actor: connection.from.clone(),
+                    client: description.actors[from].client.clone(),
+                    uid: connection.uid.clone(),
+                }
+            })?;
+            let mut link =
+                output(actors[from].as_mut(), connection).ok_or_else(|| client_type(from))?;
+            for k in to {
+                let input = lookup(&entries[k].inputs, &connection.uid)?.ok_or_else(|| {
+                    RegistryError::UnknownInput {
+                        actor: description.actors[k].name.clone(),
+                        client: description.actors[k].client.clone(),
+                        uid: connection.uid.clone(),
+                    }
+                })?;
+                if !input(actors[k].as_mut(), &mut link).ok_or_else(|| client_type(k))? {
+                    return Err(RegistryError::TypeMismatch {
+                        uid: connection.uid.clone(),
+                        from: connection.from.clone(),
+                        to: description.actors[k].name.clone(),
+                    });
+                }
+            }
+        }
+        let model = Model::new(actors.into_iter().map(|actor| actor.into_task()).collect());
+        Ok(match description.name {
+            Some(name) => model.name(name),
+            None => model,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::prelude::*;
+
+    mod optics {
+        use crate::prelude::*;
+        #[derive(UID)]
+        pub enum Source {}
+    }
+    mod structure {
+        use crate::prelude::*;
+        #[derive(UID)]
+        pub enum Source {}
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry
+            .client("Signals", |n_step: usize| {
+                Signals::new(1, n_step).into_arcx()
+            })
+            .writes::<optics::Source>()
+            .writes::<structure::Source>();
+        registry
+            .client("Logging", |_: ()| Logging::<f64>::default().into_arcx())
+            .reads::<optics::Source>()
+            .reads::<structure::Source>();
+        registry
+    }
+
+    fn description(uid: &str) -> String {
+        format!(
+            r#"(
+            actors: [
+                (name: "source", client: "Signals", outputs_rate: 1, params: 10),
+                (name: "sink", client: "Logging", inputs_rate: 1),
+            ],
+            connections: [(uid: "{uid}", from: "source", to: ["sink"])],
+        )"#
+        )
+    }
+
+    #[test]
+    fn same_name_uids_are_kept_apart() {
+        let registry = registry();
+        for uid in [
+            type_name::<optics::Source>(),
+            type_name::<structure::Source>(),
+        ] {
+            assert_eq!(uid_name(uid), "Source");
+            assert!(registry.from_ron(&description(uid)).is_ok());
+        }
+        match registry.from_ron(&description("Source")) {
+            Err(RegistryError::AmbiguousUid { candidates, .. }) => {
+                assert_eq!(candidates.len(), 2)
+            }
+            _ => panic!("expected an ambiguous UID error"),
+        }
+    }
+
+    #[test]
+    fn toml_description() {
+        let registry = registry();
+        let toml = r#"
+            name = "toml"
+
+            [[actors]]
+            name = "source"
+            client = "Signals"
+            outputs_rate = 1
+            params = 10
+
+            [[actors]]
+            name = "sink"
+            client = "Logging"
+            inputs_rate = 1
+
+            [[connections]]
+            uid = "gmt_dos_actors::registry::tests::optics::Source"
+            from = "source"
+            to = ["sink"]
+            batch = 5
+        "#;
+        assert!(registry.from_toml(toml).is_ok());
+        assert!(matches!(
+            registry.from_toml("[[actors]]\nname = 1"),
+            Err(RegistryError::Toml(_))
+        ));
+    }
+}
@@ -0,0 +1,422 @@
+/*!
+# Client test driver
+
+A [Driver] runs a client without [Actor](crate::Actor)s, channels or runtime.
+The inputs of the client are fed with scripted sequences and its outputs are collected into vectors.
+
+At each sample, the driver follows the same ordering than the [Actor](crate::Actor) loop:
+the inputs are read if `t%rate=0`, the client is updated if `t%NI=0` (or `t%NO=0` without inputs)
+and the outputs are written if `(t+1)%rate=0`.
+The bootstrapped outputs are written before the first sample.
+
+```
+use gmt_dos_actors::{prelude::*, testing::{assert_close, Driver}};
+#[derive(UID)]
+enum In {}
+#[derive(UID)]
+enum Out {}
+
+let mut driver = Driver::new(Sampler::<Vec<f64>, In, Out>::default())
+    .rates(1, 2)
+    .input::<In>((0..10).map(|i| vec![i as f64]))
+    .output::<Out>();
+assert_eq!(driver.run(usize::MAX)?, 10);
+assert_close(
+    driver.outputs::<Out>(),
+    &[vec![1.], vec![3.], vec![5.], vec![7.], vec![9.]],
+    1e-12,
+);
+# Ok::<(), gmt_dos_actors::ActorError>(())
+```
+*/
+
+use crate::{
+    actor::plain,
+    io::{Assoc, Data, TryRead, TryWrite, UniqueIdentifier},
+    ActorError, Result, TryUpdate,
+};
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::VecDeque,
+    fmt::Debug,
+    sync::Arc,
+};
+
+/// Scripted input of a [Driver]
+struct DriverInput<C> {
+    uid: TypeId,
+    rate: Option<usize>,
+    data: Box<dyn Any>,
+    read: fn(&mut C, &mut dyn Any) -> Result<()>,
+}
+fn read<C, U>(client: &mut C, data: &mut dyn Any) -> Result<()>
+where
+    C: TryRead<U>,
+    U: 'static + UniqueIdentifier,
+{
+    let data = data
+        .downcast_mut::<VecDeque<Assoc<U>>>()
+        .and_then(|data| data.pop_front())
+        .ok_or(ActorError::NoData)?;
+    client
+        .try_read(Arc::new(Data::new(data)))
+        .map_err(ActorError::client::<C>)
+}
+
+/// Collected output of a [Driver]
+struct DriverOutput<C> {
+    uid: TypeId,
+    rate: Option<usize>,
+    bootstrap: bool,
+    data: Box<dyn Any>,
+    write: fn(&mut C, &mut dyn Any) -> Result<()>,
+}
+fn write<C, U>(client: &mut C, data: &mut dyn Any) -> Result<()>
+where
+    C: TryWrite<U>,
+    U: 'static + UniqueIdentifier,
+    Assoc<U>: Clone,
+{
+    let sample = client
+        .try_write()
+        .map_err(ActorError::client::<C>)?
+        .ok_or_else(|| ActorError::Disconnected(type_name::<U>().to_string()))?;
+    if let Some(data) = data.downcast_mut::<Vec<Assoc<U>>>() {
+        data.push((**sample).clone());
+    }
+    Ok(())
+}
+
+/// Synchronous client test driver
+pub struct Driver<C> {
+    client: C,
+    inputs_rate: usize,
+    outputs_rate: usize,
+    inputs: Vec<DriverInput<C>>,
+    outputs: Vec<DriverOutput<C>>,
+    tick: Option<usize>,
+}
+impl<C: TryUpdate> Driver<C> {
+    /// Creates a new driver for the client with inputs and outputs rates of 1
+    pub fn new(client: C) -> Self {
+        Self {
+            client,
+            inputs_rate: 1,
+            outputs_rate: 1,
+            inputs: Vec::new(),
+            outputs: Vec::new(),
+            tick: None,
+        }
+    }
+    /// Sets the inputs and outputs rates `NI` and `NO` of the client
+    pub fn rates(self, inputs_rate: usize, outputs_rate: usize) -> Self {
+        Self {
+            inputs_rate,
+            outputs_rate,
+            ..self
+        }
+    }
+    /// Adds an input `U` fed with the given sequence at the inputs rate
+    pub fn input<U>(self, data: impl IntoIterator<Item = Assoc<U>>) -> Self
+    where
+        C: TryRead<U>,
+        U: 'static + UniqueIdentifier,
+    {
+        self.add_input::<U>(None, data)
+    }
+    /// Adds an input `U` fed with the given sequence at the given rate
+    pub fn input_at<U>(self, rate: usize, data: impl IntoIterator<Item = Assoc<U>>) -> Self
+    where
+        C: TryRead<U>,
+        U: 'static + UniqueIdentifier,
+    {
+        self.add_input::<U>(Some(rate), data)
+    }
+    fn add_input<U>(mut self, rate: Option<usize>, data: impl IntoIterator<Item = Assoc<U>>) -> Self
+    where
+        C: TryRead<U>,
+        U: 'static + UniqueIdentifier,
+    {
+        self.inputs.push(DriverInput {
+            uid: TypeId::of::<U>(),
+            rate,
+            data: Box::new(data.into_iter().collect::<VecDeque<_>>()),
+            read: read::<C, U>,
+        });
+        self
+    }
+    /// Adds an output `U` written at the outputs rate
+    pub fn output<U>(self) -> Self
+    where
+        C: TryWrite<U>,
+        U: 'static + UniqueIdentifier,
+        Assoc<U>: Clone,
+    {
+        self.add_output::<U>(None, false)
+    }
+    /// Adds an output `U` written at the given rate
+    pub fn output_at<U>(self, rate: usize) -> Self
+    where
+        C: TryWrite<U>,
+        U: 'static + UniqueIdentifier,
+        Assoc<U>: Clone,
+    {
+        self.add_output::<U>(Some(rate), false)
+    }
+    /// Adds a bootstrapped output `U` written at the outputs rate
+    pub fn bootstrap<U>(self) -> Self
+    where
+        C: TryWrite<U>,
+        U: 'static + UniqueIdentifier,
+        Assoc<U>: Clone,
+    {
+        self.add_output::<U>(None, true)
+    }
+    fn add_output<U>(mut self, rate: Option<usize>, bootstrap: bool) -> Self
+    where
+        C: TryWrite<U>,
+        U: 'static + UniqueIdentifier,
+        Assoc<U>: Clone,
+    {
+        self.outputs.push(DriverOutput {
+            uid: TypeId::of::<U>(),
+            rate,
+            bootstrap,
+            data: Box::new(Vec::<Assoc<U>>::new()),
+            write: write::<C, U>,
+        });
+        self
+    }
+    /// Writes the bootstrapped outputs
+    fn bootstrap_outputs(&mut self) -> Result<()> {
+        let (inputs_rate, outputs_rate) = (self.inputs_rate, self.outputs_rate);
+        for output in self.outputs.iter_mut().filter(|output| output.bootstrap) {
+            let rate = output.rate.unwrap_or(outputs_rate);
+            for _ in 0..plain::bootstrap_samples(inputs_rate, rate) {
+                (output.write)(&mut self.client, output.data.as_mut())?;
+            }
+        }
+        Ok(())
+    }
+    /// Performs the next sample of the client
+    ///
+    /// Returns an error when an input sequence is exhausted, when an output has no data or when the client fails
+    pub fn step(&mut self) -> Result<()> {
+        let t = match self.tick {
+            Some(t) => t,
+            None => {
+                self.tick = Some(0);
+                self.bootstrap_outputs()?;
+                0
+            }
+        };
+        let (ni, no) = (self.inputs_rate, self.outputs_rate);
+        for input in self
+            .inputs
+            .iter_mut()
+            .filter(|input| t.is_multiple_of(input.rate.unwrap_or(ni)))
+        {
+            (input.read)(&mut self.client, input.data.as_mut())?;
+        }
+        if (ni > 0 && t.is_multiple_of(ni)) || (ni == 0 && t.is_multiple_of(no)) {
+            self.client.try_update().map_err(ActorError::client::<C>)?;
+        }
+        for output in self
+            .outputs
+            .iter_mut()
+            .filter(|output| (t + 1).is_multiple_of(output.rate.unwrap_or(no)))
+        {
+            (output.write)(&mut self.client, output.data.as_mut())?;
+        }
+        self.tick = Some(t + 1);
+        Ok(())
+    }
+    /// Performs up to `n` samples
+    ///
+    /// Returns the number of samples performed, fewer than `n` if an input sequence is exhausted
+    /// or if an output has no data, and the error of any other failed sample
+    pub fn run(&mut self, n: usize) -> Result<usize> {
+        for k in 0..n {
+            match self.step() {
+                Ok(_) => (),
+                Err(ActorError::NoData | ActorError::Disconnected(_)) => return Ok(k),
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(n)
+    }
+    /// Returns the data written to the output `U`
+    ///
+    /// # Panics
+    /// If `U` is not an output of the driver
+    pub fn outputs<U>(&self) -> &[Assoc<U>]
+    where
+        U: 'static + UniqueIdentifier,
+    {
+        self.outputs
+            .iter()
+            .find(|output| output.uid == TypeId::of::<U>())
+            .and_then(|output| output.data.downcast_ref::<Vec<Assoc<U>>>())
+            .unwrap_or_else(|| panic!("{} is not an output of the driver", type_name::<U>()))
+    }
+    /// Returns the number of samples left in the input `U` sequence
+    pub fn remaining<U>(&self) -> usize
+    where
+        U: 'static + UniqueIdentifier,
+    {
+        self.inputs
+            .iter()
+            .filter(|input| input.uid == TypeId::of::<U>())
+            .filter_map(|input| input.data.downcast_ref::<VecDeque<Assoc<U>>>())
+            .map(|data| data.len())
+            .sum()
+    }
+    /// Returns a reference to the client
+    pub fn client(&self) -> &C {
+        &self.client
+    }
+    /// Returns a mutable reference to the client
+    pub fn client_mut(&mut self) -> &mut C {
+        &mut self.client
+    }
+}
+
+/// Data that can be compared within a tolerance
+pub trait Close {
+    /// Returns the largest absolute difference with `other`, infinite if the sizes differ
+    fn max_abs_diff(&self, other: &Self) -> f64;
+}
+impl Close for f64 {
+    fn max_abs_diff(&self, other: &Self) -> f64 {
+        (self - other).abs()
+    }
+}
+impl Close for f32 {
+    fn max_abs_diff(&self, other: &Self) -> f64 {
+        (self - other).abs() as f64
+    }
+}
+impl<T: Close> Close for [T] {
+    fn max_abs_diff(&self, other: &Self) -> f64 {
+        if self.len() != other.len() {
+            return f64::INFINITY;
+        }
+        self.iter()
+            .zip(other)
+            .map(|(a, b)| a.max_abs_diff(b))
+            .fold(0f64, f64::max)
+    }
+}
+impl<T: Close> Close for Vec<T> {
+    fn max_abs_diff(&self, other: &Self) -> f64 {
+        self.as_slice().max_abs_diff(other.as_slice())
+    }
+}
+
+/// Asserts that the samples of `actual` and `expected` are equal within `tolerance`
+///
+/// # Panics
+/// If the numbers of samples differ or if the largest absolute difference of a sample is greater than `tolerance`
+#[track_caller]
+pub fn assert_close<T: Close + Debug>(actual: &[T], expected: &[T], tolerance: f64) {
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "expected {} samples, found {}",
+        expected.len(),
+        actual.len()
+    );
+    for (k, (a, e)) in actual.iter().zip(expected).enumerate() {
+        let diff = a.max_abs_diff(e);
+        assert!(
+            diff <= tolerance,
+            "sample #{k}: {a:?} differs from {e:?} by {diff} (tolerance: {tolerance})"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        io::{Read, Write},
+        ClientResult, UID,
+    };
+
+    #[derive(UID)]
+    enum In {}
+    #[derive(UID)]
+    enum Out {}
+
+    /// Client summing its inputs, failing once the sum goes over `limit`
+    struct Accumulator {
+        sum: f64,
+        limit: f64,
+    }
+    impl Accumulator {
+        fn new() -> Self {
+            Self {
+                sum: 0.,
+                limit: f64::INFINITY,
+            }
+        }
+    }
+    impl TryUpdate for Accumulator {
+        fn try_update(&mut self) -> ClientResult<()> {
+            if self.sum > self.limit {
+                Err("limit exceeded".into())
+            } else {
+                Ok(())
+            }
+        }
+    }
+    impl Read<In> for Accumulator {
+        fn read(&mut self, data: Arc<Data<In>>) {
+            self.sum += data.iter().sum::<f64>();
+        }
+    }
+    impl Write<Out> for Accumulator {
+        fn write(&mut self) -> Option<Arc<Data<Out>>> {
+            Some(Arc::new(Data::new(vec![self.sum])))
+        }
+    }
+
+    #[test]
+    fn bootstrapped_output() {
+        let mut driver = Driver::new(Accumulator::new())
+            .rates(2, 1)
+            .input::<In>(vec![vec![1.], vec![2.]])
+            .bootstrap::<Out>();
+        assert_eq!(driver.run(usize::MAX).unwrap(), 4);
+        assert_close(
+            driver.outputs::<Out>(),
+            &[vec![0.], vec![0.], vec![1.], vec![1.], vec![3.], vec![3.]],
+            1e-12,
+        );
+    }
+
+    #[test]
+    fn multi_rate() {
+        let mut driver = Driver::new(Accumulator::new())
+            .input_at::<In>(2, vec![vec![1.]; 3])
+            .output_at::<Out>(3);
+        assert_eq!(driver.run(5).unwrap(), 5);
+        assert_eq!(driver.remaining::<In>(), 0);
+        assert_close(driver.outputs::<Out>(), &[vec![2.]], 1e-12);
+        assert_eq!(driver.run(usize::MAX).unwrap(), 1);
+        assert_close(driver.outputs::<Out>(), &[vec![2.], vec![3.]], 1e-12);
+    }
+
+    #[test]
+    fn client_error() {
+        let mut driver = Driver::new(Accumulator { sum: 0., limit: 2. })
+            .input::<In>(vec![vec![1.]; 5])
+            .output::<Out>();
+        assert!(matches!(
+            driver.run(usize::MAX),
+            Err(ActorError::Client { .. })
+        ));
+        assert_eq!(driver.remaining::<In>(), 2);
+        assert_close(driver.outputs::<Out>(), &[vec![1.], vec![2.]], 1e-12);
+    }
+}
@@ -203,21 +203,21 @@ async fn setpoint_mount_m1_asm() -> anyhow::Result<()> {
                 i * 3,
                 Signal::Sigmoid {
                     amplitude: a,
-                    sampling_frequency_hz: sim_sampling_frequency as f64,
+                    sampling_frequency_hz: Some(sim_sampling_frequency as f64),
                 },
             )
             .output_signal(
                 i * 3 + 1,
                 Signal::Sigmoid {
                     amplitude: -a,
-                    sampling_frequency_hz: sim_sampling_frequency as f64,
+                    sampling_frequency_hz: Some(sim_sampling_frequency as f64),
                 },
             )
             .output_signal(
                 i * 3 + 2,
                 Signal::Sigmoid {
                     amplitude: a,
-                    sampling_frequency_hz: sim_sampling_frequency as f64,
+                    sampling_frequency_hz: Some(sim_sampling_frequency as f64),
                 },
             )
         }),